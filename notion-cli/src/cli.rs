@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
+use crate::config::{OutputFormat, TokenStorage};
+
 /// The CLI.
 #[derive(Debug, Parser, Clone)]
 #[command(
@@ -9,6 +13,13 @@ use clap::{Parser, Subcommand};
     about = clap::crate_description!(),
 )]
 pub struct Cli {
+    /// The profile to use. Defaults to the `default_profile` of the config
+    /// file, or `default`.
+    #[clap(long, global = true, env = "NOTION_PROFILE")]
+    pub profile: Option<String>,
+    /// How to print results. Defaults to the output format of the profile.
+    #[clap(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
     /// CLI Commands.
     #[command(subcommand)]
     pub command: Commands,
@@ -17,9 +28,149 @@ pub struct Cli {
 /// Enum of all commands.
 #[derive(Debug, Subcommand, Clone)]
 pub enum Commands {
+    /// Save an API token to the profile, after checking that it is valid.
+    ///
+    /// Reads an internal integration token from stdin, or runs the OAuth flow
+    /// of a public integration with `--oauth`.
+    Login {
+        /// Where to store the token. Defaults to the current storage of the
+        /// profile, or plaintext.
+        #[clap(long, value_enum)]
+        storage: Option<TokenStorage>,
+        /// Database used by commands of this profile when no database ID is
+        /// given.
+        #[clap(long)]
+        default_database: Option<String>,
+        /// Authorize a public integration in the browser.
+        #[clap(long)]
+        oauth: bool,
+        /// The OAuth client ID of the integration.
+        #[clap(long, env = "NOTION_CLIENT_ID", required_if_eq("oauth", "true"))]
+        client_id: Option<String>,
+        /// The OAuth client secret of the integration.
+        #[clap(long, env = "NOTION_CLIENT_SECRET", required_if_eq("oauth", "true"))]
+        client_secret: Option<String>,
+        /// The port to listen on for the OAuth redirect. The redirect URI
+        /// `http://localhost:<port>/callback` must be allowed in the settings
+        /// of the integration.
+        #[clap(long, default_value = "8765")]
+        port: u16,
+    },
+    /// Remove the tokens of the profile.
+    Logout,
+    /// Show the bot user of the token of the profile.
+    Whoami,
     RetrieveBlock {
         /// The ID of the block to retrieve.
         #[clap(long)]
         block_id: String,
     },
+    /// Find out whether an ID is a page, database, block or user, and show
+    /// the object.
+    Resolve {
+        /// The ID, or a link to a page, database or block.
+        id: String,
+    },
+    /// Export every row of a database to CSV.
+    ExportCsv {
+        /// The ID of the database to export. Defaults to the default database
+        /// of the profile.
+        #[clap(long)]
+        database_id: Option<String>,
+        /// The file to write to. Defaults to stdout.
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+    /// Import the rows of a CSV file into a database.
+    ImportCsv {
+        /// The ID of the database to import into. Defaults to the default
+        /// database of the profile.
+        #[clap(long)]
+        database_id: Option<String>,
+        /// The CSV file to read.
+        #[clap(long)]
+        input: PathBuf,
+        /// Update the existing page whose value for this property matches the
+        /// row, instead of creating a new page.
+        #[clap(long)]
+        match_property: Option<String>,
+    },
+    /// Mirror databases, pages and users into a local SQLite database.
+    Sync {
+        /// The SQLite database to write to. It is created if it doesn't exist.
+        #[clap(long)]
+        db: PathBuf,
+        /// The ID of a database whose pages are mirrored. Can be repeated.
+        /// Defaults to the default database of the profile when no page ID is
+        /// given either.
+        #[clap(long)]
+        database_id: Vec<String>,
+        /// The ID of a page that is mirrored along with its content and
+        /// subpages. Can be repeated.
+        #[clap(long)]
+        page_id: Vec<String>,
+        /// Also mirror the content of the pages of the databases.
+        #[clap(long)]
+        content: bool,
+        /// Also mirror the users of the workspace.
+        #[clap(long)]
+        users: bool,
+        /// Retrieve everything again instead of only what changed since the
+        /// last sync.
+        #[clap(long)]
+        full: bool,
+    },
+    /// Work with webhook subscriptions.
+    Webhook {
+        #[command(subcommand)]
+        command: WebhookCommand,
+    },
+}
+
+/// Webhook commands.
+#[derive(Debug, Subcommand, Clone)]
+pub enum WebhookCommand {
+    /// Receive webhook events on localhost and print them, for developing an
+    /// integration. Forward a public URL to the port with a tunnel.
+    ///
+    /// Without a verification token, the token that Notion sends when the
+    /// subscription is created is printed, and used to check the signatures
+    /// of the events after it.
+    Listen {
+        /// The port to listen on.
+        #[clap(long, default_value = "8766")]
+        port: u16,
+        /// The verification token of the subscription, to check signatures
+        /// with.
+        #[clap(long, env = "NOTION_WEBHOOK_TOKEN")]
+        verification_token: Option<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn command() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn export_csv() {
+        let cli = Cli::try_parse_from(["notion", "export-csv", "--output", "f.csv"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::ExportCsv { output: Some(path), .. } if path == std::path::Path::new("f.csv")
+        ));
+
+        let cli = Cli::try_parse_from(["notion", "--format", "json", "export-csv"]).unwrap();
+        assert_eq!(cli.format, Some(OutputFormat::Json));
+        assert!(matches!(
+            cli.command,
+            Commands::ExportCsv { output: None, .. }
+        ));
+    }
 }
@@ -1,21 +1,158 @@
 //! Configuration for the CLI.
+//!
+//! The config file holds named profiles, so that several workspaces or
+//! integrations can be used side by side:
+//!
+//! ```toml
+//! default_profile = "work"
+//!
+//! [profiles.work]
+//! token_storage = "keyring"
+//! default_database = "d9824bdc-8445-4327-be8b-5b47500af6ce"
+//! output = "json"
+//!
+//! [profiles.personal]
+//! token_storage = "plaintext"
+//! api_token = "secret_..."
+//! ```
+//!
+//! A config file from before profiles existed, with a top-level `api_token`,
+//! is read as the `default` profile.
 
 use std::{
+    collections::BTreeMap,
+    fmt,
     fs::{create_dir_all, OpenOptions},
+    io::Write,
     path::PathBuf,
 };
 
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Error;
 
+/// Name of the profile used when none is given.
+pub const DEFAULT_PROFILE: &str = "default";
+
 /// Config values.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Config {
-    /// API token for the Notion API.
-    pub api_token: String,
+    /// Profile used when `--profile` isn't given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// Profiles by name.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// API token of a config file from before profiles existed.
+    #[serde(default, skip_serializing)]
+    api_token: Option<String>,
+    /// Refresh token of a config file from before profiles existed.
+    #[serde(default, skip_serializing)]
+    refresh_token: Option<String>,
+}
+
+/// Config values of a profile.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Profile {
+    /// Where the tokens of the profile are stored.
+    #[serde(default)]
+    pub token_storage: TokenStorage,
+    /// API token for the Notion API, when stored in plaintext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+    /// Refresh token, when logged in with OAuth and stored in plaintext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// The tokens, when stored in an encrypted form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_credentials: Option<String>,
+    /// Database used by commands when no database ID is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_database: Option<String>,
+    /// Output format used when `--format` isn't given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+}
+
+/// Where tokens are stored.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStorage {
+    /// In the config file, as is.
+    #[default]
+    Plaintext,
+    /// In the config file, encrypted with a passphrase read from
+    /// `NOTION_PASSPHRASE` or prompted for.
+    Encrypted,
+    /// In the secret store of the operating system.
+    Keyring,
+}
+
+/// How command results are printed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Rust debug representation.
+    #[default]
+    Debug,
+    /// Pretty-printed JSON.
+    Json,
+}
+
+/// Shows whether a token is set, without the token itself.
+const fn redacted(token: &Option<String>) -> Option<&'static str> {
+    match token {
+        Some(_) => Some("[redacted]"),
+        None => None,
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("default_profile", &self.default_profile)
+            .field("profiles", &self.profiles)
+            .field("api_token", &redacted(&self.api_token))
+            .field("refresh_token", &redacted(&self.refresh_token))
+            .finish()
+    }
+}
+
+impl fmt::Debug for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profile")
+            .field("token_storage", &self.token_storage)
+            .field("api_token", &redacted(&self.api_token))
+            .field("refresh_token", &redacted(&self.refresh_token))
+            .field(
+                "encrypted_credentials",
+                &redacted(&self.encrypted_credentials),
+            )
+            .field("default_database", &self.default_database)
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+impl Config {
+    /// The name of the profile to use, from `--profile` or `NOTION_PROFILE` if
+    /// given.
+    pub fn profile_name(&self, profile: Option<&str>) -> String {
+        profile
+            .or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+            .to_owned()
+    }
+
+    /// Get a profile by name.
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .with_context(|| Error::UnknownProfile(name.to_owned()))
+    }
 }
 
 /// Get the config file path from the config directory.
@@ -45,12 +182,116 @@ pub fn get_config_path() -> Result<PathBuf> {
 pub fn load_config() -> Result<Config> {
     let config_file = get_config_path()?;
 
-    let config = config::Config::builder()
-        .add_source(config::File::from(config_file.clone()))
-        .add_source(config::Environment::with_prefix("NOTION"))
-        .build()?
-        .try_deserialize::<Config>()
-        .context(Error::IncompleteConfig(config_file.display().to_string()))?;
+    let text = std::fs::read_to_string(&config_file).context(Error::Config)?;
+
+    parse_config(&text).context(Error::IncompleteConfig(config_file.display().to_string()))
+}
+
+fn parse_config(text: &str) -> Result<Config> {
+    let mut config = toml::from_str::<Config>(text)?;
+
+    if let Some(api_token) = config.api_token.take() {
+        config
+            .profiles
+            .entry(DEFAULT_PROFILE.to_owned())
+            .or_insert_with(|| Profile {
+                api_token: Some(api_token),
+                refresh_token: config.refresh_token.take(),
+                ..Default::default()
+            });
+    }
 
     Ok(config)
 }
+
+/// Write config to the config file, readable only by the current user
+pub fn save_config(config: &Config) -> Result<()> {
+    let config_file = get_config_path()?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(&config_file).context(Error::SaveConfig)?;
+
+    #[cfg(unix)]
+    std::fs::set_permissions(
+        &config_file,
+        std::os::unix::fs::PermissionsExt::from_mode(0o600),
+    )
+    .context(Error::SaveConfig)?;
+
+    file.write_all(toml::to_string(config)?.as_bytes())
+        .context(Error::SaveConfig)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let config = parse_config("").unwrap();
+
+        assert_eq!(config.profile_name(None), DEFAULT_PROFILE);
+        assert!(config.profile(DEFAULT_PROFILE).is_err());
+    }
+
+    #[test]
+    fn legacy() {
+        let config = parse_config(r#"api_token = "secret_abc""#).unwrap();
+        let profile = config.profile(DEFAULT_PROFILE).unwrap();
+
+        assert_eq!(profile.api_token.as_deref(), Some("secret_abc"));
+        assert_eq!(profile.token_storage, TokenStorage::Plaintext);
+        assert_eq!(
+            toml::to_string(&config).unwrap(),
+            "[profiles.default]\ntoken_storage = \"plaintext\"\napi_token = \"secret_abc\"\n"
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let config = parse_config(
+            r#"
+            default_profile = "work"
+
+            [profiles.work]
+            token_storage = "keyring"
+            default_database = "d9824bdc-8445-4327-be8b-5b47500af6ce"
+            output = "json"
+
+            [profiles.personal]
+            api_token = "secret_abc"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.profile_name(None), "work");
+        assert_eq!(config.profile_name(Some("personal")), "personal");
+
+        let work = config.profile("work").unwrap();
+        assert_eq!(work.token_storage, TokenStorage::Keyring);
+        assert_eq!(work.output, Some(OutputFormat::Json));
+    }
+
+    #[test]
+    fn debug_redacts_tokens() {
+        let config = parse_config(
+            r#"
+            [profiles.personal]
+            api_token = "secret_abc"
+            refresh_token = "secret_def"
+            "#,
+        )
+        .unwrap();
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("secret_"));
+        assert!(debug.contains("[redacted]"));
+    }
+}
@@ -14,4 +14,38 @@ pub enum Error {
     Config,
     #[error("One or more config field is missing in the config file: {0}")]
     IncompleteConfig(String),
+    #[error("Can't write the config file")]
+    SaveConfig,
+    #[error("Can't listen for the OAuth redirect on port {0}")]
+    OAuthListen(u16),
+    #[error("The OAuth redirect has an invalid state")]
+    OAuthState,
+    #[error("The OAuth redirect request is larger than {0} bytes")]
+    OAuthRedirectTooLarge(usize),
+    #[error("Authorization failed: {0}")]
+    OAuthDenied(String),
+    #[error("No profile named {0}, log in with `--profile {0}` to create it")]
+    UnknownProfile(String),
+    #[error("Not logged in with profile {0}")]
+    NotLoggedIn(String),
+    #[error("Can't read the passphrase")]
+    Passphrase,
+    #[error("Can't encrypt the tokens")]
+    Encrypt,
+    #[error("Can't decrypt the tokens, the passphrase may be wrong")]
+    Decrypt,
+    #[error("Can't access the keyring")]
+    Keyring,
+    #[error("Keyring storage needs the `keyring` feature")]
+    KeyringUnsupported,
+    #[error("The API token is invalid")]
+    InvalidToken,
+    #[error("No database ID given, and the profile has no default database")]
+    MissingDatabaseId,
+    #[error("{0} is neither an ID nor a link to a page, database or block")]
+    InvalidId(String),
+    #[error("Can't listen for webhooks on port {0}")]
+    WebhookListen(u16),
+    #[error("The webhook request is larger than {0} bytes")]
+    WebhookTooLarge(usize),
 }
@@ -0,0 +1,144 @@
+//! Logging in with the OAuth flow of a public integration.
+
+use anyhow::{Context, Result};
+use notion::{authentication::OAuthClient, model::oauth::Token};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use url::Url;
+use uuid::Uuid;
+
+use crate::error::Error;
+
+const CALLBACK_PATH: &str = "/callback";
+
+/// The largest request that is read. The redirect only has a request line and
+/// headers.
+const MAX_REQUEST: usize = 64 * 1024;
+
+/// Query parameters Notion sends to the redirect URI.
+enum Callback {
+    Code { code: String, state: String },
+    Error(String),
+}
+
+/// Runs the OAuth flow, listening on `localhost:port` for the redirect.
+///
+/// `http://localhost:{port}/callback` must be one of the redirect URIs of the
+/// integration.
+pub async fn oauth_login(client_id: &str, client_secret: &str, port: u16) -> Result<Token> {
+    let redirect_uri = format!("http://localhost:{port}{CALLBACK_PATH}");
+    let client = OAuthClient::new(client_id, client_secret)?.redirect_uri(&redirect_uri);
+
+    let state = Uuid::new_v4().to_string();
+    let url = client.authorization_url(Some(&state))?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .context(Error::OAuthListen(port))?;
+
+    eprintln!("Open this URL in your browser to authorize the integration:\n\n{url}\n");
+
+    let code = loop {
+        let (mut stream, _) = listener.accept().await?;
+
+        // a bad request is skipped rather than stopping the login
+        let callback = match read_callback(&mut stream).await {
+            Ok(callback) => callback,
+            Err(e) => {
+                eprintln!("Could not read a request: {e:#}");
+                continue;
+            },
+        };
+
+        match callback {
+            Some(Callback::Code {
+                code,
+                state: received,
+            }) if received == state => {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Logged in, you can close this window.",
+                )
+                .await;
+                break code;
+            },
+            Some(Callback::Code { .. }) => {
+                respond(&mut stream, "400 Bad Request", "Invalid state.").await;
+                anyhow::bail!(Error::OAuthState);
+            },
+            Some(Callback::Error(error)) => {
+                respond(&mut stream, "400 Bad Request", "Authorization failed.").await;
+                anyhow::bail!(Error::OAuthDenied(error));
+            },
+            // browsers also request things like the favicon
+            None => respond(&mut stream, "404 Not Found", "Not found.").await,
+        }
+    };
+
+    client.exchange_code(&code).await
+}
+
+/// Reads the request line of an HTTP request, returning the parameters of the
+/// redirect if it is a request to the callback. Fails on requests larger than
+/// [`MAX_REQUEST`] bytes.
+async fn read_callback(stream: &mut TcpStream) -> Result<Option<Callback>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+
+    while !buf.windows(4).any(|window| window == b"\r\n\r\n") {
+        if buf.len() > MAX_REQUEST {
+            anyhow::bail!(Error::OAuthRedirectTooLarge(MAX_REQUEST));
+        }
+
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+
+    let Some(target) = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+    else {
+        return Ok(None);
+    };
+
+    let url = Url::parse(&format!("http://localhost{target}"))?;
+
+    if url.path() != CALLBACK_PATH {
+        return Ok(None);
+    }
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    Ok(match (param("code"), param("state"), param("error")) {
+        (_, _, Some(error)) => Some(Callback::Error(error)),
+        (Some(code), Some(state), None) => Some(Callback::Code { code, state }),
+        _ => None,
+    })
+}
+
+/// Writes a plain text response. The browser only shows it, so failing to
+/// write it doesn't stop the login.
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: \
+         {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        eprintln!("Could not respond to a request: {e:#}");
+    }
+}
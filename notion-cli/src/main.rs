@@ -13,41 +13,42 @@
 pub mod cli;
 pub mod config;
 pub mod error;
+pub mod login;
+pub mod secrets;
+pub mod webhook;
 
-#[allow(unused)]
-use std::collections::HashMap;
-#[allow(unused)]
-use std::str::FromStr;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, Write},
+    str::FromStr,
+};
 
-#[allow(unused)]
 use anyhow::{Context, Result};
-use notion::model::objects::{
-    date::DateOrDateTime,
-    properties::{Select, SelectOption, StatusOption},
-    rich_text::DateMention,
-};
-#[allow(unused)]
-use notion::model::objects::{page::Page, parent::ParentData, properties};
-#[allow(unused)]
+use clap::Parser;
 use notion::{
     client::Notion,
+    csv::ImportOptions,
     model::{
-        ids::*,
-        objects::{block::*, rich_text::RichText},
+        ids::{BlockId, DatabaseId, PageId},
+        objects::user::{User, UserData},
     },
-    utils,
+    sync::{Mirror, SyncOptions},
+    url::NotionUrl,
 };
+use serde::Serialize;
+use uuid::Uuid;
 
-use crate::config::{load_config, Config};
-
-#[allow(unused)]
-mod ids {
-    pub const PARAGRAPH: &str = "d3d710f97c874e6c8e4d9b2576a6fb29";
-    pub const PAGE: &str = "67ace61a7fd24ab78e892b1dc9b252e4";
-    pub const USER: &str = "3e1fc0f5d02e48ae84c07ae06deece9f";
-    pub const DATABASE: &str = "fe45735bf4dc420695eb1be8d96b2184";
-    pub const DATABASE_PAGE: &str = "228a649d2e844f7fa57535a9f2c4debf";
-}
+use crate::{
+    cli::{Cli, Commands, WebhookCommand},
+    config::{load_config, save_config, OutputFormat, Profile},
+    error::Error,
+    login::oauth_login,
+    secrets::{
+        delete_credentials, env_credentials, load_credentials, store_credentials, Credentials,
+    },
+    webhook::listen,
+};
 
 /// TODO: write tests for all block struct buixlders
 /// TODO: test all builders and make sure they work, from the app and API
@@ -55,55 +56,276 @@ mod ids {
 /// TODO: use &[] instead of vec![] everywhere
 #[tokio::main]
 pub async fn main() -> Result<()> {
-    let Config { api_token } = load_config()?;
-    let notion = Notion::new(&api_token).context("Failed to create api client")?;
-
-    // let res = notion
-    //     .create_page(
-    //         Page::new()
-    //             .parent(Some(ParentData::from(DatabaseId::from_str(
-    //                 "45089d19e5b84b82b1dd7cc9f93559b4",
-    //             )?)))
-    //             .properties(HashMap::from([
-    //                 properties::Title::new("hello
-    // world2").build_with_name("Name"),
-    // properties::Select::new(SelectOption {                     name:
-    // Some("Homework".to_string()),                     color: None,
-    //                     id: None,
-    //                 })
-    //                 .build_with_name("Type"),
-    //                 properties::Select::new(SelectOption {
-    //                     name: Some("Spanish".to_string()),
-    //                     color: None,
-    //                     id: None,
-    //                 })
-    //                 .build_with_name("Subject"),
-    //                 properties::Status::new(StatusOption {
-    //                     name: Some("To Review".to_string()),
-    //                     color: None,
-    //                     id: None,
-    //                 })
-    //                 .build_with_name("Status"),
-    //             ])),
-    //     )
-    //     .await?;
-
-    let res = notion
-        .retrieve_page(PageId::from_str(ids::DATABASE_PAGE)?, None)
-        .await?;
-
-    // let res = notion
-    //     .retrieve_database(DatabaseId::from_str("
-    // 45089d19e5b84b82b1dd7cc9f93559b4")?)     .await?;
-
-    // let res = notion
-    // .retrieve_page(PageId::from_str("67ace61a7fd24ab78e892b1dc9b252e4")?, None)
-    // .await?;
-
-    println!("{:#?}", res);
-
-    // let value = Bookmar::new().build();
-    // println!("{}", serde_json::to_string(&value).unwrap());
+    let cli = Cli::parse();
+
+    let mut config = load_config()?;
+    let profile_name = config.profile_name(cli.profile.as_deref());
+
+    match cli.command {
+        Commands::Login {
+            storage,
+            default_database,
+            oauth,
+            client_id,
+            client_secret,
+            port,
+        } => {
+            let credentials = match (oauth, client_id, client_secret) {
+                (true, Some(client_id), Some(client_secret)) => {
+                    let token = oauth_login(&client_id, &client_secret, port).await?;
+
+                    Credentials {
+                        api_token: token.access_token,
+                        refresh_token: token.refresh_token,
+                    }
+                },
+                _ => {
+                    let api_token = rpassword::prompt_password("API token: ")?;
+
+                    Credentials {
+                        api_token: api_token.trim().to_owned(),
+                        refresh_token: None,
+                    }
+                },
+            };
+
+            let user = Notion::new(&credentials.api_token)?
+                .retrieve_bot_user()
+                .await
+                .context(Error::InvalidToken)?;
+
+            let profile = config.profiles.entry(profile_name.clone()).or_default();
+
+            // tokens in the previous storage are removed before switching
+            if let Some(storage) = storage {
+                delete_credentials(&profile_name, profile)?;
+                profile.token_storage = storage;
+            }
+
+            if default_database.is_some() {
+                profile.default_database = default_database;
+            }
+
+            store_credentials(&profile_name, profile, &credentials)?;
+            save_config(&config)?;
+
+            eprintln!(
+                "Logged in as {} with profile {profile_name}",
+                describe(&user)
+            );
+        },
+        Commands::Logout => {
+            let profile = config
+                .profiles
+                .get_mut(&profile_name)
+                .with_context(|| Error::UnknownProfile(profile_name.clone()))?;
+
+            delete_credentials(&profile_name, profile)?;
+            save_config(&config)?;
+
+            eprintln!("Logged out of profile {profile_name}");
+        },
+        Commands::Webhook {
+            command:
+                WebhookCommand::Listen {
+                    port,
+                    verification_token,
+                },
+        } => {
+            let output = cli
+                .format
+                .or_else(|| config.profiles.get(&profile_name)?.output)
+                .unwrap_or_default();
+
+            listen(port, verification_token.as_deref(), output).await?;
+        },
+        command => {
+            // a profile is only needed for its tokens when none are given
+            let credentials = match env_credentials() {
+                Some(credentials) => credentials,
+                None => load_credentials(&profile_name, config.profile(&profile_name)?)?,
+            };
+            let profile = config
+                .profiles
+                .get(&profile_name)
+                .cloned()
+                .unwrap_or_default();
+            let notion =
+                Notion::new(&credentials.api_token).context("Failed to create api client")?;
+            let output = cli.format.or(profile.output).unwrap_or_default();
+
+            run(command, &notion, &profile, output).await?;
+        },
+    }
+
+    Ok(())
+}
+
+/// A user's name, and for bots, the workspace they belong to.
+fn describe(user: &User) -> String {
+    let name = user.name.as_deref().unwrap_or("an unnamed user");
+
+    match &user.data {
+        Some(UserData::Bot(bot)) => bot.workspace_name.as_ref().map_or_else(
+            || name.to_owned(),
+            |workspace| format!("{name} in {workspace}"),
+        ),
+        _ => name.to_owned(),
+    }
+}
+
+fn print<T: Debug + Serialize>(output: OutputFormat, value: &T) -> Result<()> {
+    match output {
+        OutputFormat::Debug => println!("{value:#?}"),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+
+    Ok(())
+}
+
+/// The database ID given to a command, or the default database of the profile.
+fn database_id_or_default(database_id: Option<String>, profile: &Profile) -> Result<DatabaseId> {
+    let database_id = database_id
+        .or_else(|| profile.default_database.clone())
+        .context(Error::MissingDatabaseId)?;
+
+    Ok(DatabaseId::from_str(&database_id)?)
+}
+
+/// The ID in an argument that is either an ID or a Notion URL. A URL to a block
+/// gives the ID of the block, not of its page.
+fn id_from_arg(arg: &str) -> Result<Uuid> {
+    if let Ok(id) = Uuid::parse_str(arg) {
+        return Ok(id);
+    }
+
+    let url = NotionUrl::from_str(arg).with_context(|| Error::InvalidId(arg.to_owned()))?;
+
+    match url {
+        NotionUrl::Block { block_id, .. } => Ok(*block_id.into_inner()),
+        url => url
+            .object_id()
+            .with_context(|| Error::InvalidId(arg.to_owned())),
+    }
+}
+
+/// Runs a command that uses the API token of the profile.
+async fn run(
+    command: Commands,
+    notion: &Notion,
+    profile: &Profile,
+    output: OutputFormat,
+) -> Result<()> {
+    match command {
+        Commands::Login { .. } | Commands::Logout | Commands::Webhook { .. } => {
+            unreachable!("handled without a token")
+        },
+        Commands::Whoami => {
+            let user = notion
+                .retrieve_bot_user()
+                .await
+                .context(Error::InvalidToken)?;
+
+            eprintln!("Logged in as {}", describe(&user));
+
+            print(output, &user)?;
+        },
+        Commands::RetrieveBlock { block_id } => {
+            let res = notion.retrieve_block(BlockId::from_str(&block_id)?).await?;
+
+            print(output, &res)?;
+        },
+        Commands::Resolve { id } => {
+            let object = notion.resolve(id_from_arg(&id)?).await?;
+
+            eprintln!("{id} is a {}", object.kind());
+
+            print(output, &object)?;
+        },
+        Commands::ExportCsv {
+            database_id,
+            output,
+        } => {
+            let database_id = database_id_or_default(database_id, profile)?;
+
+            let writer: Box<dyn Write + Send> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout()),
+            };
+
+            let rows = notion.export_database_csv(database_id, writer).await?;
+
+            eprintln!("Exported {rows} rows");
+        },
+        Commands::ImportCsv {
+            database_id,
+            input,
+            match_property,
+        } => {
+            let database_id = database_id_or_default(database_id, profile)?;
+            let reader = File::open(input)?;
+            let options = ImportOptions::new().match_property(match_property.as_deref());
+
+            let report = notion
+                .import_database_csv(database_id, reader, options)
+                .await?;
+
+            for column in &report.skipped_columns {
+                eprintln!("Skipped column {column}");
+            }
+
+            for error in &report.errors {
+                eprintln!("Row {}: {:#}", error.row, error.error);
+            }
+
+            eprintln!(
+                "Created {} pages, updated {} pages, {} rows failed",
+                report.created.len(),
+                report.updated.len(),
+                report.errors.len()
+            );
+        },
+        Commands::Sync {
+            db,
+            database_id,
+            page_id,
+            content,
+            users,
+            full,
+        } => {
+            let mut mirror = Mirror::open(db)?;
+
+            let databases = match (database_id.is_empty(), page_id.is_empty()) {
+                (true, true) => vec![database_id_or_default(None, profile)?],
+                _ => database_id
+                    .iter()
+                    .map(|id| DatabaseId::from_str(id))
+                    .collect::<Result<_, _>>()?,
+            };
+
+            let options = SyncOptions::new()
+                .databases(databases)
+                .pages(
+                    page_id
+                        .iter()
+                        .map(|id| PageId::from_str(id))
+                        .collect::<Result<_, _>>()?,
+                )
+                .content(content)
+                .users(users)
+                .full(full);
+
+            let report = notion.sync(&mut mirror, &options).await?;
+
+            eprintln!(
+                "Updated {} pages, {} blocks and {} users, archived {} pages",
+                report.pages_updated,
+                report.blocks_updated,
+                report.users_updated,
+                report.pages_archived
+            );
+        },
+    }
 
     Ok(())
 }
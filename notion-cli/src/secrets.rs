@@ -0,0 +1,248 @@
+//! Storage of the tokens of a profile.
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Profile, TokenStorage},
+    error::Error,
+};
+
+/// Environment variable that overrides the API token of the profile.
+pub const API_TOKEN_VAR: &str = "NOTION_API_TOKEN";
+
+/// Environment variable holding the passphrase of encrypted tokens.
+pub const PASSPHRASE_VAR: &str = "NOTION_PASSPHRASE";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The tokens of a profile.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// API token for the Notion API.
+    pub api_token: String,
+    /// Refresh token, when logged in with OAuth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_token", &"[redacted]")
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[redacted]"),
+            )
+            .finish()
+    }
+}
+
+/// Stores the tokens of a profile, removing any tokens stored elsewhere.
+pub fn store_credentials(
+    name: &str,
+    profile: &mut Profile,
+    credentials: &Credentials,
+) -> Result<()> {
+    delete_credentials(name, profile)?;
+
+    match profile.token_storage {
+        TokenStorage::Plaintext => {
+            profile.api_token = Some(credentials.api_token.clone());
+            profile.refresh_token = credentials.refresh_token.clone();
+        },
+        TokenStorage::Encrypted => {
+            let plaintext = serde_json::to_vec(credentials)?;
+            profile.encrypted_credentials = Some(encrypt(&plaintext, &passphrase()?)?);
+        },
+        TokenStorage::Keyring => keyring_set(name, &serde_json::to_string(credentials)?)?,
+    }
+
+    Ok(())
+}
+
+/// The API token given by `NOTION_API_TOKEN`, which takes precedence over
+/// the tokens of any profile.
+pub fn env_credentials() -> Option<Credentials> {
+    std::env::var(API_TOKEN_VAR)
+        .ok()
+        .map(|api_token| Credentials {
+            api_token,
+            refresh_token: None,
+        })
+}
+
+/// Loads the tokens stored for a profile.
+pub fn load_credentials(name: &str, profile: &Profile) -> Result<Credentials> {
+    let not_logged_in = || Error::NotLoggedIn(name.to_owned());
+
+    match profile.token_storage {
+        TokenStorage::Plaintext => Ok(Credentials {
+            api_token: profile.api_token.clone().with_context(not_logged_in)?,
+            refresh_token: profile.refresh_token.clone(),
+        }),
+        TokenStorage::Encrypted => {
+            let encrypted = profile
+                .encrypted_credentials
+                .as_deref()
+                .with_context(not_logged_in)?;
+            let plaintext = decrypt(encrypted, &passphrase()?)?;
+
+            Ok(serde_json::from_slice(&plaintext)?)
+        },
+        TokenStorage::Keyring => {
+            let secret = keyring_get(name)?.with_context(not_logged_in)?;
+
+            Ok(serde_json::from_str(&secret)?)
+        },
+    }
+}
+
+/// Removes the tokens of a profile from every storage.
+pub fn delete_credentials(name: &str, profile: &mut Profile) -> Result<()> {
+    profile.api_token = None;
+    profile.refresh_token = None;
+    profile.encrypted_credentials = None;
+
+    if profile.token_storage == TokenStorage::Keyring {
+        keyring_delete(name)?;
+    }
+
+    Ok(())
+}
+
+fn passphrase() -> Result<String> {
+    std::env::var(PASSPHRASE_VAR)
+        .or_else(|_| rpassword::prompt_password("Passphrase: "))
+        .context(Error::Passphrase)
+}
+
+fn key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+        .context(Error::Encrypt)?;
+
+    Ok(key)
+}
+
+/// Encrypts with a key derived from the passphrase, returning the salt, nonce
+/// and ciphertext encoded in base64.
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<String> {
+    let mut salt = [0; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = ChaCha20Poly1305::new(&key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+        .context(Error::Encrypt)?;
+
+    Ok(STANDARD.encode([&salt[..], &nonce, &ciphertext].concat()))
+}
+
+fn decrypt(encrypted: &str, passphrase: &str) -> Result<Vec<u8>> {
+    let bytes = STANDARD.decode(encrypted).context(Error::Decrypt)?;
+
+    if bytes.len() < SALT_LEN + NONCE_LEN {
+        anyhow::bail!(Error::Decrypt);
+    }
+
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = ChaCha20Poly1305::new(&key(passphrase, salt)?);
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+        .context(Error::Decrypt)
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_entry(name: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(clap::crate_name!(), name).context(Error::Keyring)
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_set(name: &str, secret: &str) -> Result<()> {
+    keyring_entry(name)?
+        .set_password(secret)
+        .context(Error::Keyring)
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_get(name: &str) -> Result<Option<String>> {
+    match keyring_entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context(Error::Keyring),
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_delete(name: &str) -> Result<()> {
+    match keyring_entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context(Error::Keyring),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_set(_name: &str, _secret: &str) -> Result<()> {
+    anyhow::bail!(Error::KeyringUnsupported)
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_get(_name: &str) -> Result<Option<String>> {
+    anyhow::bail!(Error::KeyringUnsupported)
+}
+
+#[cfg(not(feature = "keyring"))]
+const fn keyring_delete(_name: &str) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let encrypted = encrypt(b"secret_abc", "hunter2").unwrap();
+
+        assert_eq!(decrypt(&encrypted, "hunter2").unwrap(), b"secret_abc");
+        assert!(decrypt(&encrypted, "hunter3").is_err());
+    }
+
+    #[test]
+    fn encryption_is_salted() {
+        assert_ne!(
+            encrypt(b"secret_abc", "hunter2").unwrap(),
+            encrypt(b"secret_abc", "hunter2").unwrap()
+        );
+    }
+
+    #[test]
+    fn debug_redacts_tokens() {
+        let credentials = Credentials {
+            api_token: "secret_abc".to_owned(),
+            refresh_token: Some("secret_def".to_owned()),
+        };
+
+        assert!(!format!("{credentials:?}").contains("secret_"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Receiving webhook events locally, while developing an integration.
+
+use anyhow::{Context, Result};
+use notion::{model::webhooks::Delivery, webhooks::WebhookHandler};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{config::OutputFormat, error::Error, print};
+
+/// The largest request that is read, including its headers. Events are far
+/// smaller.
+const MAX_REQUEST: usize = 1024 * 1024;
+
+/// Listens on `localhost:port` for webhook requests, printing each event.
+///
+/// Without a verification token, the token of the first verification request
+/// is printed and used to check the events after it.
+pub async fn listen(
+    port: u16,
+    verification_token: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    // the token is only taken from a verification request when none was
+    // given, as the request isn't signed
+    let handler = WebhookHandler::new()
+        .verification_token(verification_token)
+        .capture_verification(verification_token.is_none());
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .context(Error::WebhookListen(port))?;
+
+    eprintln!("Listening for webhooks on http://localhost:{port}");
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+
+        // a bad request is skipped rather than stopping the listener
+        let request = match read_request(&mut stream).await {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Could not read a request: {e:#}");
+                continue;
+            },
+        };
+
+        let (response, delivery) = handler.handle(&request);
+
+        if let Err(e) = respond(&mut stream, &response).await {
+            eprintln!("Could not respond to a request: {e:#}");
+        }
+
+        match delivery {
+            Ok(Delivery::Verification { verification_token }) => {
+                eprintln!(
+                    "Verification token: {verification_token}\nPaste it in the settings of the \
+                     integration to verify the subscription."
+                );
+            },
+            Ok(Delivery::Event(event)) => {
+                eprintln!(
+                    "{} on {:?} {} (attempt {})",
+                    event.data.type_name(),
+                    event.entity.kind,
+                    event.entity.id,
+                    event.attempt_number
+                );
+
+                print(output, &event)?;
+            },
+            Err(e) => eprintln!("Rejected a request: {e:#}"),
+        }
+    }
+}
+
+/// Reads an HTTP request, with a body of its `Content-Length`, up to
+/// [`MAX_REQUEST`] bytes.
+async fn read_request(stream: &mut TcpStream) -> Result<Option<http::Request<Vec<u8>>>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 4096];
+
+    let head_len = loop {
+        if let Some(i) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break i + 4;
+        }
+
+        if buf.len() > MAX_REQUEST {
+            anyhow::bail!(Error::WebhookTooLarge(MAX_REQUEST));
+        }
+
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_len]).into_owned();
+    let mut lines = head.lines();
+
+    let Some((method, target)) = lines.next().and_then(|line| {
+        let mut parts = line.split_whitespace();
+        Some((parts.next()?, parts.next()?))
+    }) else {
+        return Ok(None);
+    };
+
+    let mut request = http::Request::builder().method(method).uri(target);
+    let mut content_length = 0;
+
+    for (name, value) in lines.filter_map(|line| line.split_once(':')) {
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse()?;
+
+            if head_len.saturating_add(content_length) > MAX_REQUEST {
+                anyhow::bail!(Error::WebhookTooLarge(MAX_REQUEST));
+            }
+        }
+
+        request = request.header(name.trim(), value.trim());
+    }
+
+    let mut body = buf.split_off(head_len);
+
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    body.truncate(content_length);
+
+    Ok(Some(request.body(body)?))
+}
+
+async fn respond(stream: &mut TcpStream, response: &http::Response<String>) -> Result<()> {
+    let body = response.body();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: \
+         {}\r\nConnection: close\r\n\r\n{body}",
+        response.status(),
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
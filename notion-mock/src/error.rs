@@ -0,0 +1,134 @@
+//! Error responses, matching the error codes of the Notion API.
+//!
+//! See <https://developers.notion.com/reference/status-codes>.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// An error response of the API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_json() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_json",
+            "Error parsing JSON body.",
+        )
+    }
+
+    pub fn invalid_request_url() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_url",
+            "Invalid request URL.",
+        )
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "validation_error", message)
+    }
+
+    pub fn archived() -> Self {
+        Self::validation(
+            "Can't edit block that is archived. You must unarchive the block before editing.",
+        )
+    }
+
+    pub fn missing_version() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "missing_version",
+            "Notion-Version header failed validation: Notion-Version header should be defined, \
+             instead was `undefined`.",
+        )
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+            "API token is invalid.",
+        )
+    }
+
+    pub fn not_found(kind: &str, id: impl std::fmt::Display) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "object_not_found",
+            format!(
+                "Could not find {kind} with ID: {id}. Make sure the relevant pages and databases \
+                 are shared with your integration."
+            ),
+        )
+    }
+
+    pub fn conflict() -> Self {
+        Self::new(
+            StatusCode::CONFLICT,
+            "conflict_error",
+            "Conflict occurred while saving. Please try again.",
+        )
+    }
+
+    pub fn rate_limited() -> Self {
+        Self::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limited",
+            "You have been rate limited. Please try again in a few minutes.",
+        )
+    }
+
+    pub fn internal_server_error() -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_server_error",
+            "Unexpected error occurred.",
+        )
+    }
+
+    pub fn service_unavailable() -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "service_unavailable",
+            "Notion is unavailable, please try again later.",
+        )
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(json!({
+            "object": "error",
+            "status": self.status.as_u16(),
+            "code": self.code,
+            "message": self.message,
+            "request_id": Uuid::new_v4().to_string(),
+        }));
+
+        match self.status {
+            StatusCode::TOO_MANY_REQUESTS => {
+                (self.status, [(header::RETRY_AFTER, "1")], body).into_response()
+            },
+            status => (status, body).into_response(),
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Database query filters and sorts.
+//!
+//! Only part of the filter language is supported: compound filters, timestamp
+//! filters, and property filters on text, number, checkbox, select and status
+//! properties. Other filters are rejected with a validation error rather than
+//! ignored, so that a test can't pass because a filter had no effect.
+//!
+//! See <https://developers.notion.com/reference/post-database-query-filter>.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, FixedOffset, NaiveTime};
+use notion_model::{
+    objects::{date::DateOrDateTime, page::Page},
+    query::{Sort, SortDirection, SortTimestamp},
+};
+use serde_json::Value;
+
+use crate::{error::ApiError, store::ApiResult};
+
+/// Whether a page matches a filter.
+pub fn matches(filter: &Value, page: &Page) -> ApiResult<bool> {
+    let filter = filter.as_object().ok_or_else(|| invalid("filter"))?;
+
+    if let Some(filters) = filter.get("and") {
+        return compound(filters, page, "and")?
+            .into_iter()
+            .try_fold(true, |all, matches| Ok(all && matches));
+    }
+
+    if let Some(filters) = filter.get("or") {
+        return compound(filters, page, "or")?
+            .into_iter()
+            .try_fold(false, |any, matches| Ok(any || matches));
+    }
+
+    if let Some(timestamp) = filter.get("timestamp") {
+        let value = match timestamp.as_str() {
+            Some("created_time") => page.created_time,
+            Some("last_edited_time") => page.last_edited_time,
+            _ => return Err(invalid("timestamp")),
+        };
+
+        let name = timestamp.as_str().unwrap_or_default();
+        let condition = filter.get(name).ok_or_else(|| invalid(name))?;
+
+        return date(value.as_ref().map(timestamp_of), condition);
+    }
+
+    let Some(property) = filter.get("property").and_then(Value::as_str) else {
+        return Err(invalid("filter"));
+    };
+
+    let (kind, condition) = filter
+        .iter()
+        .find(|(key, _)| *key != "property")
+        .ok_or_else(|| invalid("filter"))?;
+
+    let value = page
+        .properties
+        .get(property)
+        .and_then(|property| serde_json::to_value(&property.data).ok())
+        .unwrap_or_default();
+
+    if let Some(actual) = value.as_object().and_then(|value| value.keys().next()) {
+        if actual != kind {
+            return Err(ApiError::validation(format!(
+                "database property {actual} does not match filter {kind}"
+            )));
+        }
+    }
+
+    let value = value.get(kind).unwrap_or(&Value::Null);
+
+    match kind.as_str() {
+        "title" | "rich_text" | "url" | "email" | "phone_number" => {
+            text(&plain_text(value), condition)
+        },
+        "number" => number(value.as_f64(), condition),
+        "checkbox" => checkbox(value.as_bool().unwrap_or(false), condition),
+        "select" | "status" => select(value.get("name").and_then(Value::as_str), condition),
+        _ => Err(unsupported(kind)),
+    }
+}
+
+fn compound(filters: &Value, page: &Page, name: &str) -> ApiResult<Vec<bool>> {
+    filters
+        .as_array()
+        .ok_or_else(|| invalid(name))?
+        .iter()
+        .map(|filter| matches(filter, page))
+        .collect()
+}
+
+/// The single condition of a type-specific filter, such as `equals`.
+fn condition<'a>(condition: &'a Value, kind: &str) -> ApiResult<(&'a str, &'a Value)> {
+    condition
+        .as_object()
+        .and_then(|condition| condition.iter().next())
+        .map(|(name, value)| (name.as_str(), value))
+        .ok_or_else(|| invalid(kind))
+}
+
+fn text(text: &str, filter: &Value) -> ApiResult<bool> {
+    let (name, value) = condition(filter, "rich_text")?;
+    let expected = || value.as_str().ok_or_else(|| invalid(name));
+
+    Ok(match name {
+        "equals" => text == expected()?,
+        "does_not_equal" => text != expected()?,
+        "contains" => text.contains(expected()?),
+        "does_not_contain" => !text.contains(expected()?),
+        "starts_with" => text.starts_with(expected()?),
+        "ends_with" => text.ends_with(expected()?),
+        "is_empty" => text.is_empty(),
+        "is_not_empty" => !text.is_empty(),
+        _ => return Err(unsupported(name)),
+    })
+}
+
+fn number(number: Option<f64>, filter: &Value) -> ApiResult<bool> {
+    let (name, value) = condition(filter, "number")?;
+
+    if name == "is_empty" || name == "is_not_empty" {
+        return Ok(number.is_none() == (name == "is_empty"));
+    }
+
+    let (Some(number), Some(expected)) = (number, value.as_f64()) else {
+        return Ok(false);
+    };
+
+    Ok(match name {
+        "equals" => number == expected,
+        "does_not_equal" => number != expected,
+        "greater_than" => number > expected,
+        "less_than" => number < expected,
+        "greater_than_or_equal_to" => number >= expected,
+        "less_than_or_equal_to" => number <= expected,
+        _ => return Err(unsupported(name)),
+    })
+}
+
+fn checkbox(checked: bool, filter: &Value) -> ApiResult<bool> {
+    let (name, value) = condition(filter, "checkbox")?;
+    let expected = value.as_bool().ok_or_else(|| invalid(name))?;
+
+    Ok(match name {
+        "equals" => checked == expected,
+        "does_not_equal" => checked != expected,
+        _ => return Err(unsupported(name)),
+    })
+}
+
+fn select(option: Option<&str>, filter: &Value) -> ApiResult<bool> {
+    let (name, value) = condition(filter, "select")?;
+
+    Ok(match name {
+        "equals" => option.is_some() && option == value.as_str(),
+        "does_not_equal" => option != value.as_str(),
+        "is_empty" => option.is_none(),
+        "is_not_empty" => option.is_some(),
+        _ => return Err(unsupported(name)),
+    })
+}
+
+fn date(date: Option<DateTime<FixedOffset>>, filter: &Value) -> ApiResult<bool> {
+    let (name, value) = condition(filter, "date")?;
+
+    if name == "is_empty" || name == "is_not_empty" {
+        return Ok(date.is_none() == (name == "is_empty"));
+    }
+
+    let expected =
+        serde_json::from_value::<DateOrDateTime>(value.clone()).map_err(|_| invalid(name))?;
+    let expected = timestamp_of(&expected);
+
+    let Some(date) = date else {
+        return Ok(false);
+    };
+
+    Ok(match name {
+        "equals" => date == expected,
+        "before" => date < expected,
+        "after" => date > expected,
+        "on_or_before" => date <= expected,
+        "on_or_after" => date >= expected,
+        _ => return Err(unsupported(name)),
+    })
+}
+
+/// Sorts pages by the sort criteria, in order of precedence.
+pub fn sort(pages: &mut [Page], sorts: &[Sort]) {
+    pages.sort_by(|a, b| {
+        sorts
+            .iter()
+            .map(|sort| compare(a, b, sort))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+fn compare(a: &Page, b: &Page, sort: &Sort) -> Ordering {
+    let (ordering, direction) = match sort {
+        Sort::Timestamp {
+            timestamp,
+            direction,
+        } => {
+            let time = |page: &Page| {
+                match timestamp {
+                    SortTimestamp::CreatedTime => page.created_time,
+                    SortTimestamp::LastEditedTime => page.last_edited_time,
+                }
+                .as_ref()
+                .map(timestamp_of)
+            };
+
+            (time(a).cmp(&time(b)), direction)
+        },
+        Sort::Property {
+            property,
+            direction,
+        } => {
+            let (a, b) = (sort_key(a, property), sort_key(b, property));
+
+            let ordering = match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                _ => plain_text(&a).cmp(&plain_text(&b)),
+            };
+
+            (ordering, direction)
+        },
+    };
+
+    match direction {
+        SortDirection::Ascending => ordering,
+        SortDirection::Descending => ordering.reverse(),
+    }
+}
+
+fn sort_key(page: &Page, property: &str) -> Value {
+    page.properties
+        .get(property)
+        .and_then(|property| serde_json::to_value(&property.data).ok())
+        .and_then(|value| value.as_object()?.values().next().cloned())
+        // select and status options are sorted by name
+        .map(|value| value.get("name").cloned().unwrap_or(value))
+        .unwrap_or_default()
+}
+
+/// The plain text of a rich text array, or of a string.
+pub fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                item.get("plain_text")
+                    .or_else(|| item.pointer("/text/content"))
+                    .or_else(|| item.pointer("/equation/expression"))
+                    .and_then(Value::as_str)
+            })
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// A date as the start of the day in UTC, or a date time as is.
+pub fn timestamp_of(date: &DateOrDateTime) -> DateTime<FixedOffset> {
+    match date {
+        DateOrDateTime::Date(date) => date.and_time(NaiveTime::MIN).and_utc().fixed_offset(),
+        DateOrDateTime::DateTime(date_time) => *date_time,
+    }
+}
+
+fn invalid(name: &str) -> ApiError {
+    ApiError::validation(format!(
+        "body failed validation: body.filter.{name} is invalid."
+    ))
+}
+
+fn unsupported(name: &str) -> ApiError {
+    ApiError::validation(format!(
+        "body failed validation: the {name} filter is not supported by notion-mock."
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use notion_model::objects::properties::{Checkbox, Number, Title};
+    use serde_json::json;
+
+    use super::*;
+
+    fn page(title: &str, number: f64, done: bool) -> Page {
+        Page::new().properties(HashMap::from([
+            Title::new(title).build_with_name("Name"),
+            Number::new(number).build_with_name("Price"),
+            Checkbox::new(done).build_with_name("Done"),
+        ]))
+    }
+
+    #[test]
+    fn property() {
+        let page = page("Tuscan kale", 3.0, true);
+
+        assert!(matches(
+            &json!({"property": "Name", "title": {"contains": "kale"}}),
+            &page
+        )
+        .unwrap());
+        assert!(matches(
+            &json!({"property": "Price", "number": {"greater_than": 2}}),
+            &page
+        )
+        .unwrap());
+        assert!(!matches(
+            &json!({"property": "Done", "checkbox": {"equals": false}}),
+            &page
+        )
+        .unwrap());
+        assert!(matches(&json!({"property": "Name", "number": {"equals": 1}}), &page).is_err());
+        assert!(matches(
+            &json!({"property": "Name", "title": {"matches": "k.*"}}),
+            &page
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn compound() {
+        let page = page("Tuscan kale", 3.0, true);
+
+        let filter = json!({"or": [
+            {"property": "Done", "checkbox": {"equals": false}},
+            {"and": [
+                {"property": "Price", "number": {"less_than_or_equal_to": 3}},
+                {"property": "Name", "title": {"starts_with": "Tuscan"}},
+            ]},
+        ]});
+
+        assert!(matches(&filter, &page).unwrap());
+    }
+
+    #[test]
+    fn sorts() {
+        let mut pages = vec![
+            page("b", 1.0, true),
+            page("a", 2.0, true),
+            page("c", 1.0, false),
+        ];
+
+        sort(
+            &mut pages,
+            &[
+                Sort::Property {
+                    property: "Price".to_owned(),
+                    direction: SortDirection::Descending,
+                },
+                Sort::Property {
+                    property: "Name".to_owned(),
+                    direction: SortDirection::Ascending,
+                },
+            ],
+        );
+
+        let names = pages
+            .iter()
+            .map(|page| sort_key(page, "Name"))
+            .map(|name| plain_text(&name))
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["a", "b", "c"]);
+    }
+}
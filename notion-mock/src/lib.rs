@@ -0,0 +1,243 @@
+//! notion-mock
+//! An in-process fake of the Notion API, for integration tests that can't use
+//! the real API.
+//!
+//! [`MockServer::start`] runs an HTTP server on a local port that implements
+//! the blocks, pages, databases, search, users, comments and OAuth endpoints
+//! against an in-memory workspace. Point a client at [`MockServer::url`] and
+//! authenticate with [`API_TOKEN`], or with an access token from the OAuth
+//! endpoints.
+//!
+//! The workspace starts out empty, except for the bot user of the
+//! integration. Pages can be created at the top level of the workspace, which
+//! the real API only allows for public integrations.
+//!
+//! # Behavior
+//!
+//! - Paginated endpoints return at most `page_size` results, 100 by default,
+//!   with the ID of the next result as the cursor.
+//! - Archived pages, databases and blocks can still be retrieved by ID, but are
+//!   left out of block children, database queries and search results. Editing
+//!   an archived object fails until it is restored with `archived: false`.
+//! - Editing the content of a page also updates the `last_edited_time` of the
+//!   page.
+//! - OAuth clients are added with [`MockServer::add_oauth_client`], and codes
+//!   are issued with [`MockServer::authorize`] instead of a user allowing
+//!   access. Codes can only be exchanged once, and revoked access tokens stop
+//!   working.
+//! - Errors have the status and code of the matching API error, such as a 404
+//!   `object_not_found` for an unknown ID, or a 400 `validation_error` for a
+//!   page property that isn't in the database schema.
+//! - Status properties can't be created, as in the real API, but can be added
+//!   with [`MockServer::add_database_property`].
+//! - Database queries support compound and timestamp filters, and property
+//!   filters on text, number, checkbox, select and status properties. Other
+//!   filters fail with a `validation_error`.
+//!
+//! # Fault injection
+//!
+//! [`MockServer::inject`] makes the next requests fail with a [`Fault`], and
+//! [`MockServer::latency`] delays every response, to test how clients handle
+//! rate limits, outages and slow responses.
+
+#![forbid(unsafe_code)]
+#![warn(
+    absolute_paths_not_starting_with_crate,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    unused_qualifications,
+    clippy::all,
+    clippy::nursery,
+    clippy::expect_used,
+    clippy::unwrap_used
+)]
+
+mod error;
+mod filter;
+mod oauth;
+mod routes;
+mod store;
+
+use std::{
+    collections::VecDeque,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use notion_model::{
+    ids::DatabaseId,
+    objects::{database_property::DatabaseProperty, user::User},
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{error::ApiError, oauth::OAuth, store::Store};
+
+/// The API token the server accepts. Requests with any other token fail with
+/// a 401 `unauthorized` error.
+pub const API_TOKEN: &str = "secret_mock";
+
+/// An error response to return instead of handling a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// 409 `conflict_error`.
+    Conflict,
+    /// 429 `rate_limited`, with a `Retry-After` header.
+    RateLimited,
+    /// 500 `internal_server_error`.
+    InternalServerError,
+    /// 503 `service_unavailable`.
+    ServiceUnavailable,
+}
+
+impl Fault {
+    fn error(self) -> ApiError {
+        match self {
+            Self::Conflict => ApiError::conflict(),
+            Self::RateLimited => ApiError::rate_limited(),
+            Self::InternalServerError => ApiError::internal_server_error(),
+            Self::ServiceUnavailable => ApiError::service_unavailable(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    queue: VecDeque<Fault>,
+    latency: Option<Duration>,
+    requests: usize,
+}
+
+/// State shared by the server and its handle.
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    store: Mutex<Store>,
+    oauth: Mutex<OAuth>,
+    faults: Mutex<Faults>,
+}
+
+impl Shared {
+    fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn oauth(&self) -> MutexGuard<'_, OAuth> {
+        self.oauth.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.faults.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Counts a request, returning the latency and fault to apply to it.
+    fn next_request(&self) -> (Option<Duration>, Option<Fault>) {
+        let mut faults = self.faults();
+        faults.requests += 1;
+
+        (faults.latency, faults.queue.pop_front())
+    }
+}
+
+/// A running mock server. The server stops when this is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts a server with an empty workspace on a free local port.
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let shared = Arc::new(Shared::default());
+        let router = routes::router(shared.clone());
+
+        let task = tokio::spawn(async move {
+            // the server only stops when the task is aborted
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(Self { addr, shared, task })
+    }
+
+    /// The base URL of the API, including the version prefix, e.g.
+    /// `http://127.0.0.1:4000/v1`.
+    pub fn url(&self) -> String {
+        format!("http://{}/v1", self.addr)
+    }
+
+    /// The bot user of the integration, returned by `GET /v1/users/me`.
+    pub fn bot_user(&self) -> User {
+        self.shared.store().bot()
+    }
+
+    /// Adds a user to the workspace.
+    pub fn add_user(&self, user: User) {
+        self.shared.store().add_user(user);
+    }
+
+    /// Adds a property to the schema of a database, as the Notion app can,
+    /// including the status properties that the API can't create. Does
+    /// nothing if there is no such database.
+    pub fn add_database_property(
+        &self,
+        database_id: DatabaseId,
+        name: &str,
+        property: DatabaseProperty,
+    ) {
+        self.shared
+            .store()
+            .add_database_property(*database_id.into_inner(), name, property);
+    }
+
+    /// Registers the client ID and secret of a public integration, for the
+    /// OAuth endpoints.
+    pub fn add_oauth_client(&self, client_id: &str, client_secret: &str) {
+        self.shared.oauth().add_client(client_id, client_secret);
+    }
+
+    /// Issues a code to a client, as Notion does when a user allows access
+    /// through the authorization URL. Exchanging the code requires the same
+    /// `redirect_uri`.
+    pub fn authorize(&self, client_id: &str, redirect_uri: Option<&str>) -> String {
+        self.shared.oauth().authorize(client_id, redirect_uri)
+    }
+
+    /// Makes the next `count` requests fail with `fault`, after any faults
+    /// that were already injected.
+    pub fn inject(&self, fault: Fault, count: usize) {
+        self.shared
+            .faults()
+            .queue
+            .extend(std::iter::repeat(fault).take(count));
+    }
+
+    /// Delays every response by `latency`, or stops delaying them if `None`.
+    pub fn latency(&self, latency: Option<Duration>) {
+        self.shared.faults().latency = latency;
+    }
+
+    /// Gives every later edit `time` as its timestamp, or the current time
+    /// again if `None`. The real API rounds timestamps down to the minute, so
+    /// this stands in for several edits in the same minute.
+    pub fn set_time(&self, time: Option<DateTime<Utc>>) {
+        self.shared.store().set_time(time);
+    }
+
+    /// The number of requests the server has received, including ones that
+    /// failed.
+    pub fn request_count(&self) -> usize {
+        self.shared.faults().requests
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
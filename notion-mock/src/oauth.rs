@@ -0,0 +1,173 @@
+//! The OAuth endpoints of public integrations.
+//!
+//! Clients authenticate with HTTP basic authentication, using the client ID
+//! and secret added with [`crate::MockServer::add_oauth_client`]. Codes are
+//! issued by [`crate::MockServer::authorize`], in place of a user going
+//! through the authorization URL, and can be exchanged once.
+
+use std::collections::HashMap;
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use notion_model::{
+    ids::UserId,
+    oauth::{Token, TokenInfo, TokenRequest},
+    objects::user::BotOwner,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+
+/// The ID of the workspace that tokens give access to.
+const WORKSPACE_ID: &str = "1a2b3c4d-0000-4000-8000-000000000001";
+
+/// An error of the token endpoint, which has the shape of an OAuth 2.0 error
+/// rather than of an API error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthError {
+    pub error: &'static str,
+    pub description: &'static str,
+}
+
+impl OAuthError {
+    const fn invalid_grant(description: &'static str) -> Self {
+        Self {
+            error: "invalid_grant",
+            description,
+        }
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        let body = Json(json!({
+            "error": self.error,
+            "error_description": self.description,
+        }));
+
+        (StatusCode::BAD_REQUEST, body).into_response()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OAuth {
+    /// Client secrets, by client ID.
+    clients: HashMap<String, String>,
+    /// Codes that haven't been exchanged yet, with the client they were
+    /// issued to and the redirect URI they were issued for.
+    codes: HashMap<String, (String, Option<String>)>,
+    /// Refresh tokens, with the client they were issued to.
+    refresh_tokens: HashMap<String, String>,
+    /// Access tokens that haven't been revoked, with when they were issued.
+    access_tokens: HashMap<String, i64>,
+}
+
+impl OAuth {
+    pub fn add_client(&mut self, client_id: &str, client_secret: &str) {
+        self.clients
+            .insert(client_id.to_owned(), client_secret.to_owned());
+    }
+
+    pub fn authorize(&mut self, client_id: &str, redirect_uri: Option<&str>) -> String {
+        let code = Uuid::new_v4().to_string();
+
+        self.codes.insert(
+            code.clone(),
+            (client_id.to_owned(), redirect_uri.map(str::to_owned)),
+        );
+
+        code
+    }
+
+    /// Whether an access token was issued and not revoked.
+    pub fn is_active(&self, access_token: &str) -> bool {
+        self.access_tokens.contains_key(access_token)
+    }
+
+    /// The client that authenticated a request with basic authentication.
+    fn client(&self, headers: &HeaderMap) -> Result<String, ApiError> {
+        headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Basic "))
+            .and_then(|credentials| STANDARD.decode(credentials).ok())
+            .and_then(|credentials| String::from_utf8(credentials).ok())
+            .and_then(|credentials| {
+                let (id, secret) = credentials.split_once(':')?;
+
+                (self.clients.get(id)? == secret).then(|| id.to_owned())
+            })
+            .ok_or_else(ApiError::unauthorized)
+    }
+
+    pub fn token(
+        &mut self,
+        headers: &HeaderMap,
+        request: TokenRequest,
+        bot_id: UserId,
+    ) -> Result<Result<Token, OAuthError>, ApiError> {
+        let client = self.client(headers)?;
+
+        let granted = match request {
+            TokenRequest::AuthorizationCode { code, redirect_uri } => {
+                match self.codes.remove(&code) {
+                    Some(issued) if issued == (client.clone(), redirect_uri) => Ok(()),
+                    _ => Err(OAuthError::invalid_grant("Invalid code.")),
+                }
+            },
+            TokenRequest::RefreshToken { refresh_token } => {
+                match self.refresh_tokens.get(&refresh_token) {
+                    Some(issued) if *issued == client => Ok(()),
+                    _ => Err(OAuthError::invalid_grant("Invalid refresh token.")),
+                }
+            },
+        };
+
+        Ok(granted.map(|()| self.issue(client, bot_id)))
+    }
+
+    fn issue(&mut self, client: String, bot_id: UserId) -> Token {
+        let access_token = format!("secret_{}", Uuid::new_v4().simple());
+        let refresh_token = format!("nrt_{}", Uuid::new_v4().simple());
+
+        self.access_tokens
+            .insert(access_token.clone(), Utc::now().timestamp());
+        self.refresh_tokens.insert(refresh_token.clone(), client);
+
+        Token {
+            access_token,
+            token_type: "bearer".to_owned(),
+            refresh_token: Some(refresh_token),
+            bot_id,
+            workspace_id: WORKSPACE_ID.to_owned().into(),
+            workspace_name: Some("Mock Workspace".to_owned()),
+            workspace_icon: None,
+            owner: BotOwner::Workspace { workspace: true },
+            duplicated_template_id: None,
+        }
+    }
+
+    pub fn introspect(&self, headers: &HeaderMap, token: &str) -> Result<TokenInfo, ApiError> {
+        self.client(headers)?;
+
+        Ok(TokenInfo {
+            active: self.is_active(token),
+            scope: None,
+            iat: self.access_tokens.get(token).copied(),
+        })
+    }
+
+    pub fn revoke(&mut self, headers: &HeaderMap, token: &str) -> Result<(), ApiError> {
+        self.client(headers)?;
+
+        self.access_tokens.remove(token);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+//! HTTP handlers of the endpoints.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use notion_model::{
+    oauth::{TokenInfo, TokenRequest},
+    objects::{block::Block, comment::Comment, database::Database, page::Page, user::User},
+    pagination::List,
+    query::DatabaseQuery,
+    search::{PageOrDatabase, SearchQuery},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    error::ApiError,
+    store::{ApiResult, DatabaseUpdate, PageUpdate},
+    Shared, API_TOKEN,
+};
+
+type Params = Query<Vec<(String, String)>>;
+
+pub fn router(shared: Arc<Shared>) -> Router {
+    Router::new()
+        .route(
+            "/v1/blocks/:id",
+            get(retrieve_block).patch(update_block).delete(delete_block),
+        )
+        .route(
+            "/v1/blocks/:id/children",
+            get(retrieve_block_children).patch(append_block_children),
+        )
+        .route("/v1/pages", post(create_page))
+        .route("/v1/pages/:id", get(retrieve_page).patch(update_page))
+        .route("/v1/databases", post(create_database))
+        .route(
+            "/v1/databases/:id",
+            get(retrieve_database).patch(update_database),
+        )
+        .route("/v1/databases/:id/query", post(query_database))
+        .route("/v1/search", post(search))
+        .route("/v1/users", get(list_users))
+        .route("/v1/users/me", get(retrieve_bot_user))
+        .route("/v1/users/:id", get(retrieve_user))
+        .route("/v1/comments", get(retrieve_comments).post(create_comment))
+        .route("/v1/oauth/token", post(create_token))
+        .route("/v1/oauth/introspect", post(introspect_token))
+        .route("/v1/oauth/revoke", post(revoke_token))
+        .fallback(|| async { ApiError::invalid_request_url() })
+        .layer(middleware::from_fn_with_state(
+            shared.clone(),
+            check_request,
+        ))
+        .with_state(shared)
+}
+
+/// Applies the injected latency and faults, then checks the headers every
+/// request must have. The OAuth endpoints authenticate clients themselves.
+async fn check_request(
+    State(shared): State<Arc<Shared>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (latency, fault) = shared.next_request();
+
+    if let Some(latency) = latency {
+        tokio::time::sleep(latency).await;
+    }
+
+    if let Some(fault) = fault {
+        return fault.error().into_response();
+    }
+
+    if request.uri().path().starts_with("/v1/oauth/") {
+        return next.run(request).await;
+    }
+
+    let headers = request.headers();
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match token {
+        Some(API_TOKEN) => {},
+        Some(token) if shared.oauth().is_active(token) => {},
+        _ => return ApiError::unauthorized().into_response(),
+    }
+
+    if !headers.contains_key("notion-version") {
+        return ApiError::missing_version().into_response();
+    }
+
+    next.run(request).await
+}
+
+fn parse_id(id: &str, name: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(id).map_err(|_| {
+        ApiError::validation(format!(
+            "path failed validation: path.{name} should be a valid uuid, instead was `\"{id}\"`."
+        ))
+    })
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> ApiResult<T> {
+    let value = serde_json::from_slice::<Value>(body).map_err(|_| ApiError::invalid_json())?;
+
+    serde_json::from_value(value)
+        .map_err(|e| ApiError::validation(format!("body failed validation: {e}")))
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn page_size(params: &[(String, String)]) -> ApiResult<Option<u32>> {
+    param(params, "page_size")
+        .map(str::parse)
+        .transpose()
+        .map_err(|_| {
+            ApiError::validation("query failed validation: query.page_size should be a number.")
+        })
+}
+
+async fn retrieve_block(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Block>> {
+    let id = parse_id(&id, "block_id")?;
+
+    shared.store().retrieve_block(id).map(Json)
+}
+
+async fn update_block(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Json<Block>> {
+    let id = parse_id(&id, "block_id")?;
+    let body = parse_body::<Map<String, Value>>(&body)?;
+
+    shared.store().update_block(id, body).map(Json)
+}
+
+async fn delete_block(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Block>> {
+    let id = parse_id(&id, "block_id")?;
+
+    shared.store().delete_block(id).map(Json)
+}
+
+async fn retrieve_block_children(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    Query(params): Params,
+) -> ApiResult<Json<List<Block>>> {
+    let id = parse_id(&id, "block_id")?;
+
+    shared
+        .store()
+        .retrieve_block_children(id, param(&params, "start_cursor"), page_size(&params)?)
+        .map(Json)
+}
+
+async fn append_block_children(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Json<List<Block>>> {
+    #[derive(Deserialize)]
+    struct AppendBlockChildren {
+        children: Vec<Block>,
+        after: Option<String>,
+    }
+
+    let id = parse_id(&id, "block_id")?;
+    let body = parse_body::<AppendBlockChildren>(&body)?;
+    let after = body
+        .after
+        .map(|after| {
+            Uuid::parse_str(&after).map_err(|_| {
+                ApiError::validation(format!(
+                    "body failed validation: body.after should be a valid uuid, instead was \
+                     `\"{after}\"`."
+                ))
+            })
+        })
+        .transpose()?;
+
+    shared
+        .store()
+        .append_block_children(id, body.children, after)
+        .map(Json)
+}
+
+async fn create_page(State(shared): State<Arc<Shared>>, body: Bytes) -> ApiResult<Json<Page>> {
+    let page = parse_body::<Page>(&body)?;
+
+    shared.store().create_page(page).map(Json)
+}
+
+async fn retrieve_page(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    Query(params): Params,
+) -> ApiResult<Json<Page>> {
+    let id = parse_id(&id, "page_id")?;
+
+    let filter_properties = params
+        .into_iter()
+        .filter(|(key, _)| key == "filter_properties")
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>();
+
+    shared
+        .store()
+        .retrieve_page(id, &filter_properties)
+        .map(Json)
+}
+
+async fn update_page(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Json<Page>> {
+    let id = parse_id(&id, "page_id")?;
+    let update = parse_body::<PageUpdate>(&body)?;
+
+    shared.store().update_page(id, update).map(Json)
+}
+
+async fn create_database(
+    State(shared): State<Arc<Shared>>,
+    body: Bytes,
+) -> ApiResult<Json<Database>> {
+    let database = parse_body::<Database>(&body)?;
+
+    shared.store().create_database(database).map(Json)
+}
+
+async fn retrieve_database(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Database>> {
+    let id = parse_id(&id, "database_id")?;
+
+    shared.store().retrieve_database(id).map(Json)
+}
+
+async fn update_database(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Json<Database>> {
+    let id = parse_id(&id, "database_id")?;
+    let update = parse_body::<DatabaseUpdate>(&body)?;
+
+    shared.store().update_database(id, update).map(Json)
+}
+
+async fn query_database(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Json<List<Page>>> {
+    let id = parse_id(&id, "database_id")?;
+    let query = match body.is_empty() {
+        true => DatabaseQuery::default(),
+        false => parse_body::<DatabaseQuery>(&body)?,
+    };
+
+    shared.store().query_database(id, &query).map(Json)
+}
+
+async fn search(
+    State(shared): State<Arc<Shared>>,
+    body: Bytes,
+) -> ApiResult<Json<List<PageOrDatabase>>> {
+    let query = match body.is_empty() {
+        true => SearchQuery::default(),
+        false => parse_body::<SearchQuery>(&body)?,
+    };
+
+    shared.store().search(&query).map(Json)
+}
+
+async fn list_users(
+    State(shared): State<Arc<Shared>>,
+    Query(params): Params,
+) -> ApiResult<Json<List<User>>> {
+    shared
+        .store()
+        .list_users(param(&params, "start_cursor"), page_size(&params)?)
+        .map(Json)
+}
+
+async fn retrieve_bot_user(State(shared): State<Arc<Shared>>) -> Json<User> {
+    Json(shared.store().bot())
+}
+
+async fn retrieve_user(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<User>> {
+    let id = parse_id(&id, "user_id")?;
+
+    shared.store().retrieve_user(id).map(Json)
+}
+
+async fn create_comment(
+    State(shared): State<Arc<Shared>>,
+    body: Bytes,
+) -> ApiResult<Json<Comment>> {
+    let comment = parse_body::<Comment>(&body)?;
+
+    shared.store().create_comment(comment).map(Json)
+}
+
+async fn retrieve_comments(
+    State(shared): State<Arc<Shared>>,
+    Query(params): Params,
+) -> ApiResult<Json<List<Comment>>> {
+    let block_id = param(&params, "block_id").ok_or_else(|| {
+        ApiError::validation(
+            "query failed validation: query.block_id should be defined, instead was `undefined`.",
+        )
+    })?;
+    let block_id = parse_id(block_id, "block_id")?;
+
+    shared
+        .store()
+        .retrieve_comments(
+            block_id,
+            param(&params, "start_cursor"),
+            page_size(&params)?,
+        )
+        .map(Json)
+}
+
+#[derive(Deserialize)]
+struct TokenBody {
+    token: String,
+}
+
+async fn create_token(
+    State(shared): State<Arc<Shared>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Response> {
+    let request = parse_body::<TokenRequest>(&body)?;
+    let bot_id = shared.store().bot().id;
+
+    let token = shared.oauth().token(&headers, request, bot_id)?;
+
+    Ok(match token {
+        Ok(token) => Json(token).into_response(),
+        Err(e) => e.into_response(),
+    })
+}
+
+async fn introspect_token(
+    State(shared): State<Arc<Shared>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<TokenInfo>> {
+    let body = parse_body::<TokenBody>(&body)?;
+
+    shared.oauth().introspect(&headers, &body.token).map(Json)
+}
+
+async fn revoke_token(
+    State(shared): State<Arc<Shared>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<Map<String, Value>>> {
+    let body = parse_body::<TokenBody>(&body)?;
+
+    shared.oauth().revoke(&headers, &body.token)?;
+
+    Ok(Json(Map::new()))
+}
@@ -0,0 +1,1093 @@
+//! In-memory store of the objects in the mock workspace.
+//!
+//! Each method implements the behavior of one endpoint, returning the same
+//! error the API would for invalid requests.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use notion_model::{
+    ids::{DiscussionId, PropertyId, UserId},
+    objects::{
+        block::{Block, BlockData, ChildDatabase, ChildPage},
+        comment::Comment,
+        database::Database,
+        database_property::{DatabaseProperty, DatabasePropertyData},
+        date::DateOrDateTime,
+        file_and_emoji::{Cover, FileOrEmoji},
+        page::Page,
+        parent::ParentData,
+        properties::{Property, PropertyData},
+        rich_text::RichText,
+        user::{Bot, BotOwner, PartialUser, User, UserData},
+    },
+    pagination::{List, ListData},
+    query::{DatabaseQuery, Sort, SortDirection, SortTimestamp},
+    search::{PageOrDatabase, SearchFilter, SearchObject, SearchQuery},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{error::ApiError, filter};
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Default and maximum number of results in a page of a paginated list.
+const PAGE_SIZE: u32 = 100;
+
+/// Body of an update page request.
+#[derive(Deserialize, Debug)]
+pub struct PageUpdate {
+    #[serde(default)]
+    properties: HashMap<String, Property>,
+    archived: Option<bool>,
+    icon: Option<FileOrEmoji>,
+    cover: Option<Cover>,
+}
+
+/// Body of an update database request.
+#[derive(Deserialize, Debug)]
+pub struct DatabaseUpdate {
+    title: Option<Vec<RichText>>,
+    description: Option<Vec<RichText>>,
+    icon: Option<FileOrEmoji>,
+    cover: Option<Cover>,
+    #[serde(default)]
+    properties: HashMap<String, DatabaseProperty>,
+    archived: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct Store {
+    bot: User,
+    users: Vec<User>,
+    /// IDs of pages and databases, in the order they were created.
+    objects: Vec<Uuid>,
+    pages: HashMap<Uuid, Page>,
+    databases: HashMap<Uuid, Database>,
+    blocks: HashMap<Uuid, Block>,
+    /// IDs of the child blocks, pages and databases of pages and blocks, in
+    /// order.
+    children: HashMap<Uuid, Vec<Uuid>>,
+    comments: Vec<Comment>,
+    last_time: DateTime<Utc>,
+    /// Time given to every edit, instead of the current time.
+    time: Option<DateTime<Utc>>,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        let bot = User {
+            id: UserId::from(Uuid::new_v4()),
+            name: Some("Mock Integration".to_owned()),
+            avatar_url: None,
+            data: Some(UserData::Bot(Bot {
+                owner: Some(BotOwner::Workspace { workspace: true }),
+                workspace_name: Some("Mock Workspace".to_owned()),
+            })),
+        };
+
+        Self {
+            users: vec![bot.clone()],
+            bot,
+            objects: Vec::new(),
+            pages: HashMap::new(),
+            databases: HashMap::new(),
+            blocks: HashMap::new(),
+            children: HashMap::new(),
+            comments: Vec::new(),
+            last_time: DateTime::<Utc>::MIN_UTC,
+            time: None,
+        }
+    }
+}
+
+impl Store {
+    /// The current time, with millisecond precision. Every call returns a later
+    /// time than the previous one, so objects can be ordered by timestamps,
+    /// unless a time was set with [`Store::set_time`].
+    fn now(&mut self) -> DateOrDateTime {
+        if let Some(time) = self.time {
+            return DateOrDateTime::DateTime(time.fixed_offset());
+        }
+
+        let now = Utc::now()
+            .trunc_subsecs(3)
+            .max(self.last_time + Duration::milliseconds(1));
+
+        self.last_time = now;
+
+        DateOrDateTime::DateTime(now.fixed_offset())
+    }
+
+    pub fn set_time(&mut self, time: Option<DateTime<Utc>>) {
+        self.time = time;
+    }
+
+    const fn partial_bot(&self) -> PartialUser {
+        PartialUser { id: self.bot.id }
+    }
+
+    pub fn bot(&self) -> User {
+        self.bot.clone()
+    }
+
+    pub fn add_user(&mut self, user: User) {
+        self.users.push(user);
+    }
+
+    fn page(&self, id: Uuid) -> ApiResult<&Page> {
+        self.pages
+            .get(&id)
+            .ok_or_else(|| ApiError::not_found("page", id))
+    }
+
+    fn database(&self, id: Uuid) -> ApiResult<&Database> {
+        self.databases
+            .get(&id)
+            .ok_or_else(|| ApiError::not_found("database", id))
+    }
+
+    /// Whether a page, database or block is archived, or `None` if it doesn't
+    /// exist.
+    fn archived(&self, id: Uuid) -> Option<bool> {
+        let archived = match (
+            self.blocks.get(&id),
+            self.pages.get(&id),
+            self.databases.get(&id),
+        ) {
+            (Some(block), _, _) => block.archived,
+            (_, Some(page), _) => page.archived,
+            (_, _, Some(database)) => database.archived,
+            _ => return None,
+        };
+
+        Some(archived.unwrap_or(false))
+    }
+
+    /// Checks that children can be added to a page or block.
+    fn check_writable(&self, id: Uuid) -> ApiResult<()> {
+        if !self.blocks.contains_key(&id) && !self.pages.contains_key(&id) {
+            return Err(ApiError::not_found("block", id));
+        }
+
+        match self.archived(id) {
+            Some(true) => Err(ApiError::archived()),
+            _ => Ok(()),
+        }
+    }
+
+    fn has_children(&self, id: Uuid) -> bool {
+        self.children.get(&id).map_or(false, |children| {
+            children
+                .iter()
+                .any(|child| self.archived(*child) == Some(false))
+        })
+    }
+
+    fn parent_of(&self, id: Uuid) -> ParentData {
+        match self.pages.contains_key(&id) {
+            true => ParentData::PageId { page_id: id.into() },
+            false => ParentData::BlockId {
+                block_id: id.into(),
+            },
+        }
+    }
+
+    /// The page that a block is part of, or the page itself.
+    fn page_of(&self, mut id: Uuid) -> Option<Uuid> {
+        while !self.pages.contains_key(&id) {
+            id = parent_id(self.blocks.get(&id)?.parent.as_ref()?)?;
+        }
+
+        Some(id)
+    }
+
+    /// Updates the last edited time of a page or block. Editing a block also
+    /// edits the page it is part of, as in the real API.
+    fn touch(&mut self, id: Uuid) {
+        let now = self.now();
+        let bot = self.partial_bot();
+
+        if let Some(block) = self.blocks.get_mut(&id) {
+            block.last_edited_time = Some(now);
+            block.last_edited_by = Some(bot);
+        }
+
+        if let Some(page) = self.page_of(id).and_then(|page| self.pages.get_mut(&page)) {
+            page.last_edited_time = Some(now);
+            page.last_edited_by = Some(bot);
+        }
+    }
+
+    /// Validates page property values against the schema of the parent,
+    /// keying them by property name and setting their IDs.
+    fn resolve_properties(
+        &self,
+        parent: Option<&ParentData>,
+        properties: HashMap<String, Property>,
+    ) -> ApiResult<HashMap<String, Property>> {
+        let database = match parent {
+            Some(ParentData::DatabaseId { database_id }) => {
+                Some(self.database(*database_id.into_inner())?)
+            },
+            _ => None,
+        };
+
+        properties
+            .into_iter()
+            .map(|(key, mut property)| {
+                let (name, id, expected) = match database {
+                    Some(database) => database
+                        .properties
+                        .iter()
+                        .find(|(name, schema)| {
+                            **name == key
+                                || schema.id.as_ref().map_or(false, |id| id.to_string() == key)
+                        })
+                        .map(|(name, schema)| {
+                            (name.clone(), schema.id.clone(), type_name(&schema.data))
+                        })
+                        .ok_or_else(|| {
+                            ApiError::validation(format!("{key} is not a property that exists."))
+                        })?,
+                    None => (
+                        key,
+                        Some(PropertyId::from("title".to_owned())),
+                        "title".to_owned(),
+                    ),
+                };
+
+                if type_name(&property.data) != expected {
+                    return Err(ApiError::validation(format!(
+                        "{name} is expected to be {expected}."
+                    )));
+                }
+
+                property.id = id;
+
+                Ok((name, property))
+            })
+            .collect()
+    }
+
+    pub fn create_page(&mut self, mut page: Page) -> ApiResult<Page> {
+        let parent = page.parent.ok_or_else(|| {
+            ApiError::validation(
+                "body failed validation: body.parent should be defined, instead was `undefined`.",
+            )
+        })?;
+
+        match parent {
+            ParentData::DatabaseId { database_id } => {
+                if self.database(*database_id.into_inner())?.archived == Some(true) {
+                    return Err(ApiError::archived());
+                }
+            },
+            ParentData::PageId { page_id } => {
+                self.page(*page_id.into_inner())?;
+                self.check_writable(*page_id.into_inner())?;
+            },
+            ParentData::Workspace { .. } => {},
+            ParentData::BlockId { .. } => {
+                return Err(ApiError::validation(
+                    "body failed validation: body.parent.page_id should be defined, instead was \
+                     `undefined`.",
+                ))
+            },
+        }
+
+        page.properties = self.resolve_properties(Some(&parent), page.properties)?;
+
+        let id = Uuid::new_v4();
+        let now = self.now();
+
+        page.id = Some(id.into());
+        page.created_time = Some(now);
+        page.last_edited_time = Some(now);
+        page.created_by = Some(self.partial_bot());
+        page.last_edited_by = Some(self.partial_bot());
+        page.archived = Some(false);
+        page.url = Some(url(id));
+
+        if let ParentData::PageId { page_id } = parent {
+            self.children
+                .entry(*page_id.into_inner())
+                .or_default()
+                .push(id);
+            self.touch(*page_id.into_inner());
+        }
+
+        self.objects.push(id);
+        self.pages.insert(id, page.clone());
+
+        Ok(page)
+    }
+
+    pub fn retrieve_page(&self, id: Uuid, filter_properties: &[String]) -> ApiResult<Page> {
+        let mut page = self.page(id)?.clone();
+
+        if !filter_properties.is_empty() {
+            page.properties.retain(|name, property| {
+                filter_properties.iter().any(|filter| {
+                    filter == name
+                        || property
+                            .id
+                            .as_ref()
+                            .map_or(false, |id| id.to_string() == *filter)
+                })
+            });
+        }
+
+        Ok(page)
+    }
+
+    pub fn update_page(&mut self, id: Uuid, update: PageUpdate) -> ApiResult<Page> {
+        let mut page = self.page(id)?.clone();
+
+        if page.archived == Some(true) && update.archived != Some(false) {
+            return Err(ApiError::archived());
+        }
+
+        let properties = self.resolve_properties(page.parent.as_ref(), update.properties)?;
+
+        page.properties.extend(properties);
+
+        if let Some(archived) = update.archived {
+            page.archived = Some(archived);
+        }
+
+        if let Some(icon) = update.icon {
+            page.icon = Some(icon);
+        }
+
+        if let Some(cover) = update.cover {
+            page.cover = Some(cover);
+        }
+
+        page.last_edited_time = Some(self.now());
+        page.last_edited_by = Some(self.partial_bot());
+
+        self.pages.insert(id, page.clone());
+
+        Ok(page)
+    }
+
+    pub fn create_database(&mut self, mut database: Database) -> ApiResult<Database> {
+        let parent =
+            match database.parent {
+                Some(ParentData::PageId { page_id }) => *page_id.into_inner(),
+                _ => return Err(ApiError::validation(
+                    "body failed validation: body.parent.page_id should be defined, instead was \
+                     `undefined`.",
+                )),
+            };
+
+        self.page(parent)?;
+        self.check_writable(parent)?;
+
+        let titles = database
+            .properties
+            .values()
+            .filter(|property| matches!(property.data, DatabasePropertyData::Title(_)))
+            .count();
+
+        if titles != 1 {
+            return Err(ApiError::validation(
+                "Title is not provided, or more than one title property was provided.",
+            ));
+        }
+
+        if database
+            .properties
+            .values()
+            .any(|property| is_status(&property.data))
+        {
+            return Err(status_not_creatable());
+        }
+
+        for (name, property) in &mut database.properties {
+            property.name = Some(name.clone());
+            property.id.get_or_insert_with(|| match property.data {
+                DatabasePropertyData::Title(_) => PropertyId::from("title".to_owned()),
+                _ => PropertyId::from(Uuid::new_v4().simple().to_string()[..4].to_owned()),
+            });
+        }
+
+        let id = Uuid::new_v4();
+        let now = self.now();
+
+        database.id = Some(id.into());
+        database.created_time = Some(now);
+        database.last_edited_time = Some(now);
+        database.created_by = Some(self.partial_bot());
+        database.last_edited_by = Some(self.partial_bot());
+        database.archived = Some(false);
+        database.in_trash = Some(false);
+        database.is_inline.get_or_insert(false);
+        database.url = Some(url(id));
+
+        self.children.entry(parent).or_default().push(id);
+        self.touch(parent);
+
+        self.objects.push(id);
+        self.databases.insert(id, database.clone());
+
+        Ok(database)
+    }
+
+    pub fn retrieve_database(&self, id: Uuid) -> ApiResult<Database> {
+        self.database(id).cloned()
+    }
+
+    pub fn update_database(&mut self, id: Uuid, update: DatabaseUpdate) -> ApiResult<Database> {
+        let mut database = self.database(id)?.clone();
+
+        if database.archived == Some(true) && update.archived != Some(false) {
+            return Err(ApiError::archived());
+        }
+
+        if let Some(title) = update.title {
+            database.title = title;
+        }
+
+        if let Some(description) = update.description {
+            database.description = description;
+        }
+
+        if let Some(icon) = update.icon {
+            database.icon = Some(icon);
+        }
+
+        if let Some(cover) = update.cover {
+            database.cover = Some(cover);
+        }
+
+        for (name, mut property) in update.properties {
+            let existing = database.properties.get(&name);
+
+            if is_status(&property.data)
+                && !existing.map_or(false, |existing| is_status(&existing.data))
+            {
+                return Err(status_not_creatable());
+            }
+
+            let id = existing
+                .and_then(|existing| existing.id.clone())
+                .unwrap_or_else(|| {
+                    PropertyId::from(Uuid::new_v4().simple().to_string()[..4].to_owned())
+                });
+
+            property.name = Some(name.clone());
+            property.id = Some(id);
+
+            database.properties.insert(name, property);
+        }
+
+        if let Some(archived) = update.archived {
+            database.archived = Some(archived);
+        }
+
+        database.last_edited_time = Some(self.now());
+        database.last_edited_by = Some(self.partial_bot());
+
+        self.databases.insert(id, database.clone());
+
+        Ok(database)
+    }
+
+    /// Adds a property to the schema of a database, as the Notion app can,
+    /// including the types that the API can't create. Does nothing if there
+    /// is no such database.
+    pub fn add_database_property(&mut self, id: Uuid, name: &str, mut property: DatabaseProperty) {
+        let Some(database) = self.databases.get_mut(&id) else {
+            return;
+        };
+
+        property.name = Some(name.to_owned());
+        property.id.get_or_insert_with(|| {
+            PropertyId::from(Uuid::new_v4().simple().to_string()[..4].to_owned())
+        });
+
+        database.properties.insert(name.to_owned(), property);
+    }
+
+    pub fn query_database(&self, id: Uuid, query: &DatabaseQuery) -> ApiResult<List<Page>> {
+        let database = self.database(id)?;
+        let parent = Some(ParentData::DatabaseId {
+            database_id: id.into(),
+        });
+
+        for sort in &query.sorts {
+            if let Sort::Property { property, .. } = sort {
+                if !database.properties.contains_key(property) {
+                    return Err(ApiError::validation(format!(
+                        "Could not find sort property with name or id: {property}"
+                    )));
+                }
+            }
+        }
+
+        let mut pages = Vec::new();
+
+        for page in self.objects.iter().filter_map(|id| self.pages.get(id)) {
+            if page.parent != parent || page.archived == Some(true) {
+                continue;
+            }
+
+            match &query.filter {
+                Some(filter) if !filter::matches(filter, page)? => {},
+                _ => pages.push(page.clone()),
+            }
+        }
+
+        filter::sort(&mut pages, &query.sorts);
+
+        paginate(
+            pages,
+            |page| page.id.map(|id| id.to_string()),
+            query.start_cursor.as_deref(),
+            query.page_size,
+            ListData::PageOrDatabase,
+        )
+    }
+
+    /// A block, or a page or database as a child page or child database block.
+    pub fn retrieve_block(&self, id: Uuid) -> ApiResult<Block> {
+        let has_children = Some(self.has_children(id));
+
+        if let Some(block) = self.blocks.get(&id) {
+            return Ok(Block {
+                has_children,
+                ..block.clone()
+            });
+        }
+
+        if let Some(page) = self.pages.get(&id) {
+            return Ok(Block {
+                id: Some(id.into()),
+                parent: page.parent,
+                created_time: page.created_time,
+                last_edited_time: page.last_edited_time,
+                created_by: page.created_by,
+                last_edited_by: page.last_edited_by,
+                has_children,
+                archived: page.archived,
+                data: BlockData::ChildPage(ChildPage {
+                    title: page_title(page),
+                }),
+            });
+        }
+
+        if let Some(database) = self.databases.get(&id) {
+            return Ok(Block {
+                id: Some(id.into()),
+                parent: database.parent,
+                created_time: database.created_time,
+                last_edited_time: database.last_edited_time,
+                created_by: database.created_by,
+                last_edited_by: database.last_edited_by,
+                has_children,
+                archived: database.archived,
+                data: BlockData::ChildDatabase(ChildDatabase {
+                    title: plain_text(&database.title),
+                }),
+            });
+        }
+
+        Err(ApiError::not_found("block", id))
+    }
+
+    pub fn retrieve_block_children(
+        &self,
+        id: Uuid,
+        start_cursor: Option<&str>,
+        page_size: Option<u32>,
+    ) -> ApiResult<List<Block>> {
+        if !self.blocks.contains_key(&id) && !self.pages.contains_key(&id) {
+            return Err(ApiError::not_found("block", id));
+        }
+
+        let children = self
+            .children
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter(|child| self.archived(**child) == Some(false))
+            .map(|child| self.retrieve_block(*child))
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        paginate(
+            children,
+            |block| block.id.map(|id| id.to_string()),
+            start_cursor,
+            page_size,
+            ListData::Block,
+        )
+    }
+
+    /// Appends blocks to the end of the children of a page or block, or after
+    /// one of its children.
+    pub fn append_block_children(
+        &mut self,
+        id: Uuid,
+        children: Vec<Block>,
+        after: Option<Uuid>,
+    ) -> ApiResult<List<Block>> {
+        self.check_writable(id)?;
+
+        let mut position = match after {
+            Some(after) => Some(
+                self.children
+                    .get(&id)
+                    .and_then(|children| children.iter().position(|child| *child == after))
+                    .map(|i| i + 1)
+                    .ok_or_else(|| {
+                        ApiError::validation(format!(
+                            "body failed validation: body.after `{after}` is not a child of the \
+                             block."
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+
+        if children.len() > PAGE_SIZE as usize {
+            return Err(ApiError::validation(format!(
+                "body failed validation: body.children.length should be ≤ `{PAGE_SIZE}`, instead \
+                 was `{}`.",
+                children.len()
+            )));
+        }
+
+        if let Some(child) = children.iter().find(|child| !creatable(&child.data)) {
+            return Err(ApiError::validation(format!(
+                "body failed validation: body.children[0].{} should not be present.",
+                type_name(&child.data)
+            )));
+        }
+
+        let mut results = Vec::new();
+
+        for child in children {
+            results.push(self.insert_block(id, child, position)?);
+            position = position.map(|position| position + 1);
+        }
+
+        self.touch(id);
+
+        Ok(List {
+            has_more: false,
+            next_cursor: None,
+            results,
+            data: ListData::Block,
+        })
+    }
+
+    /// Adds a block to the children of a page or block, at a position or at
+    /// the end. Children nested in the block are added as children of the new
+    /// block.
+    fn insert_block(
+        &mut self,
+        parent: Uuid,
+        mut block: Block,
+        position: Option<usize>,
+    ) -> ApiResult<Block> {
+        let (data, children) = take_children(&block.data)?;
+
+        let id = Uuid::new_v4();
+        let now = self.now();
+
+        block.id = Some(id.into());
+        block.parent = Some(self.parent_of(parent));
+        block.created_time = Some(now);
+        block.last_edited_time = Some(now);
+        block.created_by = Some(self.partial_bot());
+        block.last_edited_by = Some(self.partial_bot());
+        block.has_children = Some(false);
+        block.archived = Some(false);
+        block.data = data;
+
+        self.blocks.insert(id, block);
+
+        let siblings = self.children.entry(parent).or_default();
+        match position {
+            Some(position) => siblings.insert(position, id),
+            None => siblings.push(id),
+        }
+
+        for child in children {
+            self.insert_block(id, child, None)?;
+        }
+
+        self.retrieve_block(id)
+    }
+
+    /// Updates a block. The fields of the block type object that are given
+    /// replace the current ones.
+    ///
+    /// Pages and databases can also be archived and restored.
+    pub fn update_block(&mut self, id: Uuid, body: Map<String, Value>) -> ApiResult<Block> {
+        let archived = match body.get("archived") {
+            None | Some(Value::Null) => None,
+            Some(Value::Bool(archived)) => Some(*archived),
+            Some(_) => {
+                return Err(ApiError::validation(
+                    "body failed validation: body.archived should be a boolean.",
+                ))
+            },
+        };
+
+        let current = self
+            .archived(id)
+            .ok_or_else(|| ApiError::not_found("block", id))?;
+
+        if current && archived != Some(false) {
+            return Err(ApiError::archived());
+        }
+
+        let Some(mut block) = self.blocks.get(&id).cloned() else {
+            return self.set_archived(id, archived);
+        };
+
+        let type_name = type_name(&block.data);
+        let mut data = serde_json::to_value(&block.data).map_err(validation)?;
+
+        for (key, value) in body {
+            if key == "archived" {
+                continue;
+            }
+
+            match (data.get_mut(&type_name), value) {
+                (Some(Value::Object(fields)), Value::Object(update)) if key == type_name => {
+                    fields.extend(update);
+                },
+                _ => {
+                    return Err(ApiError::validation(format!(
+                        "body failed validation: body.{key} should be not present."
+                    )))
+                },
+            }
+        }
+
+        block.data = serde_json::from_value(data).map_err(validation)?;
+
+        self.blocks.insert(id, block);
+
+        self.set_archived(id, archived)
+    }
+
+    pub fn delete_block(&mut self, id: Uuid) -> ApiResult<Block> {
+        match self.archived(id) {
+            Some(true) => Err(ApiError::archived()),
+            Some(false) => self.set_archived(id, Some(true)),
+            None => Err(ApiError::not_found("block", id)),
+        }
+    }
+
+    fn set_archived(&mut self, id: Uuid, archived: Option<bool>) -> ApiResult<Block> {
+        if let Some(block) = self.blocks.get_mut(&id) {
+            block.archived = archived.or(block.archived);
+            self.touch(id);
+
+            return self.retrieve_block(id);
+        }
+
+        let now = self.now();
+        let bot = self.partial_bot();
+
+        if let Some(page) = self.pages.get_mut(&id) {
+            page.archived = archived.or(page.archived);
+            page.last_edited_time = Some(now);
+            page.last_edited_by = Some(bot);
+        } else if let Some(database) = self.databases.get_mut(&id) {
+            database.archived = archived.or(database.archived);
+            database.last_edited_time = Some(now);
+            database.last_edited_by = Some(bot);
+        }
+
+        self.retrieve_block(id)
+    }
+
+    pub fn list_users(
+        &self,
+        start_cursor: Option<&str>,
+        page_size: Option<u32>,
+    ) -> ApiResult<List<User>> {
+        paginate(
+            self.users.clone(),
+            |user| Some(user.id.to_string()),
+            start_cursor,
+            page_size,
+            ListData::User,
+        )
+    }
+
+    pub fn retrieve_user(&self, id: Uuid) -> ApiResult<User> {
+        self.users
+            .iter()
+            .find(|user| *user.id.into_inner() == id)
+            .cloned()
+            .ok_or_else(|| ApiError::not_found("user", id))
+    }
+
+    pub fn search(&self, query: &SearchQuery) -> ApiResult<List<PageOrDatabase>> {
+        let text = query.query.as_deref().unwrap_or_default().to_lowercase();
+
+        let mut results = self
+            .objects
+            .iter()
+            .filter_map(|id| match (self.pages.get(id), self.databases.get(id)) {
+                (Some(page), _) => Some(PageOrDatabase::Page(page.clone())),
+                (_, Some(database)) => Some(PageOrDatabase::Database(database.clone())),
+                _ => None,
+            })
+            .filter(|result| match query.filter {
+                Some(SearchFilter::Object {
+                    value: SearchObject::Page,
+                }) => matches!(result, PageOrDatabase::Page(_)),
+                Some(SearchFilter::Object {
+                    value: SearchObject::Database,
+                }) => matches!(result, PageOrDatabase::Database(_)),
+                None => true,
+            })
+            .filter(|result| {
+                let (archived, title) = match result {
+                    PageOrDatabase::Page(page) => (page.archived, page_title(page)),
+                    PageOrDatabase::Database(database) => {
+                        (database.archived, plain_text(&database.title))
+                    },
+                };
+
+                archived != Some(true) && title.to_lowercase().contains(&text)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(sort) = query.sort {
+            if sort.timestamp != SortTimestamp::LastEditedTime {
+                return Err(ApiError::validation(
+                    "body failed validation: body.sort.timestamp should be `\"last_edited_time\"`.",
+                ));
+            }
+
+            results.sort_by(|a, b| {
+                let ordering = last_edited_time(a).cmp(&last_edited_time(b));
+                match sort.direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+
+        paginate(
+            results,
+            |result| match result {
+                PageOrDatabase::Page(page) => page.id.map(|id| id.to_string()),
+                PageOrDatabase::Database(database) => database.id.map(|id| id.to_string()),
+            },
+            query.start_cursor.as_deref(),
+            query.page_size,
+            ListData::PageOrDatabase,
+        )
+    }
+
+    pub fn create_comment(&mut self, mut comment: Comment) -> ApiResult<Comment> {
+        let (parent, discussion_id) =
+            match (comment.parent, comment.discussion_id) {
+                (Some(ParentData::PageId { page_id }), None) => {
+                    self.page(*page_id.into_inner())?;
+                    (
+                        ParentData::from(page_id),
+                        DiscussionId::from(Uuid::new_v4()),
+                    )
+                },
+                (None, Some(discussion_id)) => {
+                    let parent = self
+                        .comments
+                        .iter()
+                        .find(|comment| comment.discussion_id == Some(discussion_id))
+                        .and_then(|comment| comment.parent)
+                        .ok_or_else(|| ApiError::not_found("discussion", discussion_id))?;
+                    (parent, discussion_id)
+                },
+                (Some(_), None) => return Err(ApiError::validation(
+                    "body failed validation: body.parent.page_id should be defined, instead was \
+                     `undefined`.",
+                )),
+                _ => return Err(ApiError::validation(
+                    "body failed validation. Fix one: body.parent should be defined, instead was \
+                     `undefined`. body.discussion_id should be defined, instead was `undefined`.",
+                )),
+            };
+
+        let now = self.now();
+
+        comment.id = Some(Uuid::new_v4().into());
+        comment.parent = Some(parent);
+        comment.discussion_id = Some(discussion_id);
+        comment.created_time = Some(now);
+        comment.last_edited_time = Some(now);
+        comment.created_by = Some(self.partial_bot());
+
+        self.comments.push(comment.clone());
+
+        Ok(comment)
+    }
+
+    pub fn retrieve_comments(
+        &self,
+        block_id: Uuid,
+        start_cursor: Option<&str>,
+        page_size: Option<u32>,
+    ) -> ApiResult<List<Comment>> {
+        self.retrieve_block(block_id)?;
+
+        let comments = self
+            .comments
+            .iter()
+            .filter(|comment| comment.parent.as_ref().and_then(parent_id) == Some(block_id))
+            .cloned()
+            .collect();
+
+        paginate(
+            comments,
+            |comment| comment.id.map(|id| id.to_string()),
+            start_cursor,
+            page_size,
+            ListData::Comment,
+        )
+    }
+}
+
+/// Returns the page of `items` that starts at the item with the ID
+/// `start_cursor`.
+fn paginate<T>(
+    items: Vec<T>,
+    id: impl Fn(&T) -> Option<String>,
+    start_cursor: Option<&str>,
+    page_size: Option<u32>,
+    data: ListData,
+) -> ApiResult<List<T>> {
+    let page_size = match page_size.unwrap_or(PAGE_SIZE) {
+        size @ 1..=PAGE_SIZE => size as usize,
+        size => {
+            return Err(ApiError::validation(format!(
+                "body failed validation: body.page_size should be a number between 1 and \
+                 {PAGE_SIZE}, instead was `{size}`."
+            )))
+        },
+    };
+
+    let start = match start_cursor {
+        Some(cursor) => items
+            .iter()
+            .position(|item| id(item).as_deref() == Some(cursor))
+            .ok_or_else(|| {
+                ApiError::validation(format!("start_cursor provided is invalid: {cursor}"))
+            })?,
+        None => 0,
+    };
+
+    let mut results = items.into_iter().skip(start).collect::<Vec<_>>();
+    let rest = results.split_off(page_size.min(results.len()));
+    let next_cursor = rest.first().and_then(id);
+
+    Ok(List {
+        has_more: next_cursor.is_some(),
+        next_cursor,
+        results,
+        data,
+    })
+}
+
+fn validation(error: serde_json::Error) -> ApiError {
+    ApiError::validation(format!("body failed validation: {error}"))
+}
+
+fn url(id: Uuid) -> String {
+    format!("https://www.notion.so/{}", id.simple())
+}
+
+fn parent_id(parent: &ParentData) -> Option<Uuid> {
+    match parent {
+        ParentData::DatabaseId { database_id } => Some(*database_id.into_inner()),
+        ParentData::PageId { page_id } => Some(*page_id.into_inner()),
+        ParentData::BlockId { block_id } => Some(*block_id.into_inner()),
+        ParentData::Workspace { .. } => None,
+    }
+}
+
+/// The name of the type of an externally tagged enum, such as `paragraph` for
+/// a paragraph block.
+fn type_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(object)) => object.keys().next().cloned().unwrap_or_default(),
+        Ok(Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn plain_text<T: Serialize>(rich_text: &T) -> String {
+    serde_json::to_value(rich_text)
+        .map(|value| filter::plain_text(&value))
+        .unwrap_or_default()
+}
+
+fn page_title(page: &Page) -> String {
+    page.properties
+        .values()
+        .find_map(|property| match &property.data {
+            PropertyData::Title(title) => Some(plain_text(title)),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn last_edited_time(result: &PageOrDatabase) -> Option<DateTime<chrono::FixedOffset>> {
+    match result {
+        PageOrDatabase::Page(page) => page.last_edited_time.as_ref(),
+        PageOrDatabase::Database(database) => database.last_edited_time.as_ref(),
+    }
+    .map(filter::timestamp_of)
+}
+
+/// Whether blocks of this type can be created with the append block children
+/// endpoint.
+const fn is_status(data: &DatabasePropertyData) -> bool {
+    matches!(data, DatabasePropertyData::Status(_))
+}
+
+/// Status properties can only be created in the Notion app.
+fn status_not_creatable() -> ApiError {
+    ApiError::validation("Status properties cannot be created through the API.")
+}
+
+const fn creatable(data: &BlockData) -> bool {
+    !matches!(
+        data,
+        BlockData::ChildPage(_)
+            | BlockData::ChildDatabase(_)
+            | BlockData::Unsupported(_)
+            | BlockData::Unknown { .. }
+    )
+}
+
+/// Splits the nested children out of the block type object, as they are
+/// stored as separate blocks.
+fn take_children(data: &BlockData) -> ApiResult<(BlockData, Vec<Block>)> {
+    let mut value = serde_json::to_value(data).map_err(validation)?;
+
+    let children = value
+        .as_object_mut()
+        .and_then(|object| object.values_mut().next())
+        .and_then(Value::as_object_mut)
+        .and_then(|fields| fields.remove("children"));
+
+    let children = match children {
+        Some(Value::Null) | None => Vec::new(),
+        Some(children) => serde_json::from_value(children).map_err(validation)?,
+    };
+
+    Ok((serde_json::from_value(value).map_err(validation)?, children))
+}
@@ -116,9 +116,17 @@ macro_rules! string_id {
 
 uuid_id! {
     BlockId;
+    CommentId;
+    CustomEmojiId;
     DatabaseId;
+    DiscussionId;
+    EventId;
+    FileUploadId;
+    IntegrationId;
     PageId;
+    SubscriptionId;
     UserId;
+    ViewId;
 }
 
 string_id! {
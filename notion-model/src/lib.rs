@@ -61,5 +61,11 @@
 
 pub mod constants;
 pub mod ids;
+pub mod markup;
+pub mod oauth;
 pub mod objects;
 pub mod pagination;
+pub mod query;
+pub mod search;
+mod unknown;
+pub mod webhooks;
@@ -0,0 +1,715 @@
+//! # Rich text markup
+//!
+//! A lightweight inline markup for writing rich text, which [`parse`] turns
+//! into rich text objects, and [`render`] turns back into markup.
+//!
+//! | Markup                         | Rich text                             |
+//! | ------------------------------ | ------------------------------------- |
+//! | `**bold**`                     | bold                                  |
+//! | `_italic_`                     | italic                                |
+//! | `__underline__`                | underline                             |
+//! | `~~strike~~`                   | strikethrough                         |
+//! | `` `code` ``                   | code                                  |
+//! | `{red}colored{/}`              | a [`Color`], such as `blue_background` |
+//! | `[text](https://example.com)`  | a link                                |
+//! | `$e = mc^2$`                   | an inline equation                    |
+//! | `@[Title](<page id>)`          | a page mention                        |
+//! | `@[Name](user:<user id>)`      | a user mention                        |
+//!
+//! Other mentions are written like user mentions, with targets of
+//! `database:<database id>`, `date:<start>[/<end>][ <time zone>]`,
+//! `link_preview:<url>`, `template_date:<today or now>` and
+//! `template_user:me`. The text in the brackets of a mention is its plain
+//! text.
+//!
+//! Formatting markers toggle their formatting, so they can overlap, as in
+//! `**a _b** c_`. A backslash escapes the next character. Code, equations,
+//! mention text and URLs are taken as they are, except that a backslash
+//! escapes the closing delimiter or another backslash.
+//!
+//! Parsing rendered rich text gives back the same rich text, except that:
+//!
+//! - `href`, and the `plain_text` of text and equations, which the API fills
+//!   in, are dropped;
+//! - adjacent text with the same formatting and link is merged, and empty text
+//!   is dropped;
+//! - code formatting on mentions and equations is dropped;
+//! - unknown rich text and mention types are rendered as their plain text.
+//!
+//! ```
+//! use notion_model::markup;
+//!
+//! let rich_text = markup::parse("**Weekly report** for {blue}May{/}").unwrap();
+//!
+//! assert_eq!(rich_text.len(), 3);
+//! assert_eq!(
+//!     markup::render(&rich_text),
+//!     "**Weekly report** for {blue}May{/}"
+//! );
+//! ```
+
+use std::{iter::Peekable, str::Chars};
+
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+
+use crate::objects::{
+    color::Color,
+    rich_text::{
+        Annotations, DatabaseMention, DateMention, Equation, Link, LinkPreviewMention, Mention,
+        PageMention, RichText, RichTextData, TemplateMention, Text, UserMention,
+    },
+};
+
+/// Parses markup into rich text.
+///
+/// # Errors
+///
+/// Returns an error if the markup is malformed, such as formatting that isn't
+/// closed, an unknown color, or a mention of an invalid ID.
+pub fn parse(markup: &str) -> Result<Vec<RichText>> {
+    Parser {
+        chars: markup.chars().peekable(),
+        annotations: Annotations::default(),
+        link: None,
+        text: String::new(),
+        rich_text: Vec::new(),
+    }
+    .parse()
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// The formatting that is toggled on.
+    annotations: Annotations,
+    /// The index of the first rich text in the link being parsed.
+    link: Option<usize>,
+    /// Text that hasn't been pushed yet.
+    text: String,
+    rich_text: Vec<RichText>,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Vec<RichText>> {
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\\' => {
+                    let c = self.chars.next().context("`\\` at the end of the markup")?;
+                    self.text.push(c);
+                },
+                '*' if self.next_is('*') => self.toggle(|a| &mut a.bold),
+                '~' if self.next_is('~') => self.toggle(|a| &mut a.strikethrough),
+                '_' if self.next_is('_') => self.toggle(|a| &mut a.underline),
+                '_' => self.toggle(|a| &mut a.italic),
+                '`' => {
+                    let code = self.literal('`', "code")?;
+                    let mut annotations = self.annotations;
+                    annotations.code = true;
+
+                    self.push(RichText::new_text(&code).annotations(Some(annotations)));
+                },
+                '$' => {
+                    let expression = self.literal('$', "equation")?;
+                    self.push(RichText::new_equation(&expression));
+                },
+                '{' => self.color()?,
+                '[' => {
+                    if self.link.is_some() {
+                        anyhow::bail!("a link inside a link");
+                    }
+
+                    self.flush();
+                    self.link = Some(self.rich_text.len());
+                },
+                ']' if self.link.is_some() => self.close_link()?,
+                '@' if self.next_is('[') => self.mention()?,
+                c => self.text.push(c),
+            }
+        }
+
+        self.flush();
+
+        if self.link.is_some() {
+            anyhow::bail!("a link that isn't closed with `](url)`");
+        }
+
+        let open = [
+            (self.annotations.bold, "**"),
+            (self.annotations.italic, "_"),
+            (self.annotations.underline, "__"),
+            (self.annotations.strikethrough, "~~"),
+            (!self.annotations.color.is_default(), "{/}"),
+        ]
+        .into_iter()
+        .filter(|(open, _)| *open)
+        .map(|(_, marker)| format!("`{marker}`"))
+        .collect::<Vec<_>>();
+
+        if !open.is_empty() {
+            anyhow::bail!("formatting that isn't closed with {}", open.join(", "));
+        }
+
+        Ok(self.rich_text)
+    }
+
+    /// Consumes the next character if it's `c`.
+    fn next_is(&mut self, c: char) -> bool {
+        self.chars.next_if_eq(&c).is_some()
+    }
+
+    fn toggle(&mut self, flag: fn(&mut Annotations) -> &mut bool) {
+        self.flush();
+
+        let flag = flag(&mut self.annotations);
+        *flag = !*flag;
+    }
+
+    /// Reads up to `close`, taking a backslash to escape the next character.
+    fn literal(&mut self, close: char, what: &str) -> Result<String> {
+        let mut literal = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some(c) if c == close => return Ok(literal),
+                Some('\\') => literal.push(
+                    self.chars
+                        .next()
+                        .with_context(|| format!("`\\` at the end of the {what}"))?,
+                ),
+                Some(c) => literal.push(c),
+                None => anyhow::bail!("{what} that isn't closed with `{close}`"),
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char, after: &str) -> Result<()> {
+        if !self.next_is(c) {
+            anyhow::bail!("expected `{c}` after {after}");
+        }
+
+        Ok(())
+    }
+
+    /// The current formatting of rich text, which is left out if it's the
+    /// default.
+    fn annotations(&self) -> Option<Annotations> {
+        (self.annotations != Annotations::default()).then_some(self.annotations)
+    }
+
+    /// Pushes rich text, keeping its annotations if it has any.
+    fn push(&mut self, rich_text: RichText) {
+        self.flush();
+
+        let annotations = rich_text.annotations.or_else(|| self.annotations());
+        self.rich_text.push(rich_text.annotations(annotations));
+    }
+
+    /// Pushes the text that has been read so far.
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let text = RichText::new_text(&std::mem::take(&mut self.text));
+        self.rich_text.push(text.annotations(self.annotations()));
+    }
+
+    fn color(&mut self) -> Result<()> {
+        let name = self.literal('}', "color")?;
+
+        self.flush();
+
+        if name == "/" {
+            if self.annotations.color.is_default() {
+                anyhow::bail!("`{{/}}` without a color to close");
+            }
+
+            self.annotations.color = Color::default();
+            return Ok(());
+        }
+
+        if !self.annotations.color.is_default() {
+            anyhow::bail!("`{{{name}}}` inside another color, which must be closed with `{{/}}`");
+        }
+
+        self.annotations.color = serde_json::from_value(name.clone().into())
+            .with_context(|| format!("unknown color `{name}`"))?;
+
+        Ok(())
+    }
+
+    fn close_link(&mut self) -> Result<()> {
+        self.flush();
+        self.expect('(', "the text of a link")?;
+
+        let url = self.literal(')', "link")?;
+        let start = self.link.take().unwrap_or_default();
+
+        if start == self.rich_text.len() {
+            anyhow::bail!("a link without text");
+        }
+
+        for rich_text in &mut self.rich_text[start..] {
+            match &mut rich_text.data {
+                RichTextData::Text(text) => text.link = Some(Link { url: url.clone() }),
+                _ => anyhow::bail!("a mention or equation inside a link"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn mention(&mut self) -> Result<()> {
+        let plain_text = self.literal(']', "mention")?;
+        self.expect('(', "the text of a mention")?;
+        let target = self.literal(')', "mention")?;
+
+        let mut mention = RichText::new_mention(parse_mention(&target)?);
+        mention.plain_text(Some(plain_text));
+
+        self.push(mention);
+
+        Ok(())
+    }
+}
+
+fn parse_mention(target: &str) -> Result<Mention> {
+    let Some((kind, value)) = target.split_once(':') else {
+        return parse_mention(&format!("page:{target}"));
+    };
+
+    let mention = match kind {
+        "page" => PageMention::new(
+            value
+                .parse()
+                .with_context(|| format!("invalid page ID `{value}`"))?,
+        )
+        .build(),
+        "database" => DatabaseMention::new(
+            value
+                .parse()
+                .with_context(|| format!("invalid database ID `{value}`"))?,
+        )
+        .build(),
+        "user" => UserMention::new(
+            value
+                .parse()
+                .with_context(|| format!("invalid user ID `{value}`"))?,
+        )
+        .build(),
+        "date" => {
+            let (dates, timezone) = match value.split_once(' ') {
+                Some((dates, timezone)) => (dates, Some(timezone)),
+                None => (value, None),
+            };
+            let (start, end) = match dates.split_once('/') {
+                Some((start, end)) => (start, Some(end)),
+                None => (dates, None),
+            };
+
+            DateMention::new(start.parse()?)
+                .end(end.map(str::parse).transpose()?)
+                .timezone(
+                    timezone
+                        .map(|timezone| {
+                            timezone
+                                .parse::<Tz>()
+                                .map_err(|e| anyhow::anyhow!("invalid time zone `{timezone}`: {e}"))
+                        })
+                        .transpose()?,
+                )
+                .build()
+        },
+        "link_preview" => Mention::LinkPreview {
+            link_preview: LinkPreviewMention {
+                url: value.to_owned(),
+            },
+        },
+        "template_date" => Mention::Template {
+            template: TemplateMention::Date(value.to_owned()),
+        },
+        "template_user" => Mention::Template {
+            template: TemplateMention::User(value.to_owned()),
+        },
+        _ => anyhow::bail!("unknown mention type `{kind}`"),
+    };
+
+    Ok(mention)
+}
+
+/// Renders rich text as markup.
+pub fn render(rich_text: &[RichText]) -> String {
+    let mut markup = String::new();
+    let mut current = Annotations::default();
+    let mut link = None;
+
+    for rich_text in rich_text {
+        let url = match &rich_text.data {
+            RichTextData::Text(Text {
+                link: Some(link), ..
+            }) => Some(link.url.as_str()),
+            _ => None,
+        };
+
+        let annotations = rich_text.annotations.unwrap_or_default();
+        close_annotations(&mut markup, &mut current, annotations);
+
+        if url != link {
+            close_link(&mut markup, link);
+
+            if url.is_some() {
+                markup.push('[');
+            }
+
+            link = url;
+        }
+
+        open_annotations(&mut markup, &mut current, annotations);
+
+        match &rich_text.data {
+            RichTextData::Text(text) if annotations.code => {
+                markup.push('`');
+                escape_literal(&mut markup, &text.content, '`');
+                markup.push('`');
+            },
+            RichTextData::Text(text) => escape(&mut markup, &text.content),
+            RichTextData::Equation(Equation { expression }) => {
+                markup.push('$');
+                escape_literal(&mut markup, expression, '$');
+                markup.push('$');
+            },
+            RichTextData::Mention(mention) => {
+                let plain_text = rich_text.plain_text.as_deref().unwrap_or_default();
+
+                match render_mention(mention) {
+                    Some(target) => {
+                        markup.push_str("@[");
+                        escape_literal(&mut markup, plain_text, ']');
+                        markup.push_str("](");
+                        escape_literal(&mut markup, &target, ')');
+                        markup.push(')');
+                    },
+                    None => escape(&mut markup, plain_text),
+                }
+            },
+            RichTextData::Unknown { .. } => {
+                escape(
+                    &mut markup,
+                    rich_text.plain_text.as_deref().unwrap_or_default(),
+                );
+            },
+        }
+    }
+
+    close_annotations(&mut markup, &mut current, Annotations::default());
+    close_link(&mut markup, link);
+
+    markup
+}
+
+fn render_mention(mention: &Mention) -> Option<String> {
+    let target = match mention {
+        Mention::Page { page } => page.id.to_string(),
+        Mention::Database { database } => format!("database:{}", database.id),
+        Mention::User { user } => format!("user:{}", user.id),
+        Mention::Date { date } => {
+            let mut target = format!("date:{}", date.start.to_string());
+
+            if let Some(end) = date.end {
+                target.push('/');
+                target.push_str(&end.to_string());
+            }
+
+            if let Some(timezone) = date.timezone {
+                target.push(' ');
+                target.push_str(timezone.name());
+            }
+
+            target
+        },
+        Mention::LinkPreview { link_preview } => format!("link_preview:{}", link_preview.url),
+        Mention::Template {
+            template: TemplateMention::Date(date),
+        } => format!("template_date:{date}"),
+        Mention::Template {
+            template: TemplateMention::User(user),
+        } => format!("template_user:{user}"),
+        Mention::Unknown { .. } => return None,
+    };
+
+    Some(target)
+}
+
+fn close_link(markup: &mut String, link: Option<&str>) {
+    if let Some(url) = link {
+        markup.push_str("](");
+        escape_literal(markup, url, ')');
+        markup.push(')');
+    }
+}
+
+/// The formatting markers, in the order they're opened.
+const fn markers(annotations: &Annotations) -> [(bool, &'static str); 4] {
+    [
+        (annotations.bold, "**"),
+        (annotations.italic, "_"),
+        (annotations.underline, "__"),
+        (annotations.strikethrough, "~~"),
+    ]
+}
+
+/// Writes the markers that close the formatting of `current` that isn't in
+/// `target`, innermost first.
+fn close_annotations(markup: &mut String, current: &mut Annotations, target: Annotations) {
+    if current.color != target.color && !current.color.is_default() {
+        markup.push_str("{/}");
+        current.color = Color::default();
+    }
+
+    for ((on, marker), (wanted, _)) in markers(current).into_iter().zip(markers(&target)).rev() {
+        if on && !wanted {
+            markup.push_str(marker);
+        }
+    }
+
+    *current = Annotations {
+        bold: current.bold && target.bold,
+        italic: current.italic && target.italic,
+        underline: current.underline && target.underline,
+        strikethrough: current.strikethrough && target.strikethrough,
+        ..*current
+    };
+}
+
+/// Writes the markers that open the formatting of `target` that isn't in
+/// `current`. Code is written around each piece of code instead.
+fn open_annotations(markup: &mut String, current: &mut Annotations, target: Annotations) {
+    for ((on, marker), (wanted, _)) in markers(current).into_iter().zip(markers(&target)) {
+        if wanted && !on {
+            markup.push_str(marker);
+        }
+    }
+
+    if current.color != target.color {
+        let name = serde_json::to_value(target.color).unwrap_or_default();
+        markup.push('{');
+        markup.push_str(name.as_str().unwrap_or_default());
+        markup.push('}');
+    }
+
+    *current = Annotations {
+        code: false,
+        ..target
+    };
+}
+
+/// Writes text, escaping the characters that are markup.
+fn escape(markup: &mut String, text: &str) {
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '~' | '`' | '$' | '{' | '[' | ']' | '@'
+        ) {
+            markup.push('\\');
+        }
+
+        markup.push(c);
+    }
+}
+
+/// Writes literal text that ends at `close`.
+fn escape_literal(markup: &mut String, text: &str, close: char) {
+    for c in text.chars() {
+        if c == '\\' || c == close {
+            markup.push('\\');
+        }
+
+        markup.push(c);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ids::{PageId, UserId};
+
+    const PAGE_ID: &str = "b55c9c91-384d-452b-81db-d1ef79372b75";
+    const USER_ID: &str = "e79a0b74-3aba-4149-9f74-0bb5791a6ee6";
+
+    fn text(content: &str) -> RichText {
+        RichText::new_text(content)
+    }
+
+    /// Checks that markup parses to rich text, and renders back unchanged.
+    fn round_trip(markup: &str, rich_text: &[RichText]) {
+        let parsed = parse(markup).unwrap();
+        assert_eq!(parsed, rich_text);
+        assert_eq!(render(&parsed), markup);
+    }
+
+    #[test]
+    fn formatting() {
+        round_trip(
+            "plain **bold** _italic_ __underline__ ~~strike~~ `a_b*c`",
+            &[
+                text("plain "),
+                text("bold").bold(true),
+                text(" "),
+                text("italic").italic(true),
+                text(" "),
+                text("underline").underline(true),
+                text(" "),
+                text("strike").strikethrough(true),
+                text(" "),
+                text("a_b*c").code(true),
+            ],
+        );
+    }
+
+    #[test]
+    fn overlapping() {
+        round_trip(
+            "**a _b_** ___c___",
+            &[
+                text("a ").bold(true),
+                text("b").bold(true).italic(true),
+                text(" "),
+                text("c").italic(true).underline(true),
+            ],
+        );
+
+        assert_eq!(
+            parse("**a _b** c_").unwrap(),
+            [
+                text("a ").bold(true),
+                text("b").bold(true).italic(true),
+                text(" c").italic(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn color() {
+        round_trip(
+            "{red}colored **bold{/}** {blue_background}x{/}",
+            &[
+                text("colored ").color(Color::Red),
+                text("bold").color(Color::Red).bold(true),
+                text(" "),
+                text("x").color(Color::BlueBackground),
+            ],
+        );
+    }
+
+    #[test]
+    fn link() {
+        let link = |rich_text: RichText, url: &str| {
+            let RichTextData::Text(text) = rich_text.data.clone() else {
+                unreachable!()
+            };
+
+            rich_text.text(Text {
+                link: Some(Link {
+                    url: url.to_owned(),
+                }),
+                ..text
+            })
+        };
+
+        round_trip(
+            "see [the **docs**](https://example.com/a_(b\\)) now",
+            &[
+                text("see "),
+                link(text("the "), "https://example.com/a_(b)"),
+                link(text("docs").bold(true), "https://example.com/a_(b)"),
+                text(" now"),
+            ],
+        );
+    }
+
+    #[test]
+    fn equation() {
+        round_trip(
+            "_$e = mc^2 \\$$_",
+            &[RichText::new_equation("e = mc^2 $").italic(true)],
+        );
+    }
+
+    #[test]
+    fn mentions() {
+        let mut page =
+            RichText::new_mention(PageMention::new(PageId::from_str_unchecked(PAGE_ID)).build());
+        page.plain_text(Some("Roadmap [Q3]".to_owned()));
+
+        let mut user =
+            RichText::new_mention(UserMention::new(UserId::from_str_unchecked(USER_ID)).build())
+                .bold(true);
+        user.plain_text(Some("Ada".to_owned()));
+
+        let mut date = RichText::new_mention(
+            DateMention::new("2024-05-01T09:00:00+00:00".parse().unwrap())
+                .end(Some("2024-05-01T10:00:00+00:00".parse().unwrap()))
+                .timezone(Some(Tz::America__New_York))
+                .build(),
+        );
+        date.plain_text(Some("May 1".to_owned()));
+
+        round_trip(
+            &format!(
+                "@[Roadmap [Q3\\]]({PAGE_ID}) by **@[Ada](user:{USER_ID})** on \
+                 @[May 1](date:2024-05-01T09:00:00+00:00/2024-05-01T10:00:00+00:00 America/New_York)"
+            ),
+            &[page, text(" by "), user, text(" on "), date],
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        round_trip(
+            "\\*\\*not bold\\*\\* a\\_b \\$5 \\{x} \\[y\\] \\@z 2 \\\\ 3",
+            &[text("**not bold** a_b $5 {x} [y] @z 2 \\ 3")],
+        );
+
+        // single markers that aren't formatting
+        assert_eq!(parse("2 * 3 ~ 4").unwrap(), [text("2 * 3 ~ 4")]);
+    }
+
+    #[test]
+    fn render_builders() {
+        let rich_text = [
+            text("Hello ").bold(true).color(Color::Green),
+            text("world").bold(true).italic(true),
+            text("!"),
+        ];
+
+        let markup = render(&rich_text);
+        assert_eq!(markup, "**{green}Hello {/}_world_**!");
+        assert_eq!(parse(&markup).unwrap(), rich_text);
+    }
+
+    #[test]
+    fn errors() {
+        for markup in [
+            "**open",
+            "_open",
+            "{red}open",
+            "{/}",
+            "{red}{blue}x{/}",
+            "{purple_rain}x{/}",
+            "`open",
+            "$open",
+            "[open",
+            "[a [b](c)](d)",
+            "[a](open",
+            "[a] b",
+            "[](https://example.com)",
+            "[@[a](user:e79a0b74-3aba-4149-9f74-0bb5791a6ee6)](https://example.com)",
+            "@[a](user:nope)",
+            "@[a](place:here)",
+            "@[a](date:tomorrow)",
+            "trailing \\",
+        ] {
+            assert!(parse(markup).is_err(), "{markup}");
+        }
+    }
+}
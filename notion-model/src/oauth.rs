@@ -0,0 +1,183 @@
+//! # Authorization
+//!
+//! Public integrations use OAuth 2.0 to get an access token for each workspace
+//! that installs them. The user is sent to the authorization URL, and Notion
+//! redirects them to the integration's redirect URI with a temporary `code`,
+//! which is exchanged for an access token.
+//!
+//! see <https://developers.notion.com/docs/authorization>
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ids::{PageId, UserId, WorkspaceId},
+    objects::user::BotOwner,
+};
+
+/// Body of a request to the token endpoint.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+pub enum TokenRequest {
+    /// Exchange the code received on the redirect URI for an access token.
+    AuthorizationCode {
+        code: String,
+        /// Required if there are more than one redirect URI configured, or if
+        /// one was included in the authorization URL.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        redirect_uri: Option<String>,
+    },
+    /// Get a new access token from a refresh token.
+    RefreshToken { refresh_token: String },
+}
+
+/// # Token
+///
+/// An access token and information about the workspace and bot it gives access
+/// to.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct Token {
+    /// An access token used to authorize requests to the Notion API.
+    pub access_token: String,
+    /// Always `bearer`.
+    pub token_type: String,
+    /// A token used to get a new access token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// An identifier for this authorization.
+    pub bot_id: UserId,
+    /// The ID of the workspace where this authorization took place.
+    pub workspace_id: WorkspaceId,
+    /// A human-readable name that can be used to display this authorization in
+    /// the UI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    /// A URL to an image that can be used to display this authorization in the
+    /// UI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_icon: Option<String>,
+    /// Who can view and share this integration.
+    pub owner: BotOwner,
+    /// The ID of the new page created in the user's workspace, if the
+    /// integration has a template and the user chose to duplicate it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicated_template_id: Option<PageId>,
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &"[redacted]")
+            .field("token_type", &self.token_type)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[redacted]"),
+            )
+            .field("bot_id", &self.bot_id)
+            .field("workspace_id", &self.workspace_id)
+            .field("workspace_name", &self.workspace_name)
+            .field("workspace_icon", &self.workspace_icon)
+            .field("owner", &self.owner)
+            .field("duplicated_template_id", &self.duplicated_template_id)
+            .finish()
+    }
+}
+
+/// # Token introspection
+///
+/// Whether a token is active, and what it gives access to.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct TokenInfo {
+    /// Whether the token can be used to make requests.
+    pub active: bool,
+    /// The capabilities of the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// When the token was issued, as a Unix timestamp in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_code() {
+        let value = TokenRequest::AuthorizationCode {
+            code: "e202e8c9-0990-40af-855f-ff8f872b1ec6".to_string(),
+            redirect_uri: Some("http://localhost:8765/callback".to_string()),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"grant_type":"authorization_code","code":"e202e8c9-0990-40af-855f-ff8f872b1ec6","redirect_uri":"http://localhost:8765/callback"}"#
+        );
+    }
+
+    #[test]
+    fn refresh_token() {
+        let value = TokenRequest::RefreshToken {
+            refresh_token: "nrt_123".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"grant_type":"refresh_token","refresh_token":"nrt_123"}"#
+        );
+    }
+
+    #[test]
+    fn token() {
+        let value: Token = serde_json::from_str(
+            r#"{"access_token":"secret_abc","token_type":"bearer","refresh_token":"nrt_abc","bot_id":"9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57","workspace_id":"j565j4d7x3-2882-61bs-564a-jj9d9ui-c36hxfr7x","workspace_name":"Ada's Notion Workspace","workspace_icon":"https://url.to.icon","owner":{"type":"workspace","workspace":true},"duplicated_template_id":null,"request_id":"f7a5e4b1-0b9a-4c1e-8e0f-3f7a8e7a9b1c"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.refresh_token.as_deref(), Some("nrt_abc"));
+        assert_eq!(value.owner, BotOwner::Workspace { workspace: true });
+        assert_eq!(value.duplicated_template_id, None);
+    }
+
+    #[test]
+    fn token_debug_redacts_tokens() {
+        let value: Token = serde_json::from_str(
+            r#"{"access_token":"secret_abc","token_type":"bearer","refresh_token":"nrt_abc","bot_id":"9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57","workspace_id":"j565j4d7x3-2882-61bs-564a-jj9d9ui-c36hxfr7x","workspace_name":"Ada's Notion Workspace","workspace_icon":null,"owner":{"type":"workspace","workspace":true},"duplicated_template_id":null}"#,
+        )
+        .unwrap();
+
+        let debug = format!("{value:?}");
+
+        assert!(!debug.contains("secret_abc"));
+        assert!(!debug.contains("nrt_abc"));
+        assert!(debug.contains("Ada's Notion Workspace"));
+    }
+
+    #[test]
+    fn token_owned_by_user() {
+        let value: Token = serde_json::from_str(
+            r#"{"access_token":"secret_abc","token_type":"bearer","bot_id":"9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57","workspace_id":"j565j4d7x3-2882-61bs-564a-jj9d9ui-c36hxfr7x","workspace_name":null,"workspace_icon":null,"owner":{"type":"user","user":{"object":"user","id":"d40e767c-d7af-4b18-a86d-55c61f1e39a4","name":"Avocado Lovelace","avatar_url":null,"type":"person","person":{"email":"avo@example.org"}}},"duplicated_template_id":"5b9b9a2e-1b8d-4b9e-9a3e-0f6e2d6a7c1f"}"#,
+        )
+        .unwrap();
+
+        match value.owner {
+            BotOwner::User { user } => {
+                assert_eq!(user.name.as_deref(), Some("Avocado Lovelace"))
+            },
+            BotOwner::Workspace { .. } => panic!("expected a user owner"),
+        }
+        assert!(value.duplicated_template_id.is_some());
+    }
+
+    #[test]
+    fn token_info() {
+        let value: TokenInfo = serde_json::from_str(
+            r#"{"active":true,"scope":"read_content insert_content","iat":1727554061,"request_id":"f7a5e4b1-0b9a-4c1e-8e0f-3f7a8e7a9b1c"}"#,
+        )
+        .unwrap();
+
+        assert!(value.active);
+        assert_eq!(value.iat, Some(1727554061));
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use super::{file::FileData, Block, BlockData};
+use crate::{ids::FileUploadId, objects::rich_text::RichText};
+
+/// # Audio block
+///
+/// Audio block objects contain a file object detailing information about the
+/// audio file. Supported file types are `.mp3`, `.wav`, `.ogg`, `.oga` and
+/// `.m4a`.
+///
+/// Audio files can be uploaded with the File Upload API, and then attached
+/// with [`Audio::file_upload`].
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Audio {
+    /// The rich text in the caption of the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<Vec<RichText>>,
+    /// A file object that details information about the file contained in
+    /// the block.
+    #[serde(flatten)]
+    pub data: FileData,
+}
+
+impl Audio {
+    pub const fn new(data: FileData) -> Self {
+        Self {
+            caption: None,
+            data,
+        }
+    }
+
+    /// An audio file at an external URL.
+    pub fn external(url: &str) -> Self {
+        Self::new(FileData::external(url))
+    }
+
+    /// An audio file uploaded with the File Upload API.
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::new(FileData::file_upload(id))
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Audio(self))
+    }
+
+    pub fn caption(mut self, caption: Option<Vec<RichText>>) -> Self {
+        self.caption = caption;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn external() {
+        let value = Audio::external("https://example.com/song.mp3").build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","audio":{"external":{"url":"https://example.com/song.mp3"}}}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<Block>(
+            r#"{
+                "object": "block",
+                "type": "audio",
+                "audio": {
+                    "caption": [],
+                    "type": "file",
+                    "file": {
+                        "url": "https://s3.us-west-2.amazonaws.com/song.mp3",
+                        "expiry_time": "2024-01-01T00:00:00.000Z"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let BlockData::Audio(audio) = value.data else {
+            panic!("not an audio block");
+        };
+
+        assert_eq!(audio.caption, Some(vec![]));
+        assert!(matches!(audio.data, FileData::File(_)));
+    }
+}
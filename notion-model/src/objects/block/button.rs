@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Block, BlockData};
+
+/// # Button block
+///
+/// Button block objects do not contain any information. The API returns
+/// buttons, but can't create them or run their actions.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct Button {
+    /// This is present so that serde serializes this into `{}` rather than as
+    /// `null`.
+    #[serde(skip)]
+    _nothing: (),
+}
+
+impl Button {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Button(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response() {
+        let value =
+            serde_json::from_str::<Block>(r#"{"object":"block","type":"button","button":{}}"#)
+                .unwrap();
+
+        assert_eq!(value, Button::new().build());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Number;
 
 use super::{Block, BlockData};
 
@@ -12,6 +13,10 @@ pub struct Column {
     /// The nested blocks. cannot be `None` when making the API call, but the
     /// API will return it as None.
     pub children: Option<Vec<Block>>,
+    /// The width of the column as a fraction of the column list, between 0
+    /// and 1. `None` when the columns are evenly spaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width_ratio: Option<Number>,
 }
 
 impl Column {
@@ -28,4 +33,43 @@ impl Column {
         self.children = children;
         self
     }
+
+    /// Sets the width of the column. Ratios that aren't finite are ignored.
+    pub fn width_ratio(mut self, width_ratio: Option<f64>) -> Self {
+        self.width_ratio = width_ratio.and_then(Number::from_f64);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn width_ratio() {
+        let value = Column::new()
+            .children(Some(vec![]))
+            .width_ratio(Some(0.25))
+            .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","column":{"children":[],"width_ratio":0.25}}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<Block>(
+            r#"{"object":"block","type":"column","column":{"width_ratio":0.5}}"#,
+        )
+        .unwrap();
+
+        let BlockData::Column(column) = value.data else {
+            panic!("not a column block");
+        };
+
+        assert_eq!(column.children, None);
+        assert_eq!(column.width_ratio.and_then(|n| n.as_f64()), Some(0.5));
+    }
 }
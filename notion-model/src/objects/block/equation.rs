@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Block, BlockData};
+
+/// # Equation block
+///
+/// Equation block objects are represented as children of paragraph blocks.
+/// They are nested within a rich text object and contain a KaTeX compatible
+/// expression.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Equation {
+    /// A KaTeX compatible string.
+    pub expression: String,
+}
+
+impl Equation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Equation(self))
+    }
+
+    pub fn expression(mut self, expression: &str) -> Self {
+        self.expression = expression.to_owned();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let value = Equation::new().build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","equation":{"expression":""}}"#
+        );
+    }
+
+    #[test]
+    fn complete() {
+        let value = Equation::new().expression("e=mc^2").build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","equation":{"expression":"e=mc^2"}}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<Block>(
+            r#"{"object":"block","type":"equation","equation":{"expression":"e=mc^2"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value, Equation::new().expression("e=mc^2").build());
+    }
+}
@@ -1,13 +1,18 @@
 use serde::{Deserialize, Serialize};
 
-use crate::objects::{
-    file_and_emoji::{ExternalFile, InternalFile},
-    rich_text::RichText,
+use super::{Block, BlockData};
+use crate::{
+    ids::FileUploadId,
+    objects::{
+        file_and_emoji::{ExternalFile, InternalFile, UploadedFile},
+        rich_text::RichText,
+    },
 };
 
 /// # File block
 ///
-/// The Notion API does not support uploading files to Notion.
+/// Files can be uploaded with the File Upload API, and then attached with
+/// [`File::file_upload`].
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct File {
     /// The rich text in the caption of the block.
@@ -19,9 +24,71 @@ pub struct File {
     pub data: FileData,
 }
 
+impl File {
+    pub const fn new(data: FileData) -> Self {
+        Self {
+            caption: None,
+            data,
+        }
+    }
+
+    /// A file at an external URL.
+    pub fn external(url: &str) -> Self {
+        Self::new(FileData::external(url))
+    }
+
+    /// A file uploaded with the File Upload API.
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::new(FileData::file_upload(id))
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::File(self))
+    }
+
+    pub fn caption(mut self, caption: Option<Vec<RichText>>) -> Self {
+        self.caption = caption;
+        self
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum FileData {
     File(InternalFile),
     External(ExternalFile),
+    /// A file uploaded with the File Upload API. Only used in requests, as the
+    /// API returns attached uploads as [`FileData::File`].
+    FileUpload(UploadedFile),
+}
+
+impl FileData {
+    pub fn external(url: &str) -> Self {
+        Self::External(ExternalFile {
+            url: url.to_owned(),
+        })
+    }
+
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::FileUpload(UploadedFile { id })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_upload() {
+        let value = File::file_upload(FileUploadId::from_str_unchecked(
+            "a3f9d3e2-1abc-42de-b904-badc0ffee000",
+        ))
+        .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","file":{"file_upload":{"id":"a3f9d3e2-1abc-42de-b904-badc0ffee000"}}}"#
+        );
+    }
 }
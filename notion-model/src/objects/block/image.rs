@@ -1,13 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use super::file::FileData;
-use crate::objects::rich_text::RichText;
+use super::{file::FileData, Block, BlockData};
+use crate::{ids::FileUploadId, objects::rich_text::RichText};
 
 /// # Image block
-///
-/// The Notion API does not support uploading files to Notion.
-///
-/// TODO: external image builder
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Image {
     /// The rich text in the caption of the block.
@@ -18,3 +14,50 @@ pub struct Image {
     #[serde(flatten)]
     pub data: FileData,
 }
+
+impl Image {
+    pub const fn new(data: FileData) -> Self {
+        Self {
+            caption: None,
+            data,
+        }
+    }
+
+    /// An image at an external URL.
+    pub fn external(url: &str) -> Self {
+        Self::new(FileData::external(url))
+    }
+
+    /// An image uploaded with the File Upload API.
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::new(FileData::file_upload(id))
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Image(self))
+    }
+
+    pub fn caption(mut self, caption: Option<Vec<RichText>>) -> Self {
+        self.caption = caption;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_upload() {
+        let value = Image::file_upload(FileUploadId::from_str_unchecked(
+            "a3f9d3e2-1abc-42de-b904-badc0ffee000",
+        ))
+        .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","image":{"file_upload":{"id":"a3f9d3e2-1abc-42de-b904-badc0ffee000"}}}"#
+        );
+    }
+}
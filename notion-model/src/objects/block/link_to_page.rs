@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Block, BlockData};
+use crate::ids::{DatabaseId, PageId};
+
+/// # Link to page block
+///
+/// A link to a page or a database, shown with its icon and title.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LinkToPage {
+    PageId {
+        /// The ID of the linked page.
+        page_id: PageId,
+    },
+    DatabaseId {
+        /// The ID of the linked database.
+        database_id: DatabaseId,
+    },
+}
+
+impl LinkToPage {
+    pub const fn page(page_id: PageId) -> Self {
+        Self::PageId { page_id }
+    }
+
+    pub const fn database(database_id: DatabaseId) -> Self {
+        Self::DatabaseId { database_id }
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::LinkToPage(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn page() {
+        let value = LinkToPage::page(PageId::from_str_unchecked(
+            "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4",
+        ))
+        .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","link_to_page":{"type":"page_id","page_id":"67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4"}}"#
+        );
+    }
+
+    #[test]
+    fn database() {
+        let value = LinkToPage::database(DatabaseId::from_str_unchecked(
+            "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4",
+        ))
+        .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","link_to_page":{"type":"database_id","database_id":"67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4"}}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<Block>(
+            r#"{
+                "object": "block",
+                "type": "link_to_page",
+                "link_to_page": {
+                    "type": "page_id",
+                    "page_id": "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.data,
+            BlockData::LinkToPage(LinkToPage::page(PageId::from_str_unchecked(
+                "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4"
+            )))
+        );
+    }
+}
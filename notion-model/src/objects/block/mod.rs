@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
 
 use super::{date::DateOrDateTime, parent::ParentData, rich_text::Mention, user::PartialUser};
-use crate::ids::BlockId;
+use crate::{ids::BlockId, unknown::impl_unknown};
 
+mod audio;
 mod bookmark;
 mod breadcrumb;
 mod bulleted_list_item;
+mod button;
 mod callout;
 mod child_database;
 mod child_page;
@@ -14,13 +16,14 @@ mod column;
 mod column_list;
 mod divider;
 mod embed;
-
+mod equation;
 mod file;
 mod heading_1;
 mod heading_2;
 mod heading_3;
 mod image;
 mod link_preview;
+mod link_to_page;
 mod numbered_list_item;
 mod paragraph;
 mod pdf;
@@ -32,11 +35,14 @@ mod table_row;
 mod template;
 mod to_do;
 mod toggle;
+mod unsupported;
 mod video;
 
+pub use audio::Audio;
 pub use bookmark::Bookmark;
 pub use breadcrumb::Breadcrumb;
 pub use bulleted_list_item::BulletedListItem;
+pub use button::Button;
 pub use callout::Callout;
 pub use child_database::ChildDatabase;
 pub use child_page::ChildPage;
@@ -45,12 +51,14 @@ pub use column::Column;
 pub use column_list::ColumnList;
 pub use divider::Divider;
 pub use embed::Embed;
-pub use file::File;
+pub use equation::Equation;
+pub use file::{File, FileData};
 pub use heading_1::Heading1;
 pub use heading_2::Heading2;
 pub use heading_3::Heading3;
 pub use image::Image;
 pub use link_preview::LinkPreview;
+pub use link_to_page::LinkToPage;
 pub use numbered_list_item::NumberedListItem;
 pub use paragraph::Paragraph;
 pub use pdf::Pdf;
@@ -62,6 +70,7 @@ pub use table_row::TableRow;
 pub use template::Template;
 pub use to_do::ToDo;
 pub use toggle::Toggle;
+pub use unsupported::Unsupported;
 pub use video::Video;
 
 /// # Block datatype
@@ -147,12 +156,14 @@ impl Block {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
-#[serde(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(remote = "Self", rename_all = "snake_case")]
 pub enum BlockData {
+    Audio(Audio),
     Bookmark(Bookmark),
     Breadcrumb(Breadcrumb),
     BulletedListItem(BulletedListItem),
+    Button(Button),
     Callout(Callout),
     ChildDatabase(ChildDatabase),
     ChildPage(ChildPage),
@@ -161,6 +172,7 @@ pub enum BlockData {
     Code(Code),
     Divider(Divider),
     Embed(Embed),
+    Equation(Equation),
     File(File),
     #[serde(rename = "heading_1")]
     Heading1(Heading1),
@@ -170,6 +182,7 @@ pub enum BlockData {
     Heading3(Heading3),
     Image(Image),
     LinkPreview(LinkPreview),
+    LinkToPage(LinkToPage),
     Mention(Mention),
     NumberedListItem(NumberedListItem),
     Paragraph(Paragraph),
@@ -183,8 +196,22 @@ pub enum BlockData {
     ToDo(ToDo),
     Toggle(Toggle),
     Video(Video),
-    #[default]
-    Unsupported,
+    Unsupported(Unsupported),
+    /// A block type that this crate doesn't know about yet, with the raw JSON
+    /// of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl_unknown!(BlockData, External);
+
+impl Default for BlockData {
+    fn default() -> Self {
+        Self::Unsupported(Unsupported::default())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
@@ -1,13 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use super::file::FileData;
-use crate::objects::rich_text::RichText;
+use super::{file::FileData, Block, BlockData};
+use crate::{ids::FileUploadId, objects::rich_text::RichText};
 
 /// # PDF block
-///
-/// The Notion API does not support uploading files to Notion.
-///
-/// TODO: external image builder
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Pdf {
     /// The rich text in the caption of the block.
@@ -18,3 +14,50 @@ pub struct Pdf {
     #[serde(flatten)]
     pub data: FileData,
 }
+
+impl Pdf {
+    pub const fn new(data: FileData) -> Self {
+        Self {
+            caption: None,
+            data,
+        }
+    }
+
+    /// A PDF at an external URL.
+    pub fn external(url: &str) -> Self {
+        Self::new(FileData::external(url))
+    }
+
+    /// A PDF uploaded with the File Upload API.
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::new(FileData::file_upload(id))
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Pdf(self))
+    }
+
+    pub fn caption(mut self, caption: Option<Vec<RichText>>) -> Self {
+        self.caption = caption;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_upload() {
+        let value = Pdf::file_upload(FileUploadId::from_str_unchecked(
+            "a3f9d3e2-1abc-42de-b904-badc0ffee000",
+        ))
+        .build();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"block","pdf":{"file_upload":{"id":"a3f9d3e2-1abc-42de-b904-badc0ffee000"}}}"#
+        );
+    }
+}
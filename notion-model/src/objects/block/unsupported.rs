@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Block, BlockData};
+
+/// # Unsupported block
+///
+/// A block of a type that the API doesn't support, such as a form or an AI
+/// block. Unsupported blocks can't be created, and their content can't be
+/// retrieved.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Unsupported {
+    /// The type of the block in the Notion app, if the API gives it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_type: Option<String>,
+}
+
+impl Unsupported {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn build(self) -> Block {
+        Block::new(BlockData::Unsupported(self))
+    }
+
+    pub fn block_type(mut self, block_type: Option<&str>) -> Self {
+        self.block_type = block_type.map(Into::into);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<Block>(
+            r#"{"object":"block","type":"unsupported","unsupported":{}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value, Unsupported::new().build());
+    }
+
+    #[test]
+    fn block_type() {
+        let value = serde_json::from_str::<Block>(
+            r#"{"object":"block","type":"unsupported","unsupported":{"block_type":"form"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value, Unsupported::new().block_type(Some("form")).build());
+    }
+}
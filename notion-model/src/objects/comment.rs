@@ -1 +1,101 @@
+use serde::{Deserialize, Serialize};
 
+use super::{date::DateOrDateTime, parent::ParentData, rich_text::RichText, user::PartialUser};
+use crate::ids::{CommentId, DiscussionId, PageId};
+
+/// # Comment
+///
+/// The Comment object represents a comment on a Notion page or block.
+///
+/// Comments are grouped into discussion threads. A comment either starts a new
+/// discussion on a page, in which case `parent` is set, or replies to an
+/// existing discussion, in which case `discussion_id` is set.
+///
+/// When you receive a comment object from the API, every field is present.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+#[serde(tag = "object", rename = "comment")]
+pub struct Comment {
+    /// Unique identifier of the comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<CommentId>,
+    /// The page or block the discussion is on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ParentData>,
+    /// Unique identifier of the discussion thread the comment belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discussion_id: Option<DiscussionId>,
+    /// Date and time when this comment was created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<DateOrDateTime>,
+    /// Date and time when this comment was updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_time: Option<DateOrDateTime>,
+    /// User who created the comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<PartialUser>,
+    /// Content of the comment.
+    pub rich_text: Vec<RichText>,
+}
+
+impl Comment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new discussion on a page.
+    pub fn page(mut self, page_id: PageId) -> Self {
+        self.parent = Some(page_id.into());
+        self
+    }
+
+    /// Replies to an existing discussion.
+    pub const fn discussion_id(mut self, discussion_id: Option<DiscussionId>) -> Self {
+        self.discussion_id = discussion_id;
+        self
+    }
+
+    pub fn rich_text(mut self, rich_text: Vec<RichText>) -> Self {
+        self.rich_text = rich_text;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn new_discussion() {
+        let value = Comment::new()
+            .page(PageId::from_str("5c6a2821-6bb1-4a7e-b6e1-c50111515c3d").unwrap())
+            .rich_text(vec![RichText::new_text("Hello world")]);
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"object":"comment","parent":{"page_id":"5c6a2821-6bb1-4a7e-b6e1-c50111515c3d"},"rich_text":[{"text":{"content":"Hello world"}}]}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value: Comment = serde_json::from_str(
+            r#"{"object":"comment","id":"94cc56ab-9f02-409d-9f99-1037e9fe502f","parent":{"type":"page_id","page_id":"5c6a2821-6bb1-4a7e-b6e1-c50111515c3d"},"discussion_id":"f1407351-36f5-4c49-a13c-49f8ba11776d","created_time":"2022-07-15T16:52:00.000Z","last_edited_time":"2022-07-15T19:16:00.000Z","created_by":{"object":"user","id":"9b15170a-9941-4297-8ee6-83fa7649a87a"},"rich_text":[{"type":"text","text":{"content":"Single comment","link":null},"annotations":{"bold":false,"italic":false,"strikethrough":false,"underline":false,"code":false,"color":"default"},"plain_text":"Single comment","href":null}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.discussion_id,
+            Some(DiscussionId::from_str("f1407351-36f5-4c49-a13c-49f8ba11776d").unwrap())
+        );
+        assert_eq!(
+            value.parent,
+            Some(
+                PageId::from_str("5c6a2821-6bb1-4a7e-b6e1-c50111515c3d")
+                    .unwrap()
+                    .into()
+            )
+        );
+    }
+}
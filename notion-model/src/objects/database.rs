@@ -8,8 +8,12 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    block::File, date::DateOrDateTime, file_and_emoji::FileOrEmoji, parent::ParentData,
-    properties::Property, rich_text::RichText, user::PartialUser,
+    database_property::DatabaseProperty,
+    date::DateOrDateTime,
+    file_and_emoji::{Cover, FileOrEmoji},
+    parent::ParentData,
+    rich_text::RichText,
+    user::PartialUser,
 };
 use crate::ids::DatabaseId;
 
@@ -28,13 +32,13 @@ pub struct Database {
     pub last_edited_by: Option<PartialUser>,
     pub title: Vec<RichText>,
     pub description: Vec<RichText>,
-    /// emoji or external, can't be internal
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub icon: Option<FileOrEmoji>,
-    /// file
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub cover: Option<File>,
-    // pub properties: HashMap<String, Property>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmoji>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<Cover>,
+    /// The schema of the database, keyed by property name.
+    #[serde(default)]
+    pub properties: HashMap<String, DatabaseProperty>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<ParentData>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -59,38 +63,38 @@ impl Database {
         self
     }
 
-    // pub fn archived(mut self, archived: Option<bool>) -> Self {
-    //     self.archived = archived;
-    //     self
-    // }
+    pub const fn archived(mut self, archived: Option<bool>) -> Self {
+        self.archived = archived;
+        self
+    }
 
-    // pub fn icon(mut self, icon: Option<FileOrEmoji>) -> Self {
-    //     self.icon = icon;
-    //     self
-    // }
+    pub fn icon(mut self, icon: Option<FileOrEmoji>) -> Self {
+        self.icon = icon;
+        self
+    }
 
-    // pub fn cover(mut self, cover: Option<File>) -> Self {
-    //     self.cover = cover;
-    //     self
-    // }
+    pub fn cover(mut self, cover: Option<Cover>) -> Self {
+        self.cover = cover;
+        self
+    }
 
-    // pub fn title(mut self, title: Vec<RichText>) -> Self {
-    //     self.title = title;
-    //     self
-    // }
+    pub fn title(mut self, title: Vec<RichText>) -> Self {
+        self.title = title;
+        self
+    }
 
-    // pub fn description(mut self, description: Vec<RichText>) -> Self {
-    //     self.description = description;
-    //     self
-    // }
+    pub fn description(mut self, description: Vec<RichText>) -> Self {
+        self.description = description;
+        self
+    }
 
-    // pub fn properties(mut self, properties: HashMap<String, Property>) -> Self {
-    //     self.properties = properties;
-    //     self
-    // }
+    pub fn properties(mut self, properties: HashMap<String, DatabaseProperty>) -> Self {
+        self.properties = properties;
+        self
+    }
 
-    // pub fn parent(mut self, parent: Option<ParentData>) -> Self {
-    //     self.parent = parent;
-    //     self
-    // }
+    pub const fn parent(mut self, parent: Option<ParentData>) -> Self {
+        self.parent = parent;
+        self
+    }
 }
@@ -0,0 +1,244 @@
+//! # Database properties
+//!
+//! Database property objects describe the schema of a database: the name and
+//! type of each column, along with any type-specific configuration such as the
+//! options of a select.
+//!
+//! See <https://developers.notion.com/reference/property-object>.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    color::OptionColor,
+    properties::{RollupFunction, SelectOption},
+};
+use crate::{
+    ids::{DatabaseId, OptionId, PropertyId},
+    unknown::impl_unknown,
+};
+
+/// A single column in a database schema.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct DatabaseProperty {
+    /// An identifier for the property, usually a short string of random
+    /// letters and symbols.
+    ///
+    /// Some automatically generated property types have special human-readable
+    /// IDs. For example, all Title properties have an id of "title".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<PropertyId>,
+    /// The name of the property as it appears in Notion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The property description, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The type of the property and its configuration.
+    #[serde(flatten)]
+    pub data: DatabasePropertyData,
+}
+
+impl DatabaseProperty {
+    pub const fn new(data: DatabasePropertyData) -> Self {
+        Self {
+            id: None,
+            name: None,
+            description: None,
+            data,
+        }
+    }
+
+    pub fn build_with_name(self, name: &str) -> (String, Self) {
+        (name.to_string(), self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(remote = "Self", rename_all = "snake_case")]
+pub enum DatabasePropertyData {
+    Button(EmptyConfig),
+    Checkbox(EmptyConfig),
+    CreatedBy(EmptyConfig),
+    CreatedTime(EmptyConfig),
+    Date(EmptyConfig),
+    Email(EmptyConfig),
+    Files(EmptyConfig),
+    Formula(FormulaConfig),
+    LastEditedBy(EmptyConfig),
+    LastEditedTime(EmptyConfig),
+    MultiSelect(SelectConfig),
+    Number(NumberConfig),
+    People(EmptyConfig),
+    PhoneNumber(EmptyConfig),
+    Relation(RelationConfig),
+    RichText(EmptyConfig),
+    Rollup(RollupConfig),
+    Select(SelectConfig),
+    Status(StatusConfig),
+    Title(EmptyConfig),
+    UniqueId(UniqueIdConfig),
+    Url(EmptyConfig),
+    Verification(EmptyConfig),
+    /// A property type that this crate doesn't know about yet, with the raw
+    /// JSON of its configuration.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl_unknown!(DatabasePropertyData, External);
+
+impl DatabasePropertyData {
+    /// Whether page values of this property can be set through the API.
+    ///
+    /// Values of formula, rollup, unique id, verification, and the
+    /// created/last edited properties are computed by Notion. Buttons have no
+    /// value, and unknown types can't be told apart.
+    pub const fn is_writable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Button(_)
+                | Self::CreatedBy(_)
+                | Self::CreatedTime(_)
+                | Self::Formula(_)
+                | Self::LastEditedBy(_)
+                | Self::LastEditedTime(_)
+                | Self::Rollup(_)
+                | Self::UniqueId(_)
+                | Self::Verification(_)
+                | Self::Unknown { .. }
+        )
+    }
+}
+
+/// Configuration for property types that have none.
+///
+/// Serializes to `{}`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct EmptyConfig {}
+
+/// Configuration of a formula property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FormulaConfig {
+    /// The formula that is used to compute the values for this property.
+    pub expression: String,
+}
+
+/// Configuration of a multi-select or select property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct SelectConfig {
+    /// The available options.
+    #[serde(default)]
+    pub options: Vec<SelectOption>,
+}
+
+/// Configuration of a number property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct NumberConfig {
+    /// The way that the number is displayed in Notion, such as `number`,
+    /// `percent` or `dollar`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// Configuration of a relation property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct RelationConfig {
+    /// The database that the relation property refers to.
+    pub database_id: DatabaseId,
+    /// The id of the corresponding property in the related database, for dual
+    /// relations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_property_id: Option<PropertyId>,
+    /// The name of the corresponding property in the related database, for
+    /// dual relations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced_property_name: Option<String>,
+}
+
+/// Configuration of a rollup property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct RollupConfig {
+    /// The function that computes the rollup value from the related pages.
+    pub function: RollupFunction,
+    /// The id of the related database property that is rolled up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation_property_id: Option<PropertyId>,
+    /// The name of the related database property that is rolled up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation_property_name: Option<String>,
+    /// The id of the rollup property.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollup_property_id: Option<PropertyId>,
+    /// The name of the rollup property.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollup_property_name: Option<String>,
+}
+
+/// Configuration of a status property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct StatusConfig {
+    /// The available options.
+    #[serde(default)]
+    pub options: Vec<SelectOption>,
+    /// The groups that the options are sorted into.
+    #[serde(default)]
+    pub groups: Vec<StatusGroup>,
+}
+
+/// A group of status options, such as "To-do", "In progress" or "Complete".
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StatusGroup {
+    /// The ID of the group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<OptionId>,
+    /// The name of the group as it appears in Notion.
+    pub name: String,
+    /// The color of the group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<OptionColor>,
+    /// The IDs of the options that belong to this group.
+    #[serde(default)]
+    pub option_ids: Vec<OptionId>,
+}
+
+/// Configuration of a unique id property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct UniqueIdConfig {
+    /// The prefix shown before the number, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select() {
+        let value: DatabaseProperty = serde_json::from_str(
+            r#"{"id":"%40Q%5BM","name":"Type","type":"select","select":{"options":[{"id":"e28f74fc-83a7-4469-8435-27eb18f9f9de","name":"Homework","color":"purple"}]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.name.as_deref(), Some("Type"));
+        assert!(
+            matches!(&value.data, DatabasePropertyData::Select(config) if config.options.len() == 1)
+        );
+    }
+
+    #[test]
+    fn title() {
+        let value: DatabaseProperty =
+            serde_json::from_str(r#"{"id":"title","name":"Name","type":"title","title":{}}"#)
+                .unwrap();
+
+        assert_eq!(value.data, DatabasePropertyData::Title(EmptyConfig {}));
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"id":"title","name":"Name","title":{}}"#
+        );
+    }
+}
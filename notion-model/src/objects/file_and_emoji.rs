@@ -1,12 +1,103 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    ids::{CustomEmojiId, FileUploadId},
+    unknown::impl_unknown,
+};
+
+/// # Icon
+///
+/// The icon of a page, database or callout. Icons that are set to an uploaded
+/// file are returned as [`FileOrEmoji::File`].
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(tag = "type", rename_all = "snake_case")]
+#[serde(remote = "Self", tag = "type", rename_all = "snake_case")]
 pub enum FileOrEmoji {
-    Emoji { emoji: String },
+    Emoji {
+        emoji: String,
+    },
+    File {
+        file: InternalFile,
+    },
+    External {
+        external: ExternalFile,
+    },
+    FileUpload {
+        file_upload: UploadedFile,
+    },
+    /// A custom emoji from the workspace.
+    CustomEmoji {
+        custom_emoji: CustomEmoji,
+    },
+    /// An icon type that this crate doesn't know about yet, with the raw JSON
+    /// of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl_unknown!(FileOrEmoji, Internal);
+
+impl FileOrEmoji {
+    pub fn emoji(emoji: &str) -> Self {
+        Self::Emoji {
+            emoji: emoji.to_owned(),
+        }
+    }
+
+    pub fn external(url: &str) -> Self {
+        Self::External {
+            external: ExternalFile {
+                url: url.to_owned(),
+            },
+        }
+    }
+
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::FileUpload {
+            file_upload: UploadedFile { id },
+        }
+    }
+
+    pub const fn custom_emoji(id: CustomEmojiId) -> Self {
+        Self::CustomEmoji {
+            custom_emoji: CustomEmoji {
+                id,
+                name: None,
+                url: None,
+            },
+        }
+    }
+}
+
+/// # Cover
+///
+/// The cover image of a page or database. Covers that are set to an uploaded
+/// file are returned as [`Cover::File`].
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Cover {
     File { file: InternalFile },
     External { external: ExternalFile },
+    FileUpload { file_upload: UploadedFile },
+}
+
+impl Cover {
+    pub fn external(url: &str) -> Self {
+        Self::External {
+            external: ExternalFile {
+                url: url.to_owned(),
+            },
+        }
+    }
+
+    pub const fn file_upload(id: FileUploadId) -> Self {
+        Self::FileUpload {
+            file_upload: UploadedFile { id },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
@@ -14,9 +105,85 @@ pub struct ExternalFile {
     pub url: String,
 }
 
+/// A file hosted by Notion.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
 #[serde(rename = "file")]
 pub struct InternalFile {
-    url: String,
-    expiry_time: DateTime<Utc>,
+    /// An authenticated URL to the file, which is only valid until the expiry
+    /// time. Retrieve the object again for a new URL.
+    pub url: String,
+    pub expiry_time: DateTime<Utc>,
+}
+
+/// A reference to a file that was uploaded with the File Upload API, to
+/// attach it to a block, page or files property.
+///
+/// Once attached, the API returns the file as an [`InternalFile`].
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct UploadedFile {
+    pub id: FileUploadId,
+}
+
+/// A custom emoji. Only the ID is needed to set it as an icon.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct CustomEmoji {
+    pub id: CustomEmojiId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn custom_emoji() {
+        let icon = serde_json::from_str::<FileOrEmoji>(
+            r#"{
+                "type": "custom_emoji",
+                "custom_emoji": {
+                    "id": "45ce454c-d427-4f53-9489-e5d0f3d1db6b",
+                    "name": "kale",
+                    "url": "https://example.com/kale.png"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            &icon,
+            FileOrEmoji::CustomEmoji { custom_emoji } if custom_emoji.name.as_deref() == Some("kale")
+        ));
+
+        assert_eq!(
+            serde_json::to_string(&FileOrEmoji::custom_emoji(
+                CustomEmojiId::from_str_unchecked("45ce454c-d427-4f53-9489-e5d0f3d1db6b")
+            ))
+            .unwrap(),
+            r#"{"type":"custom_emoji","custom_emoji":{"id":"45ce454c-d427-4f53-9489-e5d0f3d1db6b"}}"#
+        );
+    }
+
+    #[test]
+    fn cover() {
+        let cover = serde_json::from_str::<Cover>(
+            r#"{
+                "type": "file",
+                "file": {
+                    "url": "https://example.com/cover.png",
+                    "expiry_time": "2024-05-01T00:00:00.000Z"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(cover, Cover::File { .. }));
+
+        assert_eq!(
+            serde_json::to_string(&Cover::external("https://example.com/cover.png")).unwrap(),
+            r#"{"type":"external","external":{"url":"https://example.com/cover.png"}}"#
+        );
+    }
 }
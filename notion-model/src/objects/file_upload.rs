@@ -0,0 +1,166 @@
+//! # File uploads
+//!
+//! Files are uploaded to Notion in two steps: creating a file upload, then
+//! sending its contents. Files of up to 20 MB are sent in a single part.
+//! Larger files are split into parts of 5 to 20 MB, which are sent in order,
+//! and then the upload is completed.
+//!
+//! Once uploaded, a file is attached by referencing its ID with
+//! [`UploadedFile`](super::file_and_emoji::UploadedFile), in a file, image,
+//! PDF, video or audio block, a page icon or cover, or a files property. An
+//! upload that isn't attached within an hour expires.
+
+use serde::{Deserialize, Serialize};
+
+use super::date::DateOrDateTime;
+use crate::ids::FileUploadId;
+
+/// # File upload
+///
+/// The status and details of a file upload.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "object", rename = "file_upload")]
+pub struct FileUpload {
+    pub id: FileUploadId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<DateOrDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_time: Option<DateOrDateTime>,
+    /// When the upload expires if it isn't attached to anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_time: Option<DateOrDateTime>,
+    pub status: FileUploadStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// The MIME type of the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The size of the file in bytes, once it's uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+    /// The URL to send the contents of the file to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_url: Option<String>,
+    /// The URL to complete a multi-part upload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete_url: Option<String>,
+    /// How many parts of a multi-part upload there are, and how many have been
+    /// sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_of_parts: Option<NumberOfParts>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FileUploadStatus {
+    /// Waiting for the contents of the file.
+    Pending,
+    /// Ready to be attached.
+    Uploaded,
+    /// Not attached in time.
+    Expired,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub struct NumberOfParts {
+    pub total: u32,
+    pub sent: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FileUploadMode {
+    /// The file is sent in a single request. For files of up to 20 MB.
+    #[default]
+    SinglePart,
+    /// The file is sent in several parts, then completed.
+    MultiPart,
+}
+
+/// The body of a request to create a file upload.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct CreateFileUpload {
+    pub mode: FileUploadMode,
+    /// The name of the file. Required for multi-part uploads.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// The MIME type of the file. If it isn't given, it's inferred from the
+    /// filename.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The number of parts of a multi-part upload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_parts: Option<u32>,
+}
+
+impl CreateFileUpload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn multi_part(number_of_parts: u32) -> Self {
+        Self {
+            mode: FileUploadMode::MultiPart,
+            number_of_parts: Some(number_of_parts),
+            ..Self::default()
+        }
+    }
+
+    pub fn filename(mut self, filename: Option<String>) -> Self {
+        self.filename = filename;
+        self
+    }
+
+    pub fn content_type(mut self, content_type: Option<String>) -> Self {
+        self.content_type = content_type;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn create() {
+        let value = CreateFileUpload::multi_part(3)
+            .filename(Some("build.zip".to_owned()))
+            .content_type(Some("application/zip".to_owned()));
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"mode":"multi_part","filename":"build.zip","content_type":"application/zip","number_of_parts":3}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let value = serde_json::from_str::<FileUpload>(
+            r#"{
+                "object": "file_upload",
+                "id": "a3f9d3e2-1abc-42de-b904-badc0ffee000",
+                "created_time": "2025-03-15T20:53:00.000Z",
+                "last_edited_time": "2025-03-15T20:53:00.000Z",
+                "expiry_time": "2025-03-15T21:53:00.000Z",
+                "upload_url": "https://api.notion.com/v1/file_uploads/a3f9d3e2-1abc-42de-b904-badc0ffee000/send",
+                "archived": false,
+                "status": "pending",
+                "filename": "build.zip",
+                "content_type": "application/zip",
+                "content_length": null,
+                "number_of_parts": { "total": 3, "sent": 0 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.status, FileUploadStatus::Pending);
+        assert_eq!(
+            value.number_of_parts,
+            Some(NumberOfParts { total: 3, sent: 0 })
+        );
+        assert!(value.upload_url.is_some());
+    }
+}
@@ -6,8 +6,10 @@ pub mod code_languages;
 pub mod color;
 pub mod comment;
 pub mod database;
+pub mod database_property;
 pub mod date;
 pub mod file_and_emoji;
+pub mod file_upload;
 pub mod page;
 pub mod parent;
 pub mod rich_text;
@@ -3,8 +3,11 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    block::File, date::DateOrDateTime, file_and_emoji::FileOrEmoji, parent::ParentData,
-    properties::Property, user::PartialUser,
+    date::DateOrDateTime,
+    file_and_emoji::{Cover, FileOrEmoji},
+    parent::ParentData,
+    properties::Property,
+    user::PartialUser,
 };
 use crate::ids::PageId;
 
@@ -29,12 +32,10 @@ pub struct Page {
     pub last_edited_by: Option<PartialUser>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub archived: Option<bool>,
-    /// emoji or external, can't be internal
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icon: Option<FileOrEmoji>,
-    /// file
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub cover: Option<File>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<Cover>,
     pub properties: HashMap<String, Property>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<ParentData>,
@@ -59,15 +60,15 @@ impl Page {
         self
     }
 
-    // pub fn icon(mut self, icon: Option<FileOrEmoji>) -> Self {
-    //     self.icon = icon;
-    //     self
-    // }
+    pub fn icon(mut self, icon: Option<FileOrEmoji>) -> Self {
+        self.icon = icon;
+        self
+    }
 
-    // pub fn cover(mut self, cover: Option<File>) -> Self {
-    //     self.cover = cover;
-    //     self
-    // }
+    pub fn cover(mut self, cover: Option<Cover>) -> Self {
+        self.cover = cover;
+        self
+    }
 
     pub fn properties(mut self, properties: HashMap<String, Property>) -> Self {
         self.properties = properties;
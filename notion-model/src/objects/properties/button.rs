@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// The value of a button property, which has no data. Buttons can't be set
+/// or pressed through the API.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Button {
+    /// This is present so that serde serializes this into `{}` rather than as
+    /// `null`.
+    #[serde(skip)]
+    _nothing: (),
+}
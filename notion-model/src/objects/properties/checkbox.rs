@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Checkbox(bool);
+pub struct Checkbox(pub bool);
 
 impl Checkbox {
     pub fn new(checked: bool) -> Self {
@@ -4,7 +4,7 @@ use super::{Property, PropertyData};
 use crate::objects::rich_text::DateMention;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Date(Option<DateMention>);
+pub struct Date(pub Option<DateMention>);
 
 impl Date {
     pub fn new(date: DateMention) -> Self {
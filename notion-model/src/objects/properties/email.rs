@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct Email(Option<String>);
+pub struct Email(pub Option<String>);
 
 impl Email {
     pub fn new(email: String) -> Self {
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Property, PropertyData};
+use crate::{ids::FileUploadId, objects::block::FileData};
+
+/// An array of files.
+///
+/// When updating a files property, the value is overwritten by the files
+/// that are given. To remove a file, leave it out.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Files(pub Vec<NamedFile>);
+
+impl Files {
+    pub const fn new(files: Vec<NamedFile>) -> Self {
+        Self(files)
+    }
+
+    pub fn build_with_name(self, name: &str) -> (String, Property) {
+        (name.to_string(), Property::new(PropertyData::Files(self)))
+    }
+}
+
+/// A file in a files property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct NamedFile {
+    /// The name of the file, which is shown instead of its URL.
+    pub name: String,
+    #[serde(flatten)]
+    pub data: FileData,
+}
+
+impl NamedFile {
+    /// A file at an external URL.
+    pub fn external(name: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data: FileData::external(url),
+        }
+    }
+
+    /// A file uploaded with the File Upload API.
+    pub fn file_upload(name: &str, id: FileUploadId) -> Self {
+        Self {
+            name: name.to_string(),
+            data: FileData::file_upload(id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn external() {
+        let (name, property) = Files::new(vec![NamedFile::external(
+            "Receipt",
+            "https://example.com/receipt.pdf",
+        )])
+        .build_with_name("Attachments");
+
+        assert_eq!(name, "Attachments");
+        assert_eq!(
+            serde_json::to_string(&property).unwrap(),
+            r#"{"files":[{"name":"Receipt","external":{"url":"https://example.com/receipt.pdf"}}]}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let property = serde_json::from_str::<Property>(
+            r#"{
+                "id": "Q%7Dn%3E",
+                "type": "files",
+                "files": [{
+                    "name": "receipt.pdf",
+                    "type": "file",
+                    "file": {
+                        "url": "https://s3.us-west-2.amazonaws.com/receipt.pdf",
+                        "expiry_time": "2024-01-01T00:00:00.000Z"
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let PropertyData::Files(Files(files)) = property.data else {
+            panic!("not files");
+        };
+
+        assert_eq!(files[0].name, "receipt.pdf");
+        assert!(matches!(files[0].data, FileData::File(_)));
+    }
+}
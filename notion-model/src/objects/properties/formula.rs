@@ -1,14 +1,105 @@
 use serde::{Deserialize, Serialize};
 
-use crate::objects::date::DateOrDateTime;
+use crate::objects::rich_text::DateMention;
 
 /// Value of a formula property.
-/// TODO: remove tag, use a struct if possible
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+///
+/// Every type of result is `None` when the formula has no result, such as
+/// when it refers to an empty property.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum FormulaData {
-    Boolean { boolean: bool },
-    Date { date: DateOrDateTime },
-    Number { number: u32 },
-    String { string: String },
+    Boolean { boolean: Option<bool> },
+    Date { date: Option<DateMention> },
+    Number { number: Option<f64> },
+    String { string: Option<String> },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::objects::{
+        date::DateOrDateTime,
+        properties::{Property, PropertyData},
+    };
+
+    fn formula(json: &str) -> FormulaData {
+        match serde_json::from_str::<Property>(json).unwrap().data {
+            PropertyData::Formula(formula) => formula,
+            data => panic!("not a formula: {data:?}"),
+        }
+    }
+
+    #[test]
+    fn number() {
+        assert_eq!(
+            formula(
+                r#"{"id":"%3DxNp","type":"formula","formula":{"type":"number","number":-1234.56}}"#
+            ),
+            FormulaData::Number {
+                number: Some(-1234.56)
+            }
+        );
+        assert_eq!(
+            formula(
+                r#"{"id":"%3DxNp","type":"formula","formula":{"type":"number","number":null}}"#
+            ),
+            FormulaData::Number { number: None }
+        );
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(
+            formula(r#"{"id":"a","type":"formula","formula":{"type":"string","string":"Q3"}}"#),
+            FormulaData::String {
+                string: Some("Q3".to_owned())
+            }
+        );
+        assert_eq!(
+            formula(r#"{"id":"a","type":"formula","formula":{"type":"string","string":null}}"#),
+            FormulaData::String { string: None }
+        );
+    }
+
+    #[test]
+    fn boolean() {
+        assert_eq!(
+            formula(r#"{"id":"a","type":"formula","formula":{"type":"boolean","boolean":true}}"#),
+            FormulaData::Boolean {
+                boolean: Some(true)
+            }
+        );
+    }
+
+    #[test]
+    fn date_range() {
+        let FormulaData::Date { date: Some(date) } = formula(
+            r#"{
+                "id": "a",
+                "type": "formula",
+                "formula": {
+                    "type": "date",
+                    "date": {
+                        "start": "2024-01-01",
+                        "end": "2024-03-31",
+                        "time_zone": null
+                    }
+                }
+            }"#,
+        ) else {
+            panic!("not a date");
+        };
+
+        assert_eq!(date.start, "2024-01-01".parse::<DateOrDateTime>().unwrap());
+        assert_eq!(date.end, Some("2024-03-31".parse().unwrap()));
+    }
+
+    #[test]
+    fn empty_date() {
+        assert_eq!(
+            formula(r#"{"id":"a","type":"formula","formula":{"type":"date","date":null}}"#),
+            FormulaData::Date { date: None }
+        );
+    }
 }
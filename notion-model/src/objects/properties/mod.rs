@@ -1,17 +1,14 @@
-use formula::FormulaData;
 use serde::{Deserialize, Serialize};
 
-use super::{block::File, date::DateOrDateTime, rich_text::PageMention, user::PartialUser};
-use crate::ids::PropertyId;
+use super::{date::DateOrDateTime, user::PartialUser};
+use crate::{ids::PropertyId, unknown::impl_unknown};
 
+mod button;
 mod checkbox;
-// mod created_by;
-// mod created_time;
 mod date;
 mod email;
-// mod files;
+mod files;
 mod formula;
-// mod last_edited_time;
 mod multi_select;
 mod number;
 mod people;
@@ -24,28 +21,27 @@ mod status;
 mod title;
 mod unique_id;
 mod url;
+mod verification;
 
+pub use button::Button;
 pub use checkbox::Checkbox;
-// pub use created_by::CreatedBy;
-// pub use created_time::CreatedTime;
 pub use date::Date;
 pub use email::Email;
-// pub use files::Files;
-//* pub use formula::Formula;
-// pub use last_edited_time::LastEditedTime;
-// pub use last_edited_by::LastEditedBy;
-//* pub use multi_select::MultiSelect;
+pub use files::{Files, NamedFile};
+pub use formula::FormulaData;
+pub use multi_select::MultiSelect;
 pub use number::Number;
-//* pub use people::People;
+pub use people::People;
 pub use phone_number::PhoneNumber;
-//* pub use relation::Relation;
+pub use relation::Relation;
 pub use rich_text::RichText;
-pub use rollup::Rollup;
+pub use rollup::{Rollup, RollupData, RollupFunction};
 pub use select::{Select, SelectOption};
 pub use status::{Status, StatusOption};
 pub use title::Title;
 pub use unique_id::UniqueId;
 pub use url::Url;
+pub use verification::{Verification, VerificationState};
 
 /// # Page properties
 ///
@@ -102,8 +98,10 @@ impl Property {
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-#[serde(rename_all = "snake_case")]
+#[serde(remote = "Self", rename_all = "snake_case")]
 pub enum PropertyData {
+    /// A button that runs an automation in the Notion UI.
+    Button(Button),
     /// Simple checkboxes that indicate whether something is done or
     /// not.
     Checkbox(Checkbox),
@@ -132,17 +130,18 @@ pub enum PropertyData {
     ///
     /// # 📘
     ///
-    /// The Notion API does not yet support uploading files to Notion.
+    /// Files can be uploaded with the File Upload API, and then attached with
+    /// [`NamedFile::file_upload`].
     ///
     /// # 📘
     ///
     /// When updating a file page property value, the value is overwritten by
     /// the array of files passed.
     ///
-    /// Although Notion doesn't support uploading files via the API, if you pass
-    /// a file object containing a file hosted by Notion, it remains one of the
-    /// files. To remove any file, just don't pass it in the update response.
-    Files(Vec<File>),
+    /// If you pass a file object containing a file hosted by Notion, it remains
+    /// one of the files. To remove any file, just don't pass it in the update
+    /// response.
+    Files(Files),
     /// Lets you perform calculations or trigger actions based on other
     /// properties.
     ///
@@ -180,12 +179,11 @@ pub enum PropertyData {
     ///  If you want to add a new option to a multi-select property via the
     /// Update page or Update database endpoint, then your integration needs
     /// write access to the parent database.
-    MultiSelect(Vec<SelectOption>),
+    MultiSelect(MultiSelect),
     /// Numerical formats like currencies and percentages. Useful for price,
     /// etc.
     ///
     /// A number representing some value.
-    // TODO: figure out how to use int/float
     Number(Number),
     /// Lets you mention other users in your workspace (or assign them
     /// to things).
@@ -198,7 +196,7 @@ pub enum PropertyData {
     /// than 25 people, then you can use the Retrieve a page property item
     /// endpoint for the specific people property to get a complete list of
     /// people.
-    People(Vec<PartialUser>),
+    People(People),
     /// Accepts a phone number and prompts your phone or computer to call
     /// it when clicked.
     ///
@@ -217,7 +215,7 @@ pub enum PropertyData {
     /// If a relation property value is unexpectedly empty, then make sure that
     /// you have shared the original source database that the relation points to
     /// with the integration.
-    Relation(Vec<PageMention>),
+    Relation(Relation),
     /// Runs calculations based on properties in a related database.
     ///
     /// # 🚧
@@ -240,7 +238,6 @@ pub enum PropertyData {
     /// property includes more than 25 references, then you can use the Retrieve
     /// a page property item endpoint for the specific rich_text property to get
     /// its complete list of references.
-    // TODO: rollup is broken
     Rollup(Rollup),
     /// Basic text for notes, descriptions and comments about database items.
     RichText(RichText),
@@ -265,4 +262,83 @@ pub enum PropertyData {
     /// A short, unique id for each item in a database. This is not the same
     /// as the `id` property of the page object.
     UniqueId(UniqueId),
+    /// The verification status of a page in a wiki database.
+    Verification(Verification),
+    /// A property type that this crate doesn't know about yet, such as a
+    /// place, with the raw JSON of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl_unknown!(PropertyData, External);
+
+/// Accessors for the values of read-only property types, which are set by
+/// Notion rather than through the API. Each returns `None` for values of other
+/// types.
+impl PropertyData {
+    pub const fn button(&self) -> Option<&Button> {
+        match self {
+            Self::Button(button) => Some(button),
+            _ => None,
+        }
+    }
+
+    pub const fn created_by(&self) -> Option<&PartialUser> {
+        match self {
+            Self::CreatedBy(user) => Some(user),
+            _ => None,
+        }
+    }
+
+    pub const fn created_time(&self) -> Option<&DateOrDateTime> {
+        match self {
+            Self::CreatedTime(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    pub const fn formula(&self) -> Option<&FormulaData> {
+        match self {
+            Self::Formula(formula) => Some(formula),
+            _ => None,
+        }
+    }
+
+    pub const fn last_edited_by(&self) -> Option<&PartialUser> {
+        match self {
+            Self::LastEditedBy(user) => Some(user),
+            _ => None,
+        }
+    }
+
+    pub const fn last_edited_time(&self) -> Option<&DateOrDateTime> {
+        match self {
+            Self::LastEditedTime(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    pub const fn rollup(&self) -> Option<&Rollup> {
+        match self {
+            Self::Rollup(rollup) => Some(rollup),
+            _ => None,
+        }
+    }
+
+    pub const fn unique_id(&self) -> Option<&UniqueId> {
+        match self {
+            Self::UniqueId(unique_id) => Some(unique_id),
+            _ => None,
+        }
+    }
+
+    pub const fn verification(&self) -> Option<&Verification> {
+        match self {
+            Self::Verification(verification) => Some(verification),
+            _ => None,
+        }
+    }
 }
@@ -1 +1,52 @@
+use serde::{Deserialize, Serialize};
 
+use super::{Property, PropertyData, SelectOption};
+
+/// An array of select options.
+///
+/// To add an option that the database doesn't have yet, the integration
+/// needs write access to the database.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MultiSelect(pub Vec<SelectOption>);
+
+impl MultiSelect {
+    pub const fn new(options: Vec<SelectOption>) -> Self {
+        Self(options)
+    }
+
+    /// Options given by their names.
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        Self(
+            names
+                .into_iter()
+                .map(|name| SelectOption {
+                    color: None,
+                    id: None,
+                    name: Some(name.to_string()),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn build_with_name(self, name: &str) -> (String, Property) {
+        (
+            name.to_string(),
+            Property::new(PropertyData::MultiSelect(self)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let (_, property) = MultiSelect::from_names(["urgent", "backend"]).build_with_name("Tags");
+
+        assert_eq!(
+            serde_json::to_string(&property).unwrap(),
+            r#"{"multi_select":[{"name":"urgent"},{"name":"backend"}]}"#
+        );
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
-pub struct Number(Option<f64>);
+pub struct Number(pub Option<f64>);
 
 impl Number {
     pub fn new(number: f64) -> Self {
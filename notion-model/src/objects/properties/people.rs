@@ -1 +1,41 @@
+use serde::{Deserialize, Serialize};
 
+use super::{Property, PropertyData};
+use crate::{ids::UserId, objects::user::PartialUser};
+
+/// An array of users.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct People(pub Vec<PartialUser>);
+
+impl People {
+    pub const fn new(people: Vec<PartialUser>) -> Self {
+        Self(people)
+    }
+
+    /// Users given by their IDs.
+    pub fn from_ids(ids: impl IntoIterator<Item = UserId>) -> Self {
+        Self(ids.into_iter().map(|id| PartialUser { id }).collect())
+    }
+
+    pub fn build_with_name(self, name: &str) -> (String, Property) {
+        (name.to_string(), Property::new(PropertyData::People(self)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ids() {
+        let (_, property) = People::from_ids([UserId::from_str_unchecked(
+            "3e1fc0f5-d02e-48ae-84c0-7ae06deece9f",
+        )])
+        .build_with_name("Owner");
+
+        assert_eq!(
+            serde_json::to_string(&property).unwrap(),
+            r#"{"people":[{"object":"user","id":"3e1fc0f5-d02e-48ae-84c0-7ae06deece9f"}]}"#
+        );
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct PhoneNumber(Option<String>);
+pub struct PhoneNumber(pub Option<String>);
 
 impl PhoneNumber {
     pub fn new(phone_number: String) -> Self {
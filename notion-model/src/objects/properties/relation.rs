@@ -1 +1,65 @@
+use serde::{Deserialize, Serialize};
 
+use super::{Property, PropertyData};
+use crate::{ids::PageId, objects::rich_text::PageMention};
+
+/// An array of related pages.
+///
+/// To update a relation, the related database must be shared with the
+/// integration.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Relation(pub Vec<PageMention>);
+
+impl Relation {
+    pub const fn new(pages: Vec<PageMention>) -> Self {
+        Self(pages)
+    }
+
+    /// Pages given by their IDs.
+    pub fn from_ids(ids: impl IntoIterator<Item = PageId>) -> Self {
+        Self(ids.into_iter().map(PageMention::new).collect())
+    }
+
+    pub fn build_with_name(self, name: &str) -> (String, Property) {
+        (
+            name.to_string(),
+            Property::new(PropertyData::Relation(self)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ids() {
+        let (_, property) = Relation::from_ids([PageId::from_str_unchecked(
+            "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4",
+        )])
+        .build_with_name("Project");
+
+        assert_eq!(
+            serde_json::to_string(&property).unwrap(),
+            r#"{"relation":[{"id":"67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4"}]}"#
+        );
+    }
+
+    #[test]
+    fn response() {
+        let property = serde_json::from_str::<Property>(
+            r#"{
+                "id": "a",
+                "type": "relation",
+                "relation": [{ "id": "67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4" }],
+                "has_more": false
+            }"#,
+        )
+        .unwrap();
+
+        assert!(
+            matches!(property.data, PropertyData::Relation(Relation(pages)) if pages.len() == 1)
+        );
+        assert_eq!(property.has_more, Some(false));
+    }
+}
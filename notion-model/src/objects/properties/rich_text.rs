@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct RichText(Vec<crate::objects::rich_text::RichText>);
+pub struct RichText(pub Vec<crate::objects::rich_text::RichText>);
 
 impl RichText {
     pub fn new(text: Vec<crate::objects::rich_text::RichText>) -> Self {
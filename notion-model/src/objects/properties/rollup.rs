@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(tag = "type", rename_all = "snake_case")]
+use super::PropertyData;
+use crate::objects::rich_text::DateMention;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Rollup {
     /// The function that is evaluated for every page in the relation of the
     /// rollup.
@@ -12,20 +14,26 @@ pub struct Rollup {
 }
 
 /// The value of the calculated rollup.
-/// TODO: complete all types
-/// TODO: u32 is supposed to be a f64 as well
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum RollupData {
-    Array,
-    Date,
+    /// The values of the rolled up property, for functions such as
+    /// `show_original`. Each value has the type of the rolled up property.
+    Array { array: Vec<PropertyData> },
+    /// The result of a date function, such as `earliest_date` or
+    /// `date_range`.
+    Date { date: Option<DateMention> },
+    /// The rollup has more than 25 references, so the value must be
+    /// retrieved with the page property item endpoint.
     Incomplete,
-    Number(u32),
+    /// The result of a numeric function, such as `sum` or `percent_checked`.
+    Number { number: Option<f64> },
+    /// The API can't compute the rollup.
     Unsupported,
 }
 
 /// Functions that can be used in a rollup property.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum RollupFunction {
     Average,
@@ -52,4 +60,141 @@ pub enum RollupFunction {
     Sum,
     Unchecked,
     Unique,
+    /// A function that this crate doesn't know about yet, by name.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::objects::properties::{Number, Property, Title};
+
+    fn rollup(json: &str) -> Rollup {
+        match serde_json::from_str::<Property>(json).unwrap().data {
+            PropertyData::Rollup(rollup) => rollup,
+            data => panic!("not a rollup: {data:?}"),
+        }
+    }
+
+    #[test]
+    fn number() {
+        assert_eq!(
+            rollup(
+                r#"{
+                    "id": "%5Bm%3Ft",
+                    "type": "rollup",
+                    "rollup": { "type": "number", "number": 1049.75, "function": "sum" }
+                }"#
+            ),
+            Rollup {
+                function: RollupFunction::Sum,
+                data: RollupData::Number {
+                    number: Some(1049.75)
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn empty_number() {
+        assert_eq!(
+            rollup(
+                r#"{
+                    "id": "a",
+                    "type": "rollup",
+                    "rollup": { "type": "number", "number": null, "function": "average" }
+                }"#
+            )
+            .data,
+            RollupData::Number { number: None }
+        );
+    }
+
+    #[test]
+    fn date() {
+        let rollup = rollup(
+            r#"{
+                "id": "a",
+                "type": "rollup",
+                "rollup": {
+                    "type": "date",
+                    "date": { "start": "2024-01-01", "end": "2024-02-15", "time_zone": null },
+                    "function": "date_range"
+                }
+            }"#,
+        );
+
+        assert_eq!(rollup.function, RollupFunction::DateRange);
+        assert!(matches!(
+            rollup.data,
+            RollupData::Date { date: Some(date) } if date.end.is_some()
+        ));
+    }
+
+    #[test]
+    fn array() {
+        let rollup = rollup(
+            r#"{
+                "id": "a",
+                "type": "rollup",
+                "rollup": {
+                    "type": "array",
+                    "array": [
+                        { "type": "number", "number": -12.5 },
+                        { "type": "number", "number": null },
+                        {
+                            "type": "title",
+                            "title": [{
+                                "type": "text",
+                                "text": { "content": "Rent", "link": null },
+                                "plain_text": "Rent",
+                                "href": null
+                            }]
+                        }
+                    ],
+                    "function": "show_original"
+                }
+            }"#,
+        );
+
+        let RollupData::Array { array } = rollup.data else {
+            panic!("not an array");
+        };
+
+        assert_eq!(array.len(), 3);
+        assert_eq!(array[0], PropertyData::Number(Number(Some(-12.5))));
+        assert_eq!(array[1], PropertyData::Number(Number(None)));
+        assert!(matches!(&array[2], PropertyData::Title(Title(text)) if text.len() == 1));
+    }
+
+    #[test]
+    fn incomplete() {
+        assert_eq!(
+            rollup(
+                r#"{
+                    "id": "a",
+                    "type": "rollup",
+                    "rollup": { "type": "incomplete", "incomplete": {}, "function": "show_original" }
+                }"#
+            )
+            .data,
+            RollupData::Incomplete
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            rollup(
+                r#"{
+                    "id": "a",
+                    "type": "rollup",
+                    "rollup": { "type": "unsupported", "unsupported": {}, "function": "count_per_group" }
+                }"#
+            )
+            .data,
+            RollupData::Unsupported
+        );
+    }
 }
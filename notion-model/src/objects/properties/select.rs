@@ -4,7 +4,7 @@ use super::{Property, PropertyData};
 use crate::{ids::OptionId, objects::color::OptionColor};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct Select(Option<SelectOption>);
+pub struct Select(pub Option<SelectOption>);
 
 impl Select {
     pub fn new(option: SelectOption) -> Self {
@@ -4,7 +4,7 @@ use super::{Property, PropertyData};
 use crate::{ids::OptionId, objects::color::OptionColor};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct Status(Option<StatusOption>);
+pub struct Status(pub Option<StatusOption>);
 
 impl Status {
     pub fn new(option: StatusOption) -> Self {
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct Title(Vec<crate::objects::rich_text::RichText>);
+pub struct Title(pub Vec<crate::objects::rich_text::RichText>);
 
 impl Title {
     pub fn new(text: &str) -> Self {
@@ -2,18 +2,19 @@ use serde::{Deserialize, Serialize};
 
 use super::{Property, PropertyData};
 
+/// An automatically incremented ID, with an optional prefix. Unique IDs
+/// can't be set through the API.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(tag = "type", rename_all = "snake_case")]
 pub struct UniqueId {
-    pub prefix: String,
-    pub number: u32,
+    pub prefix: Option<String>,
+    pub number: Option<u32>,
 }
 
 impl UniqueId {
     pub fn new(prefix: &str, number: u32) -> Self {
         Self {
-            prefix: prefix.to_string(),
-            number,
+            prefix: Some(prefix.to_string()),
+            number: Some(number),
         }
     }
 
@@ -24,3 +25,24 @@ impl UniqueId {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response() {
+        let property = serde_json::from_str::<Property>(
+            r#"{"id":"a","type":"unique_id","unique_id":{"prefix":null,"number":42}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            property.data.unique_id(),
+            Some(&UniqueId {
+                prefix: None,
+                number: Some(42),
+            })
+        );
+    }
+}
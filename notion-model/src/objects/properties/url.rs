@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Property, PropertyData};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct Url(Option<String>);
+pub struct Url(pub Option<String>);
 
 impl Url {
     pub fn new(url: String) -> Self {
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::objects::{rich_text::DateMention, user::PartialUser};
+
+/// The verification status of a page in a wiki database.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Verification {
+    pub state: VerificationState,
+    /// The user who verified the page.
+    #[serde(default)]
+    pub verified_by: Option<PartialUser>,
+    /// When the page was verified, and when the verification expires, if it
+    /// does.
+    #[serde(default)]
+    pub date: Option<DateMention>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationState {
+    Verified,
+    Unverified,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::objects::properties::{Property, PropertyData};
+
+    #[test]
+    fn verified() {
+        let property = serde_json::from_str::<Property>(
+            r#"{
+                "id": "a",
+                "type": "verification",
+                "verification": {
+                    "state": "verified",
+                    "verified_by": {
+                        "object": "user",
+                        "id": "3e1fc0f5-d02e-48ae-84c0-7ae06deece9f"
+                    },
+                    "date": { "start": "2024-01-01T00:00:00.000Z", "end": null, "time_zone": null }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let verification = property.data.verification().unwrap();
+
+        assert_eq!(verification.state, VerificationState::Verified);
+        assert!(verification.verified_by.is_some());
+        assert!(verification.date.is_some());
+    }
+
+    #[test]
+    fn unverified() {
+        let property = serde_json::from_str::<Property>(
+            r#"{
+                "id": "a",
+                "type": "verification",
+                "verification": { "state": "unverified", "verified_by": null, "date": null }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            property.data,
+            PropertyData::Verification(Verification {
+                state: VerificationState::Unverified,
+                verified_by: None,
+                date: None,
+            })
+        );
+    }
+}
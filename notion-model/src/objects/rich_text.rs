@@ -5,6 +5,7 @@ use super::date::DateOrDateTime;
 use crate::{
     ids::{DatabaseId, PageId, UserId},
     objects::color::Color,
+    unknown::impl_unknown,
 };
 
 /// Rich text objects contain the data that Notion uses to display Notion
@@ -131,13 +132,22 @@ impl RichText {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(rename_all = "snake_case")]
+#[serde(remote = "Self", rename_all = "snake_case")]
 pub enum RichTextData {
     Text(Text),
     Mention(Mention),
     Equation(Equation),
+    /// A rich text type that this crate doesn't know about yet, with the raw
+    /// JSON of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
 }
 
+impl_unknown!(RichTextData, External);
+
 /// All rich text objects contain an annotations object that sets the styling
 /// for the rich text.
 ///
@@ -182,16 +192,37 @@ pub struct Equation {
 ///
 /// TODO: remove tag = "type"
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case", tag = "type")]
+#[serde(remote = "Self", rename_all = "snake_case", tag = "type")]
 pub enum Mention {
-    Database { database: DatabaseMention },
-    Date { date: DateMention },
-    LinkPreview { link_preview: LinkPreviewMention },
-    Page { page: PageMention },
-    Template { template: TemplateMention },
-    User { user: UserMention },
+    Database {
+        database: DatabaseMention,
+    },
+    Date {
+        date: DateMention,
+    },
+    LinkPreview {
+        link_preview: LinkPreviewMention,
+    },
+    Page {
+        page: PageMention,
+    },
+    Template {
+        template: TemplateMention,
+    },
+    User {
+        user: UserMention,
+    },
+    /// A mention type that this crate doesn't know about yet, with the raw
+    /// JSON of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
 }
 
+impl_unknown!(Mention, Internal);
+
 /// # Database mention object
 ///
 /// Database mentions contain a database reference within the corresponding
@@ -229,13 +260,13 @@ impl DatabaseMention {
 #[serde(rename_all = "snake_case")]
 pub struct DateMention {
     /// An ISO 8601 format date, with optional time.
-    start: DateOrDateTime,
+    pub start: DateOrDateTime,
     /// An ISO 8601 formatted date, with optional time. Represents the end of a
     /// date range.
     ///
     /// If `None`, this property's date value is not a range.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    end: Option<DateOrDateTime>,
+    pub end: Option<DateOrDateTime>,
     /// Time zone information for start and end. Possible values are extracted
     /// from the IANA database and they are based on the time zones from
     /// Moment.js.
@@ -247,7 +278,7 @@ pub struct DateMention {
     /// If `None`, time zone information will be contained in UTC offsets in
     /// start and end.
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "time_zone")]
-    timezone: Option<Tz>,
+    pub timezone: Option<Tz>,
 }
 
 impl DateMention {
@@ -3,7 +3,114 @@ use serde::{Deserialize, Serialize};
 use crate::ids::UserId;
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(tag = "object")]
+#[serde(tag = "object", rename = "user")]
 pub struct PartialUser {
     pub id: UserId,
 }
+
+/// # User
+///
+/// The User object represents a user in a Notion workspace. Users include
+/// full workspace members, guests, and integrations.
+///
+/// The `id` is always present. The other fields are only present when the
+/// user is retrieved from the users endpoints, not when a user is referenced
+/// by another object, such as the `created_by` of a page.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "object", rename = "user")]
+pub struct User {
+    /// Unique identifier for this user.
+    pub id: UserId,
+    /// User's name, as displayed in Notion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Chosen avatar image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    /// Whether the user is a person or a bot, and type-specific information.
+    #[serde(flatten)]
+    pub data: Option<UserData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum UserData {
+    /// User objects that represent people.
+    Person(Person),
+    /// A user object that represents a bot, such as an integration.
+    Bot(Bot),
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Person {
+    /// Email address of person. This is only present if an integration has
+    /// user capabilities that allow access to email addresses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Bot {
+    /// Information about who owns this bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<BotOwner>,
+    /// If the owner is a workspace, then the name of the workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotOwner {
+    /// The bot is owned by the workspace. Always true.
+    Workspace { workspace: bool },
+    /// The bot is owned by a user.
+    User { user: Box<User> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn person() {
+        let value: User = serde_json::from_str(
+            r#"{"object":"user","id":"d40e767c-d7af-4b18-a86d-55c61f1e39a4","type":"person","person":{"email":"avo@example.org"},"name":"Avocado Lovelace","avatar_url":"https://secure.notion-static.com/e6a352a8-8381-44d0-a1dc-9ed80e62b53d.jpg"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.name.as_deref(), Some("Avocado Lovelace"));
+        assert_eq!(
+            value.data,
+            Some(UserData::Person(Person {
+                email: Some("avo@example.org".to_string())
+            }))
+        );
+    }
+
+    #[test]
+    fn bot() {
+        let value: User = serde_json::from_str(
+            r#"{"object":"user","id":"9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57","name":"Doug Engelbot","avatar_url":null,"type":"bot","bot":{"owner":{"type":"workspace","workspace":true},"workspace_name":"Ada's Notion Workspace"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.data,
+            Some(UserData::Bot(Bot {
+                owner: Some(BotOwner::Workspace { workspace: true }),
+                workspace_name: Some("Ada's Notion Workspace".to_string()),
+            }))
+        );
+    }
+
+    #[test]
+    fn partial() {
+        let value: User = serde_json::from_str(
+            r#"{"object":"user","id":"9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.data, None);
+    }
+}
@@ -8,6 +8,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::unknown::impl_unknown;
+
 /// Paginated list of blocks
 ///
 /// see <https://developers.notion.com/reference/intro#parameters-for-paginated-requests>
@@ -33,17 +35,28 @@ pub struct List<T> {
     pub data: ListData,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
-#[serde(tag = "type", rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(remote = "Self", tag = "type", rename_all = "snake_case")]
 pub enum ListData {
     Block,
     Comment,
     Database,
     Page,
     PageOrDatabase,
-    PropertyItem { property_item: PropertyItem },
+    PropertyItem {
+        property_item: PropertyItem,
+    },
     User,
+    /// A list type that this crate doesn't know about yet, with the raw JSON
+    /// of its type-specific object.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
 }
 
+impl_unknown!(ListData, Internal);
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
 pub struct PropertyItem {}
@@ -0,0 +1,127 @@
+//! # Database queries
+//!
+//! Request body for querying a database. Results are returned as a paginated
+//! list of pages, see [`crate::pagination`].
+
+use serde::{Deserialize, Serialize};
+
+/// Body of a query database request.
+///
+/// see <https://developers.notion.com/reference/post-database-query>
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct DatabaseQuery {
+    /// When supplied, limits which pages are returned based on the filter
+    /// conditions.
+    ///
+    /// The filter is passed to the API as is. See
+    /// <https://developers.notion.com/reference/post-database-query-filter>
+    /// for the format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<serde_json::Value>,
+    /// When supplied, orders the results based on the provided sort criteria.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sorts: Vec<Sort>,
+    /// When supplied, returns a page of results starting after the cursor
+    /// provided. If not supplied, this endpoint will return the first page of
+    /// results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_cursor: Option<String>,
+    /// The number of items from the full list desired in the response. Maximum:
+    /// 100.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+impl DatabaseQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn filter(mut self, filter: Option<serde_json::Value>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn sorts(mut self, sorts: Vec<Sort>) -> Self {
+        self.sorts = sorts;
+        self
+    }
+
+    pub fn start_cursor(mut self, start_cursor: Option<String>) -> Self {
+        self.start_cursor = start_cursor;
+        self
+    }
+
+    pub const fn page_size(mut self, page_size: Option<u32>) -> Self {
+        self.page_size = page_size;
+        self
+    }
+}
+
+/// A sort criterion. Sorts are applied in the order they are given.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(untagged)]
+pub enum Sort {
+    /// Sort by the value of a property.
+    Property {
+        /// The name of the property to sort against.
+        property: String,
+        /// The direction to sort.
+        direction: SortDirection,
+    },
+    /// Sort by the time a page was created or last edited.
+    Timestamp {
+        /// The name of the timestamp to sort against.
+        timestamp: SortTimestamp,
+        /// The direction to sort.
+        direction: SortDirection,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SortTimestamp {
+    CreatedTime,
+    LastEditedTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(serde_json::to_string(&DatabaseQuery::new()).unwrap(), "{}");
+    }
+
+    #[test]
+    fn complete() {
+        let value = DatabaseQuery::new()
+            .filter(Some(
+                serde_json::json!({"property":"Done","checkbox":{"equals":true}}),
+            ))
+            .sorts(vec![
+                Sort::Property {
+                    property: "Name".to_string(),
+                    direction: SortDirection::Ascending,
+                },
+                Sort::Timestamp {
+                    timestamp: SortTimestamp::LastEditedTime,
+                    direction: SortDirection::Descending,
+                },
+            ])
+            .page_size(Some(100));
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"filter":{"checkbox":{"equals":true},"property":"Done"},"sorts":[{"property":"Name","direction":"ascending"},{"timestamp":"last_edited_time","direction":"descending"}],"page_size":100}"#
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! # Search
+//!
+//! Request body for searching the pages and databases shared with an
+//! integration. Results are returned as a paginated list of
+//! [`PageOrDatabase`], see [`crate::pagination`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    objects::{database::Database, page::Page},
+    query::{SortDirection, SortTimestamp},
+};
+
+/// Body of a search request.
+///
+/// see <https://developers.notion.com/reference/post-search>
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct SearchQuery {
+    /// The text that the API compares page and database titles against. When
+    /// not supplied, every page and database shared with the integration is
+    /// returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Limits the results to either only pages or only databases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SearchFilter>,
+    /// Orders the results. Without a sort, the most relevant results are
+    /// returned first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SearchSort>,
+    /// When supplied, returns a page of results starting after the cursor
+    /// provided. If not supplied, this endpoint will return the first page of
+    /// results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_cursor: Option<String>,
+    /// The number of items from the full list desired in the response. Maximum:
+    /// 100.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+impl SearchQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn query(mut self, query: Option<String>) -> Self {
+        self.query = query;
+        self
+    }
+
+    pub const fn filter(mut self, filter: Option<SearchFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub const fn sort(mut self, sort: Option<SearchSort>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn start_cursor(mut self, start_cursor: Option<String>) -> Self {
+        self.start_cursor = start_cursor;
+        self
+    }
+
+    pub const fn page_size(mut self, page_size: Option<u32>) -> Self {
+        self.page_size = page_size;
+        self
+    }
+}
+
+/// A search filter. The only property that can be filtered on is `object`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(tag = "property", rename_all = "snake_case")]
+pub enum SearchFilter {
+    /// Only return objects of the given type.
+    Object { value: SearchObject },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchObject {
+    Page,
+    Database,
+}
+
+/// A search sort. The only timestamp that can be sorted on is
+/// `last_edited_time`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SearchSort {
+    /// The direction to sort.
+    pub direction: SortDirection,
+    /// The name of the timestamp to sort against.
+    pub timestamp: SortTimestamp,
+}
+
+/// A search result.
+///
+/// Databases are tried first, as a page can't have the `title` field that all
+/// databases have.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum PageOrDatabase {
+    Database(Database),
+    Page(Page),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(serde_json::to_string(&SearchQuery::new()).unwrap(), "{}");
+    }
+
+    #[test]
+    fn complete() {
+        let value = SearchQuery::new()
+            .query(Some("External tasks".to_string()))
+            .filter(Some(SearchFilter::Object {
+                value: SearchObject::Database,
+            }))
+            .sort(Some(SearchSort {
+                direction: SortDirection::Ascending,
+                timestamp: SortTimestamp::LastEditedTime,
+            }))
+            .page_size(Some(10));
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"query":"External tasks","filter":{"property":"object","value":"database"},"sort":{"direction":"ascending","timestamp":"last_edited_time"},"page_size":10}"#
+        );
+    }
+
+    #[test]
+    fn results() {
+        let value: Vec<PageOrDatabase> = serde_json::from_str(
+            r#"[{"object":"database","id":"bc1211ca-e3f1-4939-ae34-5260b16f627c","title":[],"description":[],"properties":{}},{"object":"page","id":"59833787-2cf9-4fdf-8782-e53db20768a5","properties":{}}]"#,
+        )
+        .unwrap();
+
+        assert!(matches!(value[0], PageOrDatabase::Database(_)));
+        assert!(matches!(value[1], PageOrDatabase::Page(_)));
+    }
+}
@@ -0,0 +1,377 @@
+//! # Unknown types
+//!
+//! The API adds new block, property, mention and other types over time. The
+//! enums for them keep any type they don't know about in an `Unknown` variant,
+//! with the name of the type and the raw JSON of its type-specific object,
+//! rather than failing to deserialize the whole response. Unknown variants
+//! serialize back to the JSON they came from.
+//!
+//! The known variants use the derived implementations, with
+//! `#[serde(remote = "Self")]` so that they can be wrapped by
+//! [`impl_unknown!`].
+
+use std::{fmt, iter};
+
+use serde::{
+    de::{self, value::MapDeserializer, IntoDeserializer},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serializer,
+};
+use serde_json::{Map, Value};
+
+/// How an enum says which type it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tagging {
+    /// Externally tagged, as in `{ "paragraph": { ... } }`, and flattened into
+    /// a parent that has a `type` field. Requests may leave out the `type`.
+    External,
+    /// Internally tagged, as in `{ "type": "emoji", "emoji": "🥬" }`.
+    Internal,
+    /// Adjacently tagged, as in `{ "type": "page.created", "data": { ... } }`.
+    Adjacent,
+}
+
+/// The field that holds the type-specific object of adjacently tagged enums.
+const CONTENT: &str = "data";
+
+/// An enum with an `Unknown { type_name, raw }` variant.
+pub trait Tagged: Sized {
+    const TAGGING: Tagging;
+
+    /// Deserializes one of the known types, using the derived implementation.
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+
+    fn unknown(type_name: String, raw: Value) -> Self;
+}
+
+/// The error for probing the derived implementation for its known types.
+#[derive(Debug)]
+enum Probe {
+    Types(&'static [&'static str]),
+    Other,
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("probe")
+    }
+}
+
+impl std::error::Error for Probe {}
+
+impl de::Error for Probe {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Self::Other
+    }
+
+    fn unknown_variant(_variant: &str, expected: &'static [&'static str]) -> Self {
+        Self::Types(expected)
+    }
+}
+
+/// The names of the types that `T` knows about.
+///
+/// Found by deserializing a type name that can't exist, and catching the
+/// list of expected types in the error.
+fn known_types<T: Tagged>() -> &'static [&'static str] {
+    const NOWHERE: &str = "\0";
+
+    let result = match T::TAGGING {
+        Tagging::External => {
+            T::deserialize_known(IntoDeserializer::<Probe>::into_deserializer(NOWHERE))
+        },
+        Tagging::Internal | Tagging::Adjacent => T::deserialize_known(
+            MapDeserializer::<_, Probe>::new(iter::once(("type", NOWHERE))),
+        ),
+    };
+
+    match result {
+        Err(Probe::Types(types)) => types,
+        _ => &[],
+    }
+}
+
+/// Deserializes `T`, keeping types that it doesn't know as unknown.
+pub fn deserialize<'de, T: Tagged, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let mut map = Map::deserialize(deserializer)?;
+    let known = known_types::<T>();
+
+    let type_name = match map.get("type") {
+        Some(Value::String(type_name)) => type_name.clone(),
+        Some(_) => return Err(de::Error::custom("`type` is not a string")),
+        None if T::TAGGING == Tagging::External => map
+            .keys()
+            .find(|key| known.contains(&key.as_str()))
+            .cloned()
+            .ok_or_else(|| de::Error::missing_field("type"))?,
+        None => return Err(de::Error::missing_field("type")),
+    };
+
+    let content = match T::TAGGING {
+        Tagging::Adjacent => CONTENT,
+        _ => &type_name,
+    };
+
+    if !known.contains(&type_name.as_str()) {
+        let raw = map.remove(content).unwrap_or(Value::Null);
+        return Ok(T::unknown(type_name, raw));
+    }
+
+    let value = match T::TAGGING {
+        Tagging::External => {
+            let data = map.remove(&type_name).unwrap_or(Value::Null);
+            Value::Object(Map::from_iter([(type_name, data)]))
+        },
+        Tagging::Internal => Value::Object(map),
+        // other fields are left out, as the enum may be flattened into a
+        // parent
+        Tagging::Adjacent => {
+            let data = map.remove(CONTENT).unwrap_or(Value::Null);
+            Value::Object(Map::from_iter([
+                ("type".to_owned(), Value::String(type_name)),
+                (CONTENT.to_owned(), data),
+            ]))
+        },
+    };
+
+    T::deserialize_known(value).map_err(de::Error::custom)
+}
+
+/// Serializes an unknown type as it was received.
+pub fn serialize<S: Serializer>(
+    tagging: Tagging,
+    type_name: &str,
+    raw: &Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let content = match tagging {
+        Tagging::Adjacent => CONTENT,
+        _ => type_name,
+    };
+
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry("type", type_name)?;
+    map.serialize_entry(content, raw)?;
+    map.end()
+}
+
+/// Implements [`Serialize`](serde::Serialize) and
+/// [`Deserialize`](serde::Deserialize) for an enum that derives them with
+/// `#[serde(remote = "Self")]`, and has a skipped
+/// `Unknown { type_name: String, raw: Value }` variant.
+macro_rules! impl_unknown {
+    ($ty:ty, $tagging:ident) => {
+        impl $crate::unknown::Tagged for $ty {
+            const TAGGING: $crate::unknown::Tagging = $crate::unknown::Tagging::$tagging;
+
+            fn deserialize_known<'de, D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                Self::deserialize(deserializer)
+            }
+
+            fn unknown(type_name: String, raw: ::serde_json::Value) -> Self {
+                Self::Unknown { type_name, raw }
+            }
+        }
+
+        impl ::serde::Serialize for $ty {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    Self::Unknown { type_name, raw } => $crate::unknown::serialize(
+                        $crate::unknown::Tagging::$tagging,
+                        type_name,
+                        raw,
+                        serializer,
+                    ),
+                    _ => Self::serialize(self, serializer),
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $ty {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                $crate::unknown::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_unknown;
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        objects::{
+            block::{Block, BlockData},
+            database_property::{DatabaseProperty, DatabasePropertyData},
+            file_and_emoji::FileOrEmoji,
+            properties::{Property, PropertyData, RollupFunction},
+            rich_text::{Mention, RichText, RichTextData},
+        },
+        pagination::{List, ListData},
+    };
+
+    /// Deserializes JSON as `T`, checking that it serializes back unchanged.
+    fn round_trip<T: serde::Serialize + de::DeserializeOwned>(json: Value) -> T {
+        let value = serde_json::from_value::<T>(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), json);
+        value
+    }
+
+    #[test]
+    fn external_types() {
+        let types = known_types::<BlockData>();
+
+        assert!(types.contains(&"paragraph"));
+        assert!(types.contains(&"heading_1"));
+        assert!(!types.contains(&"unknown"));
+    }
+
+    #[test]
+    fn internal_types() {
+        assert_eq!(
+            known_types::<FileOrEmoji>(),
+            &["emoji", "file", "external", "file_upload", "custom_emoji"]
+        );
+    }
+
+    #[test]
+    fn block() {
+        let block = round_trip::<Block>(json!({
+            "object": "block",
+            "has_children": false,
+            "type": "transcription",
+            "transcription": { "title": [], "status": "notes_ready" },
+        }));
+
+        assert_eq!(
+            block.data,
+            BlockData::Unknown {
+                type_name: "transcription".to_owned(),
+                raw: json!({ "title": [], "status": "notes_ready" }),
+            }
+        );
+    }
+
+    #[test]
+    fn known_block() {
+        let block = serde_json::from_value::<Block>(json!({
+            "object": "block",
+            "type": "divider",
+            "divider": {},
+            "in_trash": false,
+        }))
+        .unwrap();
+
+        assert!(matches!(block.data, BlockData::Divider(_)));
+    }
+
+    #[test]
+    fn known_block_without_type() {
+        let block =
+            serde_json::from_value::<Block>(json!({ "object": "block", "divider": {} })).unwrap();
+
+        assert!(matches!(block.data, BlockData::Divider(_)));
+    }
+
+    #[test]
+    fn property() {
+        let property = round_trip::<Property>(json!({
+            "id": "abc",
+            "type": "place",
+            "place": { "lat": 40.7, "lon": -74.0 },
+        }));
+
+        assert!(
+            matches!(property.data, PropertyData::Unknown { type_name, .. } if type_name == "place")
+        );
+    }
+
+    #[test]
+    fn database_property() {
+        let property = round_trip::<DatabaseProperty>(json!({
+            "id": "abc",
+            "name": "Where",
+            "type": "place",
+            "place": {},
+        }));
+
+        assert!(matches!(
+            property.data,
+            DatabasePropertyData::Unknown { type_name, .. } if type_name == "place"
+        ));
+
+        let property = round_trip::<DatabaseProperty>(json!({
+            "id": "def",
+            "name": "Total",
+            "rollup": {
+                "function": "geometric_mean",
+                "relation_property_id": "ghi",
+                "rollup_property_id": "jkl",
+            },
+        }));
+
+        assert!(matches!(
+            property.data,
+            DatabasePropertyData::Rollup(config)
+                if config.function == RollupFunction::Unknown("geometric_mean".to_owned())
+        ));
+    }
+
+    #[test]
+    fn rich_text() {
+        let text = round_trip::<RichText>(json!({
+            "plain_text": "x",
+            "type": "sparkle",
+            "sparkle": { "x": 1 },
+        }));
+
+        assert!(matches!(text.data, RichTextData::Unknown { .. }));
+    }
+
+    #[test]
+    fn mention() {
+        let mention = round_trip::<Mention>(json!({
+            "type": "custom_emoji",
+            "custom_emoji": { "id": "1", "name": "kale" },
+        }));
+
+        assert!(matches!(mention, Mention::Unknown { .. }));
+    }
+
+    #[test]
+    fn file_or_emoji() {
+        let icon = round_trip::<FileOrEmoji>(json!({
+            "type": "sticker",
+            "sticker": { "id": "1", "name": "kale", "url": "https://example.com" },
+        }));
+
+        assert!(matches!(icon, FileOrEmoji::Unknown { .. }));
+    }
+
+    #[test]
+    fn list() {
+        let list = round_trip::<List<Value>>(json!({
+            "object": "list",
+            "has_more": false,
+            "next_cursor": null,
+            "results": [],
+            "type": "template",
+            "template": {},
+        }));
+
+        assert!(matches!(list.data, ListData::Unknown { .. }));
+    }
+
+    #[test]
+    fn bad_known_type() {
+        assert!(serde_json::from_value::<FileOrEmoji>(json!({ "type": "emoji" })).is_err());
+        assert!(serde_json::from_value::<FileOrEmoji>(json!({ "emoji": "🥬" })).is_err());
+    }
+}
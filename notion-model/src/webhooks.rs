@@ -0,0 +1,393 @@
+//! # Webhooks
+//!
+//! Integrations can subscribe to events in the workspaces they have access
+//! to. Notion sends each event to the subscription's URL as a POST request,
+//! signed with the subscription's verification token.
+//!
+//! Events only say what changed, not how: retrieve the entity to get its
+//! current state. Events can be delivered more than once, and out of order.
+//!
+//! see <https://developers.notion.com/reference/webhooks>
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    ids::{
+        BlockId, CommentId, DatabaseId, EventId, IntegrationId, PageId, PropertyId, SubscriptionId,
+        UserId, WorkspaceId,
+    },
+    objects::date::DateOrDateTime,
+    unknown::impl_unknown,
+};
+
+/// # Webhook delivery
+///
+/// The body of a request to a webhook URL.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(untagged)]
+pub enum Delivery {
+    /// Sent once when a subscription is created, with the token to paste in
+    /// the integration's settings to verify the subscription. The token also
+    /// signs every event.
+    Verification {
+        verification_token: String,
+    },
+    Event(Box<Event>),
+}
+
+/// # Webhook event
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Event {
+    /// Identifier for the event, which is the same for every delivery of it.
+    pub id: EventId,
+    /// When the event happened.
+    pub timestamp: DateOrDateTime,
+    pub workspace_id: WorkspaceId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    pub subscription_id: SubscriptionId,
+    pub integration_id: IntegrationId,
+    /// Who made the change.
+    #[serde(default)]
+    pub authors: Vec<Author>,
+    /// The users and bots that can see the entity, if the integration has
+    /// the capability to read user information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessible_by: Option<Vec<Author>>,
+    /// How many times the event has been delivered, from 1.
+    pub attempt_number: u32,
+    /// What the event is about.
+    pub entity: Entity,
+    /// The type of the event, and what changed.
+    #[serde(flatten)]
+    pub data: EventData,
+}
+
+/// The type of an event, with what changed.
+///
+/// Events that happen close together, such as edits to a page, are
+/// aggregated into one event.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(remote = "Self", tag = "type", content = "data")]
+pub enum EventData {
+    #[serde(rename = "page.created")]
+    PageCreated(ParentData),
+    /// The content of the page changed.
+    #[serde(rename = "page.content_updated")]
+    PageContentUpdated(ContentUpdated),
+    #[serde(rename = "page.properties_updated")]
+    PagePropertiesUpdated(PropertiesUpdated),
+    #[serde(rename = "page.moved")]
+    PageMoved(ParentData),
+    /// The page was moved to the trash.
+    #[serde(rename = "page.deleted")]
+    PageDeleted(ParentData),
+    /// The page was restored from the trash.
+    #[serde(rename = "page.undeleted")]
+    PageUndeleted(ParentData),
+    #[serde(rename = "page.locked")]
+    PageLocked(ParentData),
+    #[serde(rename = "page.unlocked")]
+    PageUnlocked(ParentData),
+    #[serde(rename = "database.created")]
+    DatabaseCreated(ParentData),
+    /// The rows of the database changed, not their content.
+    #[serde(rename = "database.content_updated")]
+    DatabaseContentUpdated(ContentUpdated),
+    /// Properties of the database were added, renamed or removed.
+    #[serde(rename = "database.schema_updated")]
+    DatabaseSchemaUpdated(SchemaUpdated),
+    #[serde(rename = "database.moved")]
+    DatabaseMoved(ParentData),
+    #[serde(rename = "database.deleted")]
+    DatabaseDeleted(ParentData),
+    #[serde(rename = "database.undeleted")]
+    DatabaseUndeleted(ParentData),
+    #[serde(rename = "comment.created")]
+    CommentCreated(CommentData),
+    #[serde(rename = "comment.updated")]
+    CommentUpdated(CommentData),
+    #[serde(rename = "comment.deleted")]
+    CommentDeleted(CommentData),
+    /// An event type that this crate doesn't know about yet, with the raw
+    /// JSON of its data.
+    #[serde(skip)]
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl_unknown!(EventData, Adjacent);
+
+impl EventData {
+    /// The name of the event type, like `page.created`.
+    pub fn type_name(&self) -> &str {
+        match self {
+            Self::PageCreated(_) => "page.created",
+            Self::PageContentUpdated(_) => "page.content_updated",
+            Self::PagePropertiesUpdated(_) => "page.properties_updated",
+            Self::PageMoved(_) => "page.moved",
+            Self::PageDeleted(_) => "page.deleted",
+            Self::PageUndeleted(_) => "page.undeleted",
+            Self::PageLocked(_) => "page.locked",
+            Self::PageUnlocked(_) => "page.unlocked",
+            Self::DatabaseCreated(_) => "database.created",
+            Self::DatabaseContentUpdated(_) => "database.content_updated",
+            Self::DatabaseSchemaUpdated(_) => "database.schema_updated",
+            Self::DatabaseMoved(_) => "database.moved",
+            Self::DatabaseDeleted(_) => "database.deleted",
+            Self::DatabaseUndeleted(_) => "database.undeleted",
+            Self::CommentCreated(_) => "comment.created",
+            Self::CommentUpdated(_) => "comment.updated",
+            Self::CommentDeleted(_) => "comment.deleted",
+            Self::Unknown { type_name, .. } => type_name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParentData {
+    /// Where the entity is, after the event.
+    pub parent: Entity,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ContentUpdated {
+    pub parent: Entity,
+    /// The blocks that were added, changed or removed.
+    #[serde(default)]
+    pub updated_blocks: Vec<Entity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct PropertiesUpdated {
+    pub parent: Entity,
+    /// The IDs of the properties whose values changed.
+    #[serde(default)]
+    pub updated_properties: Vec<PropertyId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SchemaUpdated {
+    pub parent: Entity,
+    #[serde(default)]
+    pub updated_properties: Vec<SchemaChange>,
+}
+
+/// A database property that was added, changed or removed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SchemaChange {
+    pub id: PropertyId,
+    pub name: String,
+    pub action: SchemaAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaAction {
+    Created,
+    Updated,
+    Deleted,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CommentData {
+    /// The page that the comment is on.
+    pub page_id: PageId,
+    /// The page, or the block of a discussion, that the comment is in.
+    pub parent: Entity,
+}
+
+/// # Entity
+///
+/// A reference to a page, database, block, comment or other object.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Entity {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub kind: EntityKind,
+}
+
+impl Entity {
+    pub fn page_id(&self) -> Option<PageId> {
+        (self.kind == EntityKind::Page).then(|| self.id.into())
+    }
+
+    pub fn database_id(&self) -> Option<DatabaseId> {
+        (self.kind == EntityKind::Database).then(|| self.id.into())
+    }
+
+    /// Pages are blocks too, so this is also the ID of a page.
+    pub fn block_id(&self) -> Option<BlockId> {
+        matches!(self.kind, EntityKind::Block | EntityKind::Page).then(|| self.id.into())
+    }
+
+    pub fn comment_id(&self) -> Option<CommentId> {
+        (self.kind == EntityKind::Comment).then(|| self.id.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Page,
+    Database,
+    Block,
+    Comment,
+    /// The top level of the workspace.
+    Space,
+    #[serde(other)]
+    Unknown,
+}
+
+/// A user or bot that caused an event, or can see its entity.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Author {
+    pub id: UserId,
+    #[serde(rename = "type")]
+    pub kind: AuthorKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorKind {
+    Person,
+    Bot,
+    Agent,
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn payload(type_name: &str, data: serde_json::Value) -> serde_json::Value {
+        json!({
+            "id": "367cba44-b6f3-4c92-81e7-6a2e9659efd4",
+            "timestamp": "2024-12-05T23:55:34.285Z",
+            "workspace_id": "13950b26-c203-4f3b-b97d-93ec06319565",
+            "workspace_name": "Quantify Labs",
+            "subscription_id": "29d75c0d-5546-4414-8459-7b7a92f1fc4b",
+            "integration_id": "0ef2e755-4912-8096-91c1-00376a88a5ca",
+            "type": type_name,
+            "authors": [{ "id": "c7c11cca-1d73-471d-9b6e-bdef51470190", "type": "person" }],
+            "accessible_by": [
+                { "id": "556a1abf-4f08-40c6-878a-75890d2a88ba", "type": "person" },
+                { "id": "1edc05f6-2702-81b5-8408-00279347f034", "type": "bot" },
+            ],
+            "attempt_number": 1,
+            "entity": { "id": "153104cd-477e-809d-8dc4-ff2d96ae3090", "type": "page" },
+            "data": data,
+        })
+    }
+
+    #[test]
+    fn verification() {
+        let delivery = serde_json::from_value::<Delivery>(json!({
+            "verification_token": "secret_tMrlL1qK5vuQAh1b6cZGhFChZTSYJlce98V0pYn7yBl",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            delivery,
+            Delivery::Verification {
+                verification_token: "secret_tMrlL1qK5vuQAh1b6cZGhFChZTSYJlce98V0pYn7yBl".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn page_created() {
+        let value = payload(
+            "page.created",
+            json!({ "parent": { "id": "13950b26-c203-4f3b-b97d-93ec06319565", "type": "space" } }),
+        );
+
+        let Delivery::Event(event) = serde_json::from_value::<Delivery>(value).unwrap() else {
+            panic!("not an event");
+        };
+
+        assert_eq!(event.attempt_number, 1);
+        assert_eq!(event.authors[0].kind, AuthorKind::Person);
+        assert_eq!(
+            event.entity.page_id(),
+            Some(PageId::from_str_unchecked(
+                "153104cd-477e-809d-8dc4-ff2d96ae3090"
+            ))
+        );
+        assert_eq!(event.entity.database_id(), None);
+        assert!(
+            matches!(&event.data, EventData::PageCreated(data) if data.parent.kind == EntityKind::Space)
+        );
+        assert_eq!(event.data.type_name(), "page.created");
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "page.created");
+        assert_eq!(serde_json::from_value::<Event>(value).unwrap(), *event);
+    }
+
+    #[test]
+    fn updates() {
+        let event = serde_json::from_value::<Event>(payload(
+            "page.properties_updated",
+            json!({
+                "parent": { "id": "13950b26-c203-4f3b-b97d-93ec06319565", "type": "database" },
+                "updated_properties": ["XGe%40", "bDf%5B", "DbAu"],
+            }),
+        ))
+        .unwrap();
+
+        let EventData::PagePropertiesUpdated(data) = event.data else {
+            panic!("not page.properties_updated");
+        };
+        assert_eq!(data.updated_properties.len(), 3);
+        assert!(data.parent.database_id().is_some());
+
+        let event = serde_json::from_value::<Event>(payload(
+            "database.schema_updated",
+            json!({
+                "parent": { "id": "13950b26-c203-4f3b-b97d-93ec06319565", "type": "page" },
+                "updated_properties": [{ "id": "KZ%7BD", "name": "Due", "action": "created" }],
+            }),
+        ))
+        .unwrap();
+
+        let EventData::DatabaseSchemaUpdated(data) = event.data else {
+            panic!("not database.schema_updated");
+        };
+        assert_eq!(data.updated_properties[0].action, SchemaAction::Created);
+
+        let event = serde_json::from_value::<Event>(payload(
+            "comment.created",
+            json!({
+                "page_id": "0c1f7cb2-8090-4f18-924e-d92965055e32",
+                "parent": { "id": "0c1f7cb2-8090-4f18-924e-d92965055e32", "type": "page" },
+            }),
+        ))
+        .unwrap();
+        assert!(matches!(event.data, EventData::CommentCreated(_)));
+    }
+
+    #[test]
+    fn unknown_type() {
+        let event = serde_json::from_value::<Event>(payload(
+            "data_source.created",
+            json!({ "parent": { "id": "13950b26-c203-4f3b-b97d-93ec06319565", "type": "team" } }),
+        ))
+        .unwrap();
+
+        assert_eq!(event.data.type_name(), "data_source.created");
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "data_source.created");
+        assert_eq!(value["data"]["parent"]["type"], "team");
+        assert_eq!(value["entity"]["type"], "page");
+    }
+}
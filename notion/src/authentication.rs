@@ -1 +1,233 @@
+//! # Authorization
+//!
+//! Helpers for the OAuth flow of public integrations. See
+//! [`crate::model::oauth`] for an overview of the flow.
+//!
+//! The token endpoints authenticate with the client ID and secret of the
+//! integration instead of an access token, so they use an [`OAuthClient`]
+//! rather than a [`crate::client::Notion`].
 
+use std::fmt;
+
+use anyhow::{Context, Result};
+use notion_model::{
+    constants::{API_BASE_URL, API_VERSION},
+    oauth::{Token, TokenInfo, TokenRequest},
+};
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client, ClientBuilder, RequestBuilder, Url,
+};
+use serde::Serialize;
+
+use crate::{
+    client::SendAndGetText,
+    errors::{Error, NotionApiError},
+    result_types,
+};
+
+/// A client for the OAuth endpoints of a public integration.
+#[derive(Clone)]
+pub struct OAuthClient {
+    http: Client,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: Option<String>,
+    base_url: String,
+}
+
+impl fmt::Debug for OAuthClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthClient")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"[redacted]")
+            .field("redirect_uri", &self.redirect_uri)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct TokenBody<'a> {
+    token: &'a str,
+}
+
+impl OAuthClient {
+    /// Creates a client from the OAuth client ID and client secret of an
+    /// integration, found in its settings.
+    pub fn new(client_id: &str, client_secret: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+
+        headers.insert("Notion-Version", HeaderValue::from_static(API_VERSION));
+
+        let http = ClientBuilder::new()
+            .default_headers(headers)
+            .build()
+            .context(Error::ClientBuild)?;
+
+        Ok(Self {
+            http,
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            redirect_uri: None,
+            base_url: API_BASE_URL.to_owned(),
+        })
+    }
+
+    /// The redirect URI the user is sent to after authorizing the integration.
+    /// It must be one of the redirect URIs in the settings of the integration.
+    pub fn redirect_uri(mut self, redirect_uri: &str) -> Self {
+        self.redirect_uri = Some(redirect_uri.to_owned());
+        self
+    }
+
+    /// Sends requests to `base_url` instead of the Notion API. See
+    /// [`crate::client::Notion::base_url`].
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_owned();
+        if !self.base_url.ends_with('/') {
+            self.base_url.push('/');
+        }
+        self
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.http
+            .post(self.base_url.clone() + path)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+    }
+
+    /// # Authorization URL
+    ///
+    /// The URL to send users to, so that they can choose the pages the
+    /// integration has access to.
+    ///
+    /// `state` is returned as is in the query of the redirect URI, and should
+    /// be checked to protect against cross-site request forgery.
+    pub fn authorization_url(&self, state: Option<&str>) -> Result<String> {
+        let mut url =
+            Url::parse(&(self.base_url.clone() + "oauth/authorize")).context(Error::OAuthUrl)?;
+
+        url.query_pairs_mut()
+            .append_pair("client_id", &self.client_id)
+            .append_pair("response_type", "code")
+            .append_pair("owner", "user");
+
+        if let Some(redirect_uri) = &self.redirect_uri {
+            url.query_pairs_mut()
+                .append_pair("redirect_uri", redirect_uri);
+        }
+
+        if let Some(state) = state {
+            url.query_pairs_mut().append_pair("state", state);
+        }
+
+        Ok(url.into())
+    }
+
+    /// # Create a token
+    ///
+    /// Exchanges the temporary `code` received on the redirect URI for an
+    /// access token.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if the code is invalid or was already used,
+    /// or if the redirect URI doesn't match the one in the authorization URL.
+    pub async fn exchange_code(&self, code: &str) -> Result<Token> {
+        self.create_token(&TokenRequest::AuthorizationCode {
+            code: code.to_owned(),
+            redirect_uri: self.redirect_uri.clone(),
+        })
+        .await
+    }
+
+    /// # Refresh a token
+    ///
+    /// Gets a new access token using a refresh token.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if the refresh token is invalid.
+    pub async fn refresh_token(&self, refresh_token: &str) -> Result<Token> {
+        self.create_token(&TokenRequest::RefreshToken {
+            refresh_token: refresh_token.to_owned(),
+        })
+        .await
+    }
+
+    async fn create_token(&self, request: &TokenRequest) -> Result<Token> {
+        let text = self
+            .post("oauth/token")
+            .json(request)
+            .send_and_get_text()
+            .await?;
+
+        let res = serde_json::from_str::<result_types::OAuth<Token>>(&text)
+            .context(Error::SerializeResponse("Token", "create_token"))?;
+
+        res.into_result()
+    }
+
+    /// # Introspect a token
+    ///
+    /// Gets whether a token is active, and its scope.
+    pub async fn introspect_token(&self, token: &str) -> Result<TokenInfo> {
+        let text = self
+            .post("oauth/introspect")
+            .json(&TokenBody { token })
+            .send_and_get_text()
+            .await?;
+
+        let res = serde_json::from_str::<result_types::OAuth<TokenInfo>>(&text)
+            .context(Error::SerializeResponse("TokenInfo", "introspect_token"))?;
+
+        res.into_result()
+    }
+
+    /// # Revoke a token
+    ///
+    /// Revokes an access token, removing the integration from the workspace.
+    pub async fn revoke_token(&self, token: &str) -> Result<()> {
+        let text = self
+            .post("oauth/revoke")
+            .json(&TokenBody { token })
+            .send_and_get_text()
+            .await?;
+
+        let res = serde_json::from_str::<result_types::OAuth<serde_json::Value>>(&text)
+            .context(Error::SerializeResponse("revoke response", "revoke_token"))?;
+
+        res.into_result().map(|_| ())
+    }
+}
+
+impl<T> result_types::OAuth<T> {
+    fn into_result(self) -> Result<T> {
+        match self {
+            Self::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+            Self::Rejected {
+                error,
+                error_description,
+            } => anyhow::bail!(NotionApiError::OAuth(match error_description {
+                Some(description) => format!("{error}: {description}"),
+                None => error,
+            })),
+            Self::Ok(value) => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug() {
+        let client = OAuthClient::new("client_id", "secret_client_secret").unwrap();
+        let debug = format!("{client:?}");
+
+        assert!(debug.contains("client_id"));
+        assert!(!debug.contains("secret_client_secret"));
+    }
+}
@@ -0,0 +1,603 @@
+//! # Batches
+//!
+//! Running many operations, such as updating every page of a database, with
+//! [`Notion::run_batch`]. Operations run concurrently, up to
+//! [`BatchOptions::concurrency`] at a time, and a failed operation doesn't
+//! stop the others: the [`BatchReport`] lists the operations that succeeded
+//! and the ones that failed, with their error.
+//!
+//! Requests are paced by the client, so set [`Notion::rate_limit`] and
+//! [`Notion::retries`] to stay under the rate limit of the API.
+//!
+//! # Resuming
+//!
+//! With [`BatchOptions::state`], the indices of the operations that succeeded
+//! are saved to a file as they complete, along with the blocks of
+//! [`BatchOperation::AppendBlocks`] that were appended so far. Running the
+//! same operations again with the same file skips them, so an interrupted
+//! batch can be resumed without creating pages twice. Failed operations are
+//! run again, and appending blocks resumes after the last request that
+//! succeeded. A request that failed with a server error may still have been
+//! applied, though, and is sent again.
+//!
+//! The file is a log of JSON lines, appended to as operations progress and
+//! compacted when a batch starts. Its first line is a hash of the operations,
+//! and running different operations, or the same ones in another order, with
+//! it fails rather than skipping the wrong ones.
+//!
+//! ```no_run
+//! # async fn run(notion: notion::client::Notion, pages: Vec<notion::model::ids::PageId>) -> anyhow::Result<()> {
+//! use notion::batch::{BatchOperation, BatchOptions};
+//!
+//! let notion = notion.rate_limit(Some(3)).retries(3);
+//!
+//! let options = BatchOptions::new()
+//!     .state(Some("archive.jsonl".into()))
+//!     .on_progress(|progress| eprintln!("{}/{}", progress.done, progress.total));
+//!
+//! let report = notion
+//!     .run_batch(pages.into_iter().map(BatchOperation::ArchivePage), options)
+//!     .await?;
+//!
+//! for failure in &report.failed {
+//!     eprintln!("operation {} failed: {}", failure.index, failure.error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Notion::rate_limit`]: crate::client::Notion::rate_limit
+//! [`Notion::retries`]: crate::client::Notion::retries
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use notion_model::{
+    ids::{BlockId, PageId},
+    objects::{block::Block, page::Page},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    client::Notion,
+    errors::{Error, NotionApiError},
+    transport::HttpTransport,
+};
+
+/// The most blocks that can be appended in one request.
+const MAX_CHILDREN: usize = 100;
+
+/// An operation of a batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// Creates a page, see [`Notion::create_page`].
+    CreatePage(Page),
+    /// Updates a page, see [`Notion::update_page`].
+    UpdatePage(Page),
+    /// Appends blocks to a page or block, in requests of up to 100 blocks.
+    AppendBlocks {
+        parent: BlockId,
+        children: Vec<Block>,
+    },
+    /// Archives a page.
+    ArchivePage(PageId),
+}
+
+/// What an operation of a batch returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchOutput {
+    /// The page that was created, updated or archived.
+    Page(Box<Page>),
+    /// The blocks that were appended. When resuming, only the blocks appended
+    /// by this run.
+    Blocks(Vec<Block>),
+}
+
+/// An operation that succeeded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSuccess {
+    /// The position of the operation in the batch.
+    pub index: usize,
+    pub output: BatchOutput,
+}
+
+/// An operation that failed.
+#[derive(Debug)]
+pub struct BatchFailure {
+    /// The position of the operation in the batch.
+    pub index: usize,
+    pub operation: BatchOperation,
+    pub error: anyhow::Error,
+}
+
+impl BatchFailure {
+    /// The error returned by the API, if the request got a response.
+    pub fn api_error(&self) -> Option<&NotionApiError> {
+        self.error.downcast_ref()
+    }
+}
+
+/// The outcome of a batch.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// The operations that succeeded, in the order they completed.
+    pub succeeded: Vec<BatchSuccess>,
+    /// The operations that failed, in the order they completed.
+    pub failed: Vec<BatchFailure>,
+    /// The number of operations skipped because they succeeded in a previous
+    /// run, see [`BatchOptions::state`].
+    pub skipped: usize,
+}
+
+/// How far a batch is, passed to [`BatchOptions::on_progress`] after every
+/// operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// The number of operations that completed, including skipped ones.
+    pub done: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// The number of operations in the batch.
+    pub total: usize,
+}
+
+type ProgressCallback = Arc<dyn Fn(&BatchProgress) + Send + Sync>;
+
+/// How to run a batch.
+#[derive(Clone)]
+pub struct BatchOptions {
+    /// How many operations run at the same time.
+    pub concurrency: usize,
+    /// A file to save the operations that succeeded to, and to resume from.
+    pub state: Option<PathBuf>,
+    pub on_progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for BatchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchOptions")
+            .field("concurrency", &self.concurrency)
+            .field("state", &self.state)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 3,
+            state: None,
+            on_progress: None,
+        }
+    }
+}
+
+impl BatchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn state(mut self, state: Option<PathBuf>) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn on_progress<F: Fn(&BatchProgress) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.on_progress = Some(Arc::new(f));
+        self
+    }
+}
+
+/// The first line of a state file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    /// The hash of the operations of the batch, see [`batch_fingerprint`].
+    fingerprint: String,
+}
+
+/// A line of a state file after the header, recording progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Entry {
+    /// The operation at an index succeeded.
+    Succeeded(usize),
+    /// More blocks of the `AppendBlocks` operation at an index were appended.
+    Appended { index: usize, blocks: usize },
+}
+
+/// The operations of a batch that succeeded, saved to [`BatchOptions::state`].
+#[derive(Debug, Default)]
+struct BatchState {
+    succeeded: BTreeSet<usize>,
+    /// The number of blocks appended so far by the `AppendBlocks` operations
+    /// that didn't succeed yet, by index.
+    appended: BTreeMap<usize, usize>,
+    /// The state file, open to append entries to.
+    file: Option<File>,
+}
+
+/// The hash of an operation.
+fn fingerprint(operation: &BatchOperation) -> Result<String> {
+    // through a `Value`, whose objects have sorted keys, as the properties
+    // of pages are in a `HashMap`
+    let value = serde_json::to_value(operation).context(Error::SerializeRequest)?;
+    let bytes = serde_json::to_vec(&value).context(Error::SerializeRequest)?;
+
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+/// The hash of the operations of a batch, in order, to tell whether a state
+/// belongs to it.
+fn batch_fingerprint(operations: &[BatchOperation]) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    for operation in operations {
+        hasher.update(fingerprint(operation)?);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+impl BatchState {
+    /// Loads the state of the batch with a fingerprint, and compacts its file.
+    /// Fails if the state is of other operations.
+    fn load(options: &BatchOptions, fingerprint: &str) -> Result<Self> {
+        let mut state = Self::default();
+
+        let Some(path) = &options.state else {
+            return Ok(state);
+        };
+
+        if path.exists() {
+            let text = fs::read_to_string(path).context(Error::BatchStateRead)?;
+            let mut lines = text.split_inclusive('\n');
+
+            let header = lines
+                .next()
+                .and_then(|line| serde_json::from_str::<Header>(line).ok())
+                .context(Error::BatchStateRead)?;
+
+            if header.fingerprint != fingerprint {
+                anyhow::bail!(Error::BatchStateMismatch);
+            }
+
+            for line in lines {
+                // a process stopped while appending leaves half a line
+                if !line.ends_with('\n') {
+                    break;
+                }
+
+                state.apply(serde_json::from_str(line).context(Error::BatchStateRead)?);
+            }
+        }
+
+        state.compact(path, fingerprint)?;
+
+        Ok(state)
+    }
+
+    fn apply(&mut self, entry: Entry) {
+        match entry {
+            Entry::Succeeded(index) => {
+                self.succeeded.insert(index);
+                self.appended.remove(&index);
+            },
+            Entry::Appended { index, blocks } => {
+                *self.appended.entry(index).or_default() += blocks;
+            },
+        }
+    }
+
+    /// Rewrites the file with one entry for each operation, and opens it to
+    /// append to.
+    fn compact(&mut self, path: &Path, fingerprint: &str) -> Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).context(Error::BatchStateWrite)?;
+        }
+
+        let entries = self
+            .succeeded
+            .iter()
+            .map(|&index| Entry::Succeeded(index))
+            .chain(
+                self.appended
+                    .iter()
+                    .map(|(&index, &blocks)| Entry::Appended { index, blocks }),
+            );
+
+        let mut text = line(&Header {
+            fingerprint: fingerprint.to_owned(),
+        })?;
+        for entry in entries {
+            text.push_str(&line(&entry)?);
+        }
+
+        // write next to the state and rename over it, so that a process
+        // stopped while writing doesn't leave half a file
+        let temp = path.with_extension("tmp");
+
+        fs::write(&temp, text).context(Error::BatchStateWrite)?;
+        fs::rename(&temp, path).context(Error::BatchStateWrite)?;
+
+        self.file = Some(
+            OpenOptions::new()
+                .append(true)
+                .open(path)
+                .context(Error::BatchStateWrite)?,
+        );
+
+        Ok(())
+    }
+
+    /// Applies an entry, and appends it to the file.
+    fn record(&mut self, entry: Entry) -> Result<()> {
+        self.apply(entry);
+
+        if let Some(file) = &mut self.file {
+            file.write_all(line(&entry)?.as_bytes())
+                .context(Error::BatchStateWrite)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A line of JSON, with its newline.
+fn line(value: &impl Serialize) -> Result<String> {
+    let mut line = serde_json::to_string(value).context(Error::BatchStateWrite)?;
+    line.push('\n');
+
+    Ok(line)
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Runs a batch of operations, see [`crate::batch`].
+    ///
+    /// # Errors
+    ///
+    /// Only fails if the state file can't be read or written. Operations that
+    /// fail are listed in [`BatchReport::failed`].
+    pub async fn run_batch<I>(&self, operations: I, options: BatchOptions) -> Result<BatchReport>
+    where
+        I: IntoIterator<Item = BatchOperation>,
+    {
+        let operations = operations.into_iter().collect::<Vec<_>>();
+        let fingerprint = batch_fingerprint(&operations)?;
+        let state = Mutex::new(BatchState::load(&options, &fingerprint)?);
+
+        let mut report = BatchReport::default();
+        let mut progress = BatchProgress {
+            done: 0,
+            succeeded: 0,
+            failed: 0,
+            total: operations.len(),
+        };
+
+        let pending = operations
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !lock(&state).succeeded.contains(index))
+            .collect::<Vec<_>>();
+
+        report.skipped = progress.total - pending.len();
+        progress.done = report.skipped;
+
+        let (state, options) = (&state, &options);
+
+        let mut results = futures::stream::iter(pending)
+            .map(|(index, operation)| async move {
+                let result = self.run_operation(index, &operation, state).await;
+                (index, operation, result)
+            })
+            .buffer_unordered(options.concurrency.max(1));
+
+        while let Some((index, operation, result)) = results.next().await {
+            match result {
+                Ok(output) => {
+                    lock(state).record(Entry::Succeeded(index))?;
+
+                    report.succeeded.push(BatchSuccess { index, output });
+                    progress.succeeded += 1;
+                },
+                Err(error) => {
+                    report.failed.push(BatchFailure {
+                        index,
+                        operation,
+                        error,
+                    });
+                    progress.failed += 1;
+                },
+            }
+
+            progress.done += 1;
+
+            if let Some(on_progress) = &options.on_progress {
+                on_progress(&progress);
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn run_operation(
+        &self,
+        index: usize,
+        operation: &BatchOperation,
+        state: &Mutex<BatchState>,
+    ) -> Result<BatchOutput> {
+        match operation {
+            BatchOperation::CreatePage(page) => {
+                let page = self.create_page(page.clone()).await?;
+                Ok(BatchOutput::Page(Box::new(page)))
+            },
+            BatchOperation::UpdatePage(page) => {
+                let page = self.update_page(page.clone()).await?;
+                Ok(BatchOutput::Page(Box::new(page)))
+            },
+            BatchOperation::AppendBlocks { parent, children } => {
+                let appended = lock(state).appended.get(&index).copied().unwrap_or(0);
+                let mut blocks = Vec::with_capacity(children.len());
+
+                for chunk in children
+                    .get(appended..)
+                    .unwrap_or_default()
+                    .chunks(MAX_CHILDREN)
+                {
+                    let list = self.append_block_children(*parent, chunk.to_vec()).await?;
+                    blocks.extend(list.results);
+
+                    // saved as it goes, so that a failed operation doesn't
+                    // append the same blocks again
+                    lock(state).record(Entry::Appended {
+                        index,
+                        blocks: chunk.len(),
+                    })?;
+                }
+
+                Ok(BatchOutput::Blocks(blocks))
+            },
+            BatchOperation::ArchivePage(page_id) => {
+                let page = self
+                    .update_page(Page::new().id(Some(*page_id)).archived(Some(true)))
+                    .await?;
+                Ok(BatchOutput::Page(Box::new(page)))
+            },
+        }
+    }
+}
+
+fn lock(state: &Mutex<BatchState>) -> MutexGuard<'_, BatchState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state() {
+        let path = std::env::temp_dir().join(format!("notion-batch-{}.jsonl", std::process::id()));
+        let options = BatchOptions::new().state(Some(path.clone()));
+
+        let mut state = BatchState::load(&options, "a").unwrap();
+        assert!(state.succeeded.is_empty());
+
+        state
+            .record(Entry::Appended {
+                index: 0,
+                blocks: 100,
+            })
+            .unwrap();
+        state.record(Entry::Succeeded(0)).unwrap();
+        state
+            .record(Entry::Appended {
+                index: 1,
+                blocks: 100,
+            })
+            .unwrap();
+        state
+            .record(Entry::Appended {
+                index: 1,
+                blocks: 50,
+            })
+            .unwrap();
+        drop(state);
+
+        // entries are appended, one per line
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 5);
+
+        // with half a line, left by a process stopped while appending
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"succeeded":"#).unwrap();
+        drop(file);
+
+        let state = BatchState::load(&options, "a").unwrap();
+        assert_eq!(state.succeeded, BTreeSet::from([0]));
+        assert_eq!(state.appended, BTreeMap::from([(1, 150)]));
+        drop(state);
+
+        // loading compacts the file
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"fingerprint\":\"a\"}\n{\"succeeded\":0}\n{\"appended\":{\"index\":1,\"blocks\":150}}\n"
+        );
+
+        // the state of other operations
+        assert_eq!(
+            BatchState::load(&options, "b").unwrap_err().to_string(),
+            "batch state is of other operations"
+        );
+
+        fs::write(&path, "[").unwrap();
+        assert_eq!(
+            BatchState::load(&options, "a").unwrap_err().to_string(),
+            "could not read batch state"
+        );
+
+        fs::remove_file(path).unwrap();
+
+        // without a file, nothing is saved
+        BatchState::load(&BatchOptions::new(), "a")
+            .unwrap()
+            .record(Entry::Succeeded(0))
+            .unwrap();
+    }
+
+    #[test]
+    fn fingerprints() {
+        let page = |name: &str| {
+            serde_json::from_value::<Page>(serde_json::json!({
+                "properties": {
+                    "Name": { "title": [{ "text": { "content": name } }] },
+                    "Done": { "checkbox": true },
+                    "Price": { "number": 3 },
+                },
+            }))
+            .unwrap()
+        };
+
+        let kale = fingerprint(&BatchOperation::CreatePage(page("Kale"))).unwrap();
+
+        assert_eq!(
+            kale,
+            fingerprint(&BatchOperation::CreatePage(page("Kale"))).unwrap()
+        );
+        assert_ne!(
+            kale,
+            fingerprint(&BatchOperation::UpdatePage(page("Kale"))).unwrap()
+        );
+        assert_ne!(
+            kale,
+            fingerprint(&BatchOperation::CreatePage(page("Bread"))).unwrap()
+        );
+
+        let (kale, bread) = (
+            BatchOperation::CreatePage(page("Kale")),
+            BatchOperation::CreatePage(page("Bread")),
+        );
+
+        assert_ne!(
+            batch_fingerprint(&[kale.clone(), bread.clone()]).unwrap(),
+            batch_fingerprint(&[bread, kale]).unwrap()
+        );
+    }
+}
@@ -6,13 +6,15 @@ use notion_model::{
 use serde::Serialize;
 
 use crate::{
+    cache::CacheKind,
     client::{Notion, SendAndGetText},
     errors::{Error, NotionApiError},
     model::pagination::List,
     result_types,
+    transport::HttpTransport,
 };
 
-impl Notion {
+impl<T: HttpTransport> Notion<T> {
     /// # Append block children
     ///
     /// Creates and appends new children blocks to the parent block_id
@@ -47,21 +49,53 @@ impl Notion {
         &self,
         block_id: BlockId,
         children: Vec<Block>,
+    ) -> Result<List<Block>> {
+        self.insert_block_children(block_id, None, children).await
+    }
+
+    /// # Append block children after a block
+    ///
+    /// Like [`Notion::append_block_children`], but inserts the new blocks
+    /// right after `after`, which must be one of the children of the block,
+    /// instead of at the bottom.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if `after` isn't a child of the block, and
+    /// the same errors as [`Notion::append_block_children`].
+    pub async fn append_block_children_after(
+        &self,
+        block_id: BlockId,
+        after: BlockId,
+        children: Vec<Block>,
+    ) -> Result<List<Block>> {
+        self.insert_block_children(block_id, Some(after), children)
+            .await
+    }
+
+    async fn insert_block_children(
+        &self,
+        block_id: BlockId,
+        after: Option<BlockId>,
+        children: Vec<Block>,
     ) -> Result<List<Block>> {
         #[derive(Serialize, Debug)]
         struct AppendBlockChildren {
             children: Vec<Block>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            after: Option<BlockId>,
         }
 
         let text = self
             .api_patch(&format!("blocks/{block_id}/children"))
-            .json(&AppendBlockChildren { children })
+            .json(&AppendBlockChildren { children, after })
             .send_and_get_text()
             .await?;
 
-        let res = serde_json::from_str::<result_types::List<Block>>(&text).context(
-            Error::SerializeResponse("List<Block>", "append_block_children"),
-        )?;
+        self.invalidate(&block_id.to_string());
+
+        let res =
+            self.parse::<result_types::List<Block>>(&text, "List<Block>", "append_block_children")?;
 
         match res {
             result_types::List::List(block_list) => Ok(block_list),
@@ -91,12 +125,14 @@ impl Notion {
     /// limits.
     pub async fn retrieve_block(&self, block_id: BlockId) -> Result<Block> {
         let text = self
-            .api_get(&format!("blocks/{block_id}"))
-            .send_and_get_text()
+            .send_cached(
+                CacheKind::Block,
+                block_id.to_string(),
+                self.api_get(&format!("blocks/{block_id}")),
+            )
             .await?;
 
-        let res = serde_json::from_str::<result_types::Block>(&text)
-            .context(Error::SerializeResponse("Block response", "retrieve_block"))?;
+        let res = self.parse::<result_types::Block>(&text, "Block response", "retrieve_block")?;
 
         match res {
             result_types::Block::Block(block) => Ok(block),
@@ -117,7 +153,8 @@ impl Notion {
     /// children. The response may contain fewer than page_size of results.
     ///
     /// See Pagination for details about how to use a cursor to iterate through
-    /// the list.
+    /// the list. Pass the `next_cursor` of the previous response as
+    /// `start_cursor` to retrieve the next page of children.
     ///
     /// # 📘 Integration capabilities
     ///
@@ -133,14 +170,29 @@ impl Notion {
     ///
     /// Returns a 400 or 429 HTTP response if the request exceeds the request
     /// limits.
-    pub async fn retrieve_block_children(&self, block_id: BlockId) -> Result<List<Block>> {
+    pub async fn retrieve_block_children(
+        &self,
+        block_id: BlockId,
+        start_cursor: Option<String>,
+    ) -> Result<List<Block>> {
+        let query = start_cursor
+            .iter()
+            .map(|cursor| ("start_cursor", cursor))
+            .collect::<Vec<_>>();
+
         let text = self
-            .api_get(&format!("blocks/{block_id}/children"))
-            .send_and_get_text()
+            .send_cached(
+                CacheKind::BlockChildren,
+                block_id.to_string(),
+                self.api_get(&format!("blocks/{block_id}/children"))
+                    .query(&query),
+            )
             .await?;
 
-        let res = serde_json::from_str::<result_types::List<Block>>(&text).context(
-            Error::SerializeResponse("List<Block>", "retrieve_block_children"),
+        let res = self.parse::<result_types::List<Block>>(
+            &text,
+            "List<Block>",
+            "retrieve_block_children",
         )?;
 
         match res {
@@ -149,6 +201,26 @@ impl Notion {
         }
     }
 
+    /// Retrieves the children of a block, following `next_cursor` until every
+    /// child has been retrieved.
+    ///
+    /// Like [`Notion::retrieve_block_children`], only the first level of
+    /// children is returned.
+    pub async fn retrieve_block_children_all(&self, block_id: BlockId) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        let mut start_cursor = None;
+
+        loop {
+            let list = self.retrieve_block_children(block_id, start_cursor).await?;
+            blocks.extend(list.results);
+
+            match list.next_cursor {
+                Some(cursor) if list.has_more => start_cursor = Some(cursor),
+                _ => return Ok(blocks),
+            }
+        }
+    }
+
     /// # Update a block
     ///
     /// Updates the content for the specified block_id based on the block type.
@@ -232,11 +304,15 @@ impl Notion {
             .send_and_get_text()
             .await?;
 
-        let res = serde_json::from_str::<result_types::Block>(&text)
-            .context(Error::SerializeResponse("Block", "update_block"))?;
+        self.invalidate(&block_id.to_string());
+
+        let res = self.parse::<result_types::Block>(&text, "Block", "update_block")?;
 
         match res {
-            result_types::Block::Block(block) => Ok(block),
+            result_types::Block::Block(block) => {
+                self.invalidate_parent(block.parent.as_ref());
+                Ok(block)
+            },
             result_types::Block::Error(e) => anyhow::bail!(NotionApiError::from(e)),
         }
     }
@@ -271,11 +347,15 @@ impl Notion {
             .send_and_get_text()
             .await?;
 
-        let res = serde_json::from_str::<result_types::Block>(&text)
-            .context(Error::SerializeResponse("Block", "delete_block"))?;
+        self.invalidate(&block_id.to_string());
+
+        let res = self.parse::<result_types::Block>(&text, "Block", "delete_block")?;
 
         match res {
-            result_types::Block::Block(block) => Ok(block),
+            result_types::Block::Block(block) => {
+                self.invalidate_parent(block.parent.as_ref());
+                Ok(block)
+            },
             result_types::Block::Error(e) => anyhow::bail!(NotionApiError::from(e)),
         }
     }
@@ -0,0 +1,439 @@
+//! # Response cache
+//!
+//! An optional in-memory cache for the responses of the endpoints that
+//! retrieve blocks, block children, pages and databases. Enable it with
+//! [`Notion::cache`].
+//!
+//! - Every kind of object has its own time to live. A kind without a time to
+//!   live is never cached.
+//! - The cache is bounded by number of entries and by total size of the
+//!   responses. The least recently used entries are evicted first.
+//! - Mutations made through the same client, such as [`Notion::update_block`],
+//!   invalidate the cached responses of the object and of its parent. Mutations
+//!   made by anyone else are only seen once the entries expire.
+//! - Concurrent identical requests share a single HTTP request.
+//!
+//! Only successful responses are cached. The cache is shared by the clones of
+//! a client.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use notion_model::objects::parent::ParentData;
+
+use crate::{
+    client::{ApiRequest, Notion, SendAndGetText},
+    transport::HttpTransport,
+};
+
+/// The kinds of responses that can be cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    Block,
+    BlockChildren,
+    Page,
+    Database,
+}
+
+/// Configuration of the response cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Time to live of retrieved blocks.
+    pub block_ttl: Option<Duration>,
+    /// Time to live of retrieved lists of block children.
+    pub block_children_ttl: Option<Duration>,
+    /// Time to live of retrieved pages.
+    pub page_ttl: Option<Duration>,
+    /// Time to live of retrieved databases.
+    pub database_ttl: Option<Duration>,
+    /// Maximum number of cached responses.
+    pub max_entries: usize,
+    /// Maximum total size of the cached responses, in bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for CacheConfig {
+    /// Caches every kind for a minute, up to 1000 responses or 16 MiB.
+    fn default() -> Self {
+        let ttl = Some(Duration::from_secs(60));
+
+        Self {
+            block_ttl: ttl,
+            block_children_ttl: ttl,
+            page_ttl: ttl,
+            database_ttl: ttl,
+            max_entries: 1000,
+            max_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn block_ttl(mut self, block_ttl: Option<Duration>) -> Self {
+        self.block_ttl = block_ttl;
+        self
+    }
+
+    pub const fn block_children_ttl(mut self, block_children_ttl: Option<Duration>) -> Self {
+        self.block_children_ttl = block_children_ttl;
+        self
+    }
+
+    pub const fn page_ttl(mut self, page_ttl: Option<Duration>) -> Self {
+        self.page_ttl = page_ttl;
+        self
+    }
+
+    pub const fn database_ttl(mut self, database_ttl: Option<Duration>) -> Self {
+        self.database_ttl = database_ttl;
+        self
+    }
+
+    pub const fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    pub const fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    const fn ttl(&self, kind: CacheKind) -> Option<Duration> {
+        match kind {
+            CacheKind::Block => self.block_ttl,
+            CacheKind::BlockChildren => self.block_children_ttl,
+            CacheKind::Page => self.page_ttl,
+            CacheKind::Database => self.database_ttl,
+        }
+    }
+}
+
+/// Whether the response was successful, and its body.
+type Response = (bool, String);
+
+type SharedFetch = Shared<BoxFuture<'static, Result<Response, Arc<anyhow::Error>>>>;
+
+struct Entry {
+    object_id: String,
+    text: String,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+struct InFlight {
+    token: u64,
+    object_id: String,
+    fetch: SharedFetch,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    in_flight: HashMap<String, InFlight>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl State {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.text.len();
+        }
+    }
+
+    fn insert(&mut self, config: &CacheConfig, key: String, entry: Entry) {
+        if entry.text.len() > config.max_bytes || config.max_entries == 0 {
+            return;
+        }
+
+        self.remove(&key);
+
+        while self.entries.len() >= config.max_entries
+            || self.bytes + entry.text.len() > config.max_bytes
+        {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+
+            self.remove(&oldest);
+        }
+
+        self.bytes += entry.text.len();
+        self.entries.insert(key, entry);
+    }
+}
+
+/// The cache shared by a client and its clones.
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    state: Mutex<State>,
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state();
+
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .field("entries", &state.entries.len())
+            .field("bytes", &state.bytes)
+            .field("in_flight", &state.in_flight.len())
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // the state is always left consistent, so a panic in another thread
+        // doesn't make it unusable
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the cached response for `key`, or runs `fetch`, sharing it with
+    /// any identical request made while it runs.
+    async fn get_or_fetch<F>(
+        &self,
+        kind: CacheKind,
+        object_id: String,
+        key: String,
+        fetch: F,
+    ) -> Result<String>
+    where
+        F: Future<Output = Result<Response>> + Send + 'static,
+    {
+        let Some(ttl) = self.config.ttl(kind) else {
+            return Ok(fetch.await?.1);
+        };
+
+        let (token, fetch) = {
+            let mut state = self.state();
+            let now = state.tick();
+
+            match state.entries.get_mut(&key) {
+                Some(entry) if entry.expires_at > Instant::now() => {
+                    entry.last_used = now;
+                    return Ok(entry.text.clone());
+                },
+                Some(_) => state.remove(&key),
+                None => {},
+            }
+
+            // a request for the same key that is already in flight is shared
+            let in_flight = state
+                .in_flight
+                .entry(key.clone())
+                .or_insert_with(|| InFlight {
+                    token: now,
+                    object_id: object_id.clone(),
+                    fetch: fetch.map(|res| res.map_err(Arc::new)).boxed().shared(),
+                });
+            let shared = (in_flight.token, in_flight.fetch.clone());
+            drop(state);
+
+            shared
+        };
+
+        let res = fetch.await;
+
+        if let Ok((true, text)) = &res {
+            self.complete(
+                &key,
+                token,
+                Entry {
+                    object_id,
+                    text: text.clone(),
+                    expires_at: Instant::now() + ttl,
+                    last_used: 0,
+                },
+            );
+        } else {
+            self.complete_uncached(&key, token);
+        }
+
+        match res {
+            Ok((_, text)) => Ok(text),
+            Err(e) => Err(anyhow::anyhow!("{e:#}")),
+        }
+    }
+
+    /// Caches the response of a request that was in flight.
+    ///
+    /// An invalidation while the request was in flight removes it, and its
+    /// response may be outdated, so it isn't cached then.
+    fn complete(&self, key: &str, token: u64, mut entry: Entry) {
+        let mut state = self.state();
+
+        if state.in_flight.get(key).map(|in_flight| in_flight.token) == Some(token) {
+            state.in_flight.remove(key);
+            entry.last_used = state.tick();
+            state.insert(&self.config, key.to_owned(), entry);
+        }
+    }
+
+    /// Forgets a request that was in flight without caching its response.
+    fn complete_uncached(&self, key: &str, token: u64) {
+        let mut state = self.state();
+
+        if state.in_flight.get(key).map(|in_flight| in_flight.token) == Some(token) {
+            state.in_flight.remove(key);
+        }
+    }
+
+    /// Removes every cached response about an object.
+    fn invalidate(&self, object_id: &str) {
+        let mut state = self.state();
+
+        let keys = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.object_id == object_id)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in keys {
+            state.remove(&key);
+        }
+
+        state
+            .in_flight
+            .retain(|_, in_flight| in_flight.object_id != object_id);
+    }
+
+    fn clear(&self) {
+        let mut state = self.state();
+
+        state.entries.clear();
+        state.in_flight.clear();
+        state.bytes = 0;
+    }
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Sends a GET request, going through the response cache if it is
+    /// enabled. `object_id` is the object the response is about, used for
+    /// invalidation.
+    pub(crate) async fn send_cached(
+        &self,
+        kind: CacheKind,
+        object_id: String,
+        request: ApiRequest<T>,
+    ) -> Result<String> {
+        let Some(cache) = &self.cache else {
+            return request.send_and_get_text().await;
+        };
+
+        let request = request.prepare()?;
+        let key = request.url().to_owned();
+
+        cache
+            .get_or_fetch(kind, object_id, key, request.send())
+            .await
+    }
+
+    /// Invalidates the cached responses about an object.
+    pub(crate) fn invalidate(&self, object_id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(object_id);
+        }
+    }
+
+    /// Invalidates the cached responses about the parent of an object, such as
+    /// the list of children that contains it.
+    pub(crate) fn invalidate_parent(&self, parent: Option<&ParentData>) {
+        if let Some(parent_id) = parent.and_then(parent_id) {
+            self.invalidate(&parent_id);
+        }
+    }
+
+    /// Removes every cached response.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+}
+
+/// The ID of a parent, unless it is the workspace.
+pub(crate) fn parent_id(parent: &ParentData) -> Option<String> {
+    match parent {
+        ParentData::DatabaseId { database_id } => Some(database_id.to_string()),
+        ParentData::PageId { page_id } => Some(page_id.to_string()),
+        ParentData::BlockId { block_id } => Some(block_id.to_string()),
+        ParentData::Workspace { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(object_id: &str, text: &str, last_used: u64) -> Entry {
+        Entry {
+            object_id: object_id.to_string(),
+            text: text.to_string(),
+            expires_at: Instant::now() + Duration::from_secs(60),
+            last_used,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let config = CacheConfig::new().max_entries(2);
+        let mut state = State::default();
+
+        state.insert(&config, "a".to_string(), entry("a", "1", 1));
+        state.insert(&config, "b".to_string(), entry("b", "2", 3));
+        state.insert(&config, "c".to_string(), entry("c", "3", 2));
+        state.insert(&config, "d".to_string(), entry("d", "4", 4));
+
+        let mut keys = state.entries.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+
+        assert_eq!(keys, vec!["b", "d"]);
+        assert_eq!(state.bytes, 2);
+    }
+
+    #[test]
+    fn bounded_by_bytes() {
+        let config = CacheConfig::new().max_bytes(5);
+        let mut state = State::default();
+
+        state.insert(&config, "a".to_string(), entry("a", "abc", 1));
+        state.insert(&config, "b".to_string(), entry("b", "def", 2));
+        state.insert(&config, "c".to_string(), entry("c", "too long", 3));
+
+        assert_eq!(state.entries.keys().collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(state.bytes, 3);
+    }
+}
@@ -0,0 +1,328 @@
+//! # Record and replay
+//!
+//! A cassette is a JSON file of request and response pairs, for tests that
+//! need real API responses without a network connection or an API token.
+//! Attach one to a client with [`Notion::cassette`].
+//!
+//! - In [`Mode::Record`], requests are sent as usual, and each request and its
+//!   response are appended to the cassette, which is saved after every request.
+//!   The bearer token is redacted.
+//! - In [`Mode::Replay`], nothing is sent. Each request is answered with the
+//!   response of the first unused recorded request with the same method, URL
+//!   and body, and fails with [`Error::UnmatchedRequest`] if there is none.
+//!
+//! URLs are recorded relative to the base URL of the client, so a cassette
+//! recorded against a mock server replays the same way as one recorded against
+//! the API.
+//!
+//! Responses are kept as JSON in the cassette, so that they can be read and
+//! edited. A response that isn't JSON is kept as a string.
+//!
+//! [`Notion::cassette`]: crate::client::Notion::cassette
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{errors::Error, transport::HttpRequest};
+
+/// The value recorded instead of the bearer token.
+const REDACTED: &str = "Bearer [redacted]";
+
+/// Whether a cassette records or replays requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+/// A request, as recorded in a cassette.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The URL relative to the base URL of the client, including the query.
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A response, as recorded in a cassette.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+/// A request and the response it got.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    /// Whether each interaction has been replayed.
+    used: Vec<bool>,
+}
+
+/// A cassette file, in record or replay mode. See the [module
+/// documentation](self).
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    state: Mutex<State>,
+}
+
+impl fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cassette")
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .field("interactions", &self.state().interactions.len())
+            .finish()
+    }
+}
+
+impl Cassette {
+    /// Records requests to a new cassette at `path`, replacing any existing
+    /// file once the first request is recorded.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            mode: Mode::Record,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Replays the requests recorded in the cassette at `path`.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+
+        let text = fs::read_to_string(&path).context(Error::CassetteRead)?;
+        let file = serde_json::from_str::<CassetteFile>(&text).context(Error::CassetteRead)?;
+
+        let used = vec![false; file.interactions.len()];
+
+        Ok(Self {
+            path,
+            mode: Mode::Replay,
+            state: Mutex::new(State {
+                interactions: file.interactions,
+                used,
+            }),
+        })
+    }
+
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The recorded interactions, in order.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state().interactions.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the response of the first unused interaction that matches the
+    /// request.
+    pub(crate) fn replay_response(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        self.state().take_response(request)
+    }
+
+    /// Appends an interaction and saves the cassette. The file is written
+    /// while the state is locked, so that it can't be overwritten with fewer
+    /// interactions.
+    pub(crate) fn record_interaction(&self, interaction: Interaction) -> Result<()> {
+        self.state().record(&self.path, interaction)
+    }
+}
+
+impl State {
+    fn take_response(&mut self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        let index = self
+            .interactions
+            .iter()
+            .zip(self.used.iter())
+            .position(|(interaction, used)| !used && interaction.request.matches(request))
+            .ok_or_else(|| anyhow!("{} {}", request.method, request.url))
+            .context(Error::UnmatchedRequest)?;
+
+        self.used[index] = true;
+
+        Ok(self.interactions[index].response.clone())
+    }
+
+    fn record(&mut self, path: &Path, interaction: Interaction) -> Result<()> {
+        self.interactions.push(interaction);
+        self.used.push(false);
+
+        let file = CassetteFile {
+            interactions: self.interactions.clone(),
+        };
+        let text = serde_json::to_string_pretty(&file).context(Error::CassetteWrite)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(Error::CassetteWrite)?;
+        }
+
+        fs::write(path, text + "\n").context(Error::CassetteWrite)
+    }
+}
+
+impl RecordedRequest {
+    /// Records a request sent by a client with the given base URL.
+    pub(crate) fn new(request: &HttpRequest, base_url: &str) -> Self {
+        let url = request.url.strip_prefix(base_url).unwrap_or(&request.url);
+
+        let headers = request
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = match name == header::AUTHORIZATION {
+                    true => REDACTED.to_owned(),
+                    false => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                };
+
+                (name.to_string(), value)
+            })
+            .collect();
+
+        Self {
+            method: request.method.to_string(),
+            url: url.to_owned(),
+            headers,
+            body: request.body.as_deref().map(body_to_value),
+        }
+    }
+
+    /// Whether two requests are the same, ignoring headers.
+    fn matches(&self, other: &Self) -> bool {
+        self.method == other.method && self.url == other.url && self.body == other.body
+    }
+}
+
+impl RecordedResponse {
+    pub(crate) fn new(status: u16, text: &str) -> Self {
+        Self {
+            status,
+            body: body_to_value(text.as_bytes()),
+        }
+    }
+
+    /// The response body, as it was received.
+    pub fn text(&self) -> String {
+        match &self.body {
+            Value::String(text) => text.clone(),
+            body => body.to_string(),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+fn body_to_value(body: &[u8]) -> Value {
+    serde_json::from_slice(body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, url: &str, body: Option<Value>) -> RecordedRequest {
+        RecordedRequest {
+            method: method.to_owned(),
+            url: url.to_owned(),
+            headers: BTreeMap::new(),
+            body,
+        }
+    }
+
+    fn cassette(interactions: Vec<Interaction>) -> Cassette {
+        Cassette {
+            path: PathBuf::new(),
+            mode: Mode::Replay,
+            state: Mutex::new(State {
+                used: vec![false; interactions.len()],
+                interactions,
+            }),
+        }
+    }
+
+    #[test]
+    fn replay_in_order() {
+        let get = request("GET", "users/me", None);
+
+        let cassette = cassette(vec![
+            Interaction {
+                request: get.clone(),
+                response: RecordedResponse::new(200, r#"{"n":1}"#),
+            },
+            Interaction {
+                request: get.clone(),
+                response: RecordedResponse::new(200, r#"{"n":2}"#),
+            },
+        ]);
+
+        assert_eq!(cassette.replay_response(&get).unwrap().text(), r#"{"n":1}"#);
+        assert_eq!(cassette.replay_response(&get).unwrap().text(), r#"{"n":2}"#);
+        assert!(cassette.replay_response(&get).is_err());
+    }
+
+    #[test]
+    fn replay_matches_body() {
+        let search = |query: &str| {
+            request(
+                "POST",
+                "search",
+                Some(serde_json::json!({ "query": query })),
+            )
+        };
+
+        let cassette = cassette(vec![Interaction {
+            request: search("kale"),
+            response: RecordedResponse::new(200, "{}"),
+        }]);
+
+        let e = cassette.replay_response(&search("bread")).unwrap_err();
+        assert_eq!(e.to_string(), Error::UnmatchedRequest.to_string());
+        assert!(cassette.replay_response(&search("kale")).is_ok());
+    }
+
+    #[test]
+    fn text_body() {
+        let response = RecordedResponse::new(502, "Bad Gateway");
+
+        assert_eq!(response.body, Value::String("Bad Gateway".to_owned()));
+        assert_eq!(response.text(), "Bad Gateway");
+        assert!(!response.is_success());
+    }
+}
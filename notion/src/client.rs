@@ -1,26 +1,438 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
 use anyhow::{Context, Result};
 use notion_model::constants::{API_BASE_URL, API_VERSION};
 use reqwest::{
     header::{self, HeaderMap, HeaderValue},
-    Client, ClientBuilder, RequestBuilder,
+    RequestBuilder, Url,
 };
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{field, Instrument};
+
+use crate::{
+    cache::{CacheConfig, ResponseCache},
+    cassette::{Cassette, Interaction, Mode, RecordedRequest, RecordedResponse},
+    drift::{self, DriftMode},
+    errors::Error,
+    metrics::{self, Metrics, RequestMetrics},
+    transport::{HttpRequest, HttpResponse, HttpTransport, Method, ReqwestTransport, StatusCode},
+};
+
+/// The longest time to wait before retrying a request, unless the API asks
+/// for longer with a `Retry-After` header.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Spaces out requests, shared by the clones of a client.
+#[derive(Debug)]
+struct RateLimiter {
+    interval: Duration,
+    /// The earliest time the next request can be sent.
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second,
+            next: Mutex::new(Instant::now()),
+        }
+    }
 
-use crate::errors::Error;
+    /// Waits for the turn of a request.
+    async fn wait(&self) {
+        let now = Instant::now();
 
-#[derive(Debug, Clone)]
-pub struct Notion {
-    http: Client,
+        let slot = {
+            let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+            let slot = (*next).max(now);
+            *next = slot + self.interval;
+            slot
+        };
+
+        if slot > now {
+            tokio::time::sleep(slot - now).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Notion<T = ReqwestTransport> {
+    transport: Arc<T>,
+    /// The headers of every request.
+    headers: HeaderMap,
+    base_url: String,
+    pub(crate) cache: Option<Arc<ResponseCache>>,
+    cassette: Option<Arc<Cassette>>,
+    drift: Option<DriftMode>,
+    metrics: Option<Arc<dyn Metrics>>,
+    /// How many times a request is sent again after a 429 or 5xx response.
+    max_retries: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<T> Clone for Notion<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            headers: self.headers.clone(),
+            base_url: self.base_url.clone(),
+            cache: self.cache.clone(),
+            cassette: self.cassette.clone(),
+            drift: self.drift.clone(),
+            metrics: self.metrics.clone(),
+            max_retries: self.max_retries,
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
 }
 
 pub trait SendAndGetText {
     fn send_and_get_text(self) -> impl std::future::Future<Output = Result<String>> + Send;
 }
 
+/// Requests of the [`OAuthClient`](crate::authentication::OAuthClient), which
+/// are traced but not logged, since their bodies hold tokens.
 impl SendAndGetText for RequestBuilder {
     async fn send_and_get_text(self) -> Result<String> {
-        let res = self.send().await.context(Error::RequestSend)?;
+        let (client, request) = self.build_split();
+        let request = request.context(Error::RequestSend)?;
+
+        let span = tracing::info_span!(
+            "notion.request",
+            method = %request.method(),
+            endpoint = request.url().path(),
+            status = field::Empty,
+            request_id = field::Empty,
+            duration_ms = field::Empty,
+        );
+
+        async move {
+            let start = Instant::now();
+            let res = client.execute(request).await.context(Error::RequestSend)?;
+
+            let span = tracing::Span::current();
+            span.record("status", res.status().as_u16());
+            span.record(
+                "duration_ms",
+                u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+            );
+            if let Some(request_id) = res
+                .headers()
+                .get(metrics::REQUEST_ID_HEADER)
+                .and_then(|request_id| request_id.to_str().ok())
+            {
+                span.record("request_id", request_id);
+            }
+
+            res.text().await.context(Error::ParseResponse)
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+/// A request to the API, sent through the cassette of the client if it has
+/// one.
+#[derive(Debug)]
+pub(crate) struct ApiRequest<T> {
+    method: Method,
+    url: String,
+    query: Vec<(String, String)>,
+    body: Option<serde_json::Result<Vec<u8>>>,
+    /// The content type of the body, if it isn't JSON.
+    content_type: Option<String>,
+    notion: Notion<T>,
+}
+
+/// An [`ApiRequest`] that is ready to be sent.
+#[derive(Debug)]
+pub(crate) struct PreparedRequest<T> {
+    request: HttpRequest,
+    notion: Notion<T>,
+}
+
+impl<T: HttpTransport> ApiRequest<T> {
+    pub(crate) fn json<B: Serialize + ?Sized>(mut self, json: &B) -> Self {
+        self.body = Some(serde_json::to_vec(json));
+        self.content_type = None;
+        self
+    }
+
+    /// Sends `body` as is, such as a multipart form.
+    pub(crate) fn body(mut self, body: Vec<u8>, content_type: &str) -> Self {
+        self.body = Some(Ok(body));
+        self.content_type = Some(content_type.to_owned());
+        self
+    }
+
+    pub(crate) fn query<K: AsRef<str>, V: AsRef<str>>(mut self, query: &[(K, V)]) -> Self {
+        self.query.extend(
+            query
+                .iter()
+                .map(|(key, value)| (key.as_ref().to_owned(), value.as_ref().to_owned())),
+        );
+        self
+    }
+
+    pub(crate) fn prepare(self) -> Result<PreparedRequest<T>> {
+        let mut url = Url::parse(&self.url).context(Error::RequestSend)?;
+
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+
+        let mut headers = self.notion.headers.clone();
+
+        let body = self.body.transpose().context(Error::SerializeRequest)?;
+
+        if body.is_some() {
+            let content_type = match &self.content_type {
+                Some(content_type) => {
+                    HeaderValue::from_str(content_type).context(Error::SerializeRequest)?
+                },
+                None => HeaderValue::from_static("application/json"),
+            };
+
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+
+        Ok(PreparedRequest {
+            request: HttpRequest {
+                method: self.method,
+                url: url.into(),
+                headers,
+                body,
+            },
+            notion: self.notion,
+        })
+    }
+}
+
+impl<T: HttpTransport> SendAndGetText for ApiRequest<T> {
+    async fn send_and_get_text(self) -> Result<String> {
+        Ok(self.prepare()?.send().await?.1)
+    }
+}
 
-        res.text().await.context(Error::ParseResponse)
+impl<T: HttpTransport> PreparedRequest<T> {
+    pub(crate) fn url(&self) -> &str {
+        &self.request.url
+    }
+
+    /// Sends the request, returning whether it succeeded and the response
+    /// body.
+    ///
+    /// The request is traced and measured, see [`crate::metrics`].
+    pub(crate) async fn send(self) -> Result<(bool, String)> {
+        let Self { request, notion } = self;
+
+        let path = request
+            .url
+            .strip_prefix(&notion.base_url)
+            .unwrap_or(&request.url);
+        let (endpoint, object_id) = metrics::endpoint(path);
+
+        let span = tracing::info_span!(
+            "notion.request",
+            method = %request.method,
+            endpoint,
+            object_id,
+            status = field::Empty,
+            request_id = field::Empty,
+            duration_ms = field::Empty,
+            retries = field::Empty,
+        );
+
+        let method = request.method.clone();
+        let mut attempts = Attempts::default();
+        let start = Instant::now();
+
+        let result = notion
+            .send_recorded(request, &mut attempts)
+            .instrument(span.clone())
+            .await;
+
+        let duration = start.elapsed();
+
+        span.record(
+            "duration_ms",
+            u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        );
+        span.record("retries", attempts.retries);
+
+        match &result {
+            Ok(response) => {
+                span.record("status", response.status.as_u16());
+                if let Some(request_id) = metrics::request_id(response) {
+                    span.record("request_id", request_id);
+                }
+            },
+            Err(e) => {
+                let _guard = span.enter();
+                tracing::warn!(error = %e, "request failed");
+            },
+        }
+
+        if let Some(hook) = &notion.metrics {
+            hook.record(&RequestMetrics {
+                method,
+                endpoint,
+                status: result.as_ref().ok().map(|response| response.status),
+                duration,
+                retries: attempts.retries,
+                rate_limited: attempts.rate_limited,
+            });
+        }
+
+        let response = result?;
+
+        Ok((response.status.is_success(), response.body))
+    }
+}
+
+/// The retries of a request.
+#[derive(Debug, Default)]
+struct Attempts {
+    retries: u32,
+    rate_limited: u32,
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Sends a request through the cassette of the client if it has one.
+    async fn send_recorded(
+        &self,
+        request: HttpRequest,
+        attempts: &mut Attempts,
+    ) -> Result<HttpResponse> {
+        let Some(cassette) = &self.cassette else {
+            return self.send_with_retries(request, attempts).await;
+        };
+
+        let recorded = RecordedRequest::new(&request, &self.base_url);
+
+        match cassette.mode() {
+            Mode::Replay => {
+                let response = cassette.replay_response(&recorded)?;
+
+                Ok(HttpResponse {
+                    status: StatusCode::from_u16(response.status).context(Error::CassetteRead)?,
+                    headers: HeaderMap::new(),
+                    body: response.text(),
+                })
+            },
+            Mode::Record => {
+                let response = self.send_with_retries(request, attempts).await?;
+
+                cassette.record_interaction(Interaction {
+                    request: recorded,
+                    response: RecordedResponse::new(response.status.as_u16(), &response.body),
+                })?;
+
+                Ok(response)
+            },
+        }
+    }
+
+    /// Sends a request, sending it again after a 429 response, or a 5xx
+    /// response to an idempotent request, up to [`Notion::retries`] times.
+    async fn send_with_retries(
+        &self,
+        request: HttpRequest,
+        attempts: &mut Attempts,
+    ) -> Result<HttpResponse> {
+        let path = request
+            .url
+            .strip_prefix(&self.base_url)
+            .unwrap_or(&request.url);
+        let idempotent = idempotent(&request.method, &metrics::endpoint(path).0);
+
+        loop {
+            if attempts.retries >= self.max_retries {
+                return self.send_once(request, attempts).await;
+            }
+
+            let response = self.send_once(request.clone(), attempts).await?;
+
+            let Some(delay) = retry_delay(&response, idempotent, attempts.retries) else {
+                return Ok(response);
+            };
+
+            tracing::warn!(
+                status = response.status.as_u16(),
+                retry_in_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                "retrying request",
+            );
+
+            tokio::time::sleep(delay).await;
+            attempts.retries += 1;
+        }
+    }
+
+    async fn send_once(
+        &self,
+        request: HttpRequest,
+        attempts: &mut Attempts,
+    ) -> Result<HttpResponse> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.wait().await;
+        }
+
+        metrics::log_request(&request);
+
+        let response = self.transport.send(request).await?;
+
+        metrics::log_response(&response);
+
+        if response.status == StatusCode::TOO_MANY_REQUESTS {
+            attempts.rate_limited += 1;
+        }
+
+        Ok(response)
+    }
+}
+
+/// Whether sending a request twice has the same effect as sending it once, so
+/// that it can be retried after a 5xx response, which it may have been
+/// applied despite. Creating pages, appending blocks and the like aren't.
+fn idempotent(method: &Method, endpoint: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE => true,
+        // updates, but not appending children
+        Method::PATCH => !endpoint.ends_with("/children"),
+        // queries
+        Method::POST => endpoint == "search" || endpoint.ends_with("/query"),
+        _ => false,
+    }
+}
+
+/// How long to wait before retrying a request that got `response`, if it
+/// should be retried: the `Retry-After` of rate limited responses, or an
+/// exponential backoff for server errors of idempotent requests.
+fn retry_delay(response: &HttpResponse, idempotent: bool, retries: u32) -> Option<Duration> {
+    let backoff = Duration::from_millis(500)
+        .saturating_mul(2_u32.saturating_pow(retries))
+        .min(MAX_BACKOFF);
+
+    match response.status {
+        StatusCode::TOO_MANY_REQUESTS => Some(
+            response
+                .headers
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                .map_or(backoff, Duration::from_secs),
+        ),
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT
+            if idempotent =>
+        {
+            Some(backoff)
+        },
+        _ => None,
     }
 }
 
@@ -29,9 +441,15 @@ macro_rules! api_method {
         paste::item! {
             $(
                 #[allow(dead_code)]
-                pub(crate) fn [< api_ $method>](&self, path: &str) -> RequestBuilder {
-                    let url = self.api_url(path);
-                    self.http.$method(&url)
+                pub(crate) fn [< api_ $method>](&self, path: &str) -> ApiRequest<T> {
+                    ApiRequest {
+                        method: Method::[< $method:upper >],
+                        url: self.api_url(path),
+                        query: Vec::new(),
+                        body: None,
+                        content_type: None,
+                        notion: self.clone(),
+                    }
                 }
             )*
         }
@@ -39,7 +457,16 @@ macro_rules! api_method {
 }
 
 impl Notion {
+    /// Creates a client that sends its requests with [`reqwest`].
     pub fn new(api_token: &str) -> Result<Self> {
+        Self::with_transport(api_token, ReqwestTransport::new()?)
+    }
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Creates a client that sends its requests through `transport`. See
+    /// [`crate::transport`].
+    pub fn with_transport(api_token: &str, transport: T) -> Result<Self> {
         let mut headers = HeaderMap::new();
 
         headers.insert("Notion-Version", HeaderValue::from_static(API_VERSION));
@@ -49,20 +476,110 @@ impl Notion {
 
         headers.insert(header::AUTHORIZATION, auth);
 
-        let http = ClientBuilder::new()
-            .default_headers(headers)
-            .build()
-            .context(Error::ClientBuild)?;
+        Ok(Self {
+            transport: Arc::new(transport),
+            headers,
+            base_url: API_BASE_URL.to_owned(),
+            cache: None,
+            cassette: None,
+            drift: None,
+            metrics: None,
+            max_retries: 0,
+            rate_limiter: None,
+        })
+    }
 
-        Ok(Self { http })
+    /// Sends requests to `base_url` instead of the Notion API, such as a local
+    /// mock server.
+    ///
+    /// Endpoint paths are appended to the base URL, so it should include the
+    /// API version prefix, e.g. `http://localhost:8080/v1/`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_owned();
+        if !self.base_url.ends_with('/') {
+            self.base_url.push('/');
+        }
+        self
+    }
+
+    /// Caches the responses of the endpoints that retrieve blocks, pages and
+    /// databases. See [`crate::cache`].
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(Arc::new(ResponseCache::new(config)));
+        self
+    }
+
+    /// Records requests to, or replays them from, a cassette. See
+    /// [`crate::cassette`].
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+        self
+    }
+
+    /// Checks every response for fields that the model doesn't capture. See
+    /// [`crate::drift`].
+    pub fn drift(mut self, mode: DriftMode) -> Self {
+        self.drift = Some(mode);
+        self
+    }
+
+    /// Reports the metrics of every request to `metrics`. See
+    /// [`crate::metrics`].
+    pub fn metrics<M: Metrics>(mut self, metrics: M) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Sends requests again, up to `max_retries` times, after a 429
+    /// `rate_limited` response, waiting as long as its `Retry-After` header
+    /// asks, or after a 500, 502, 503 or 504 response, with an exponential
+    /// backoff. Requests that may have been applied despite a server error,
+    /// such as creating a page or appending blocks, are only retried after a
+    /// 429. Requests are not retried by default.
+    pub const fn retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sends at most `requests_per_second` requests per second, across the
+    /// clones of the client, making later requests wait for their turn.
+    /// Notion allows an average of 3 requests per second per integration.
+    /// `None` or `Some(0)` doesn't limit requests, which is the default.
+    pub fn rate_limit(mut self, requests_per_second: Option<u32>) -> Self {
+        self.rate_limiter = requests_per_second
+            .filter(|requests_per_second| *requests_per_second > 0)
+            .map(|requests_per_second| Arc::new(RateLimiter::new(requests_per_second)));
+        self
+    }
+
+    /// The transport that sends the requests of the client.
+    pub fn transport(&self) -> &T {
+        &self.transport
     }
 
     /// Returns the absolute URL for an endpoint in the API.
     pub(crate) fn api_url(&self, path: &str) -> String {
-        API_BASE_URL.to_owned() + path
+        self.base_url.clone() + path
+    }
+
+    /// Parses the response of an endpoint, checking it for drift if enabled.
+    pub(crate) fn parse<R: DeserializeOwned + Serialize>(
+        &self,
+        text: &str,
+        what: &'static str,
+        endpoint: &'static str,
+    ) -> Result<R> {
+        let context = Error::SerializeResponse(what, endpoint);
+
+        let res = serde_json::from_str::<R>(text).context(context)?;
+
+        if let Some(mode) = &self.drift {
+            drift::check(mode, endpoint, text, &res).context(context)?;
+        }
+
+        Ok(res)
     }
 
-    // TODO: ratelimits, maybe. notion doesn't respond with a rate limit header
     // TODO: size limits. see https://developers.notion.com/reference/request-limits
     api_method! {
         delete;
@@ -73,3 +590,81 @@ impl Notion {
         put;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: StatusCode, retry_after: Option<&'static str>) -> HttpResponse {
+        let mut headers = HeaderMap::new();
+        if let Some(retry_after) = retry_after {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from_static(retry_after));
+        }
+
+        HttpResponse {
+            status,
+            headers,
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn retry_delays() {
+        let rate_limited = response(StatusCode::TOO_MANY_REQUESTS, Some("3"));
+        assert_eq!(
+            retry_delay(&rate_limited, false, 0),
+            Some(Duration::from_secs(3))
+        );
+
+        let rate_limited = response(StatusCode::TOO_MANY_REQUESTS, None);
+        assert_eq!(
+            retry_delay(&rate_limited, false, 1),
+            Some(Duration::from_secs(1))
+        );
+
+        let unavailable = response(StatusCode::SERVICE_UNAVAILABLE, None);
+        assert_eq!(
+            retry_delay(&unavailable, true, 0),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            retry_delay(&unavailable, true, 3),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(retry_delay(&unavailable, true, 40), Some(MAX_BACKOFF));
+        assert_eq!(retry_delay(&unavailable, false, 0), None);
+
+        let conflict = response(StatusCode::CONFLICT, None);
+        assert_eq!(retry_delay(&conflict, true, 0), None);
+        assert_eq!(retry_delay(&response(StatusCode::OK, None), true, 0), None);
+    }
+
+    #[test]
+    fn idempotent_requests() {
+        assert!(idempotent(&Method::GET, "pages/{id}"));
+        assert!(idempotent(&Method::DELETE, "blocks/{id}"));
+        assert!(idempotent(&Method::PATCH, "pages/{id}"));
+        assert!(idempotent(&Method::PATCH, "blocks/{id}"));
+        assert!(idempotent(&Method::POST, "databases/{id}/query"));
+        assert!(idempotent(&Method::POST, "search"));
+
+        assert!(!idempotent(&Method::POST, "pages"));
+        assert!(!idempotent(&Method::POST, "file_uploads"));
+        assert!(!idempotent(&Method::POST, "comments"));
+        assert!(!idempotent(&Method::PATCH, "blocks/{id}/children"));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter() {
+        let rate_limiter = RateLimiter::new(20);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            rate_limiter.wait().await;
+        }
+
+        // the first request doesn't wait
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+}
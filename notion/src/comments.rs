@@ -1 +1,126 @@
+use anyhow::Result;
+use notion_model::{ids::BlockId, objects::comment::Comment};
 
+use crate::{
+    client::{Notion, SendAndGetText},
+    errors::NotionApiError,
+    model::pagination::List,
+    result_types,
+    transport::HttpTransport,
+};
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Create comment
+    ///
+    /// Creates a comment in a page or existing discussion thread.
+    ///
+    /// There are two locations you can add a new comment to:
+    ///
+    /// 1. A page
+    /// 2. An existing discussion thread
+    ///
+    /// If the intention is to add a new comment to a page, a parent object must
+    /// be provided in the body params. Alternatively, if a new comment is being
+    /// added to an existing discussion thread, the discussion_id string must be
+    /// provided in the body params. Exactly one of these parameters must be
+    /// provided.
+    ///
+    /// # 🚧
+    /// The API does not currently support starting a new comment thread on a
+    /// block inline.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have insert comment
+    /// capabilities. Attempting to call this API without insert comment
+    /// capabilities will return an HTTP response with a 403 status code. For
+    /// more information on integration capabilities, see the capabilities
+    /// guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the page or discussion doesn't exist, or
+    /// if the integration doesn't have access to it.
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn create_comment(&self, comment: Comment) -> Result<Comment> {
+        let text = self
+            .api_post("comments")
+            .json(&comment)
+            .send_and_get_text()
+            .await?;
+
+        let res = self.parse::<result_types::Comment>(&text, "Comment", "create_comment")?;
+
+        match res {
+            result_types::Comment::Comment(comment) => Ok(comment),
+            result_types::Comment::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// # Retrieve comments
+    ///
+    /// Retrieves a list of un-resolved Comment objects from a page or block.
+    ///
+    /// See Pagination for details about how to use a cursor to iterate through
+    /// the list. Pass the `next_cursor` of the previous response as
+    /// `start_cursor` to retrieve the next page of comments.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have read comment
+    /// capabilities. Attempting to call this API without read comment
+    /// capabilities will return an HTTP response with a 403 status code. For
+    /// more information on integration capabilities, see the capabilities
+    /// guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the block doesn't exist, or if the
+    /// integration doesn't have access to the block.
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn retrieve_comments(
+        &self,
+        block_id: BlockId,
+        start_cursor: Option<String>,
+    ) -> Result<List<Comment>> {
+        let block_id = block_id.to_string();
+
+        let mut query = vec![("block_id", &block_id)];
+        query.extend(start_cursor.iter().map(|cursor| ("start_cursor", cursor)));
+
+        let text = self
+            .api_get("comments")
+            .query(&query)
+            .send_and_get_text()
+            .await?;
+
+        let res =
+            self.parse::<result_types::List<Comment>>(&text, "List<Comment>", "retrieve_comments")?;
+
+        match res {
+            result_types::List::List(comment_list) => Ok(comment_list),
+            result_types::List::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// Retrieves the comments of a page or block, following `next_cursor` until
+    /// every comment has been retrieved.
+    pub async fn retrieve_comments_all(&self, block_id: BlockId) -> Result<Vec<Comment>> {
+        let mut comments = Vec::new();
+        let mut start_cursor = None;
+
+        loop {
+            let list = self.retrieve_comments(block_id, start_cursor).await?;
+            comments.extend(list.results);
+
+            match list.next_cursor {
+                Some(cursor) if list.has_more => start_cursor = Some(cursor),
+                _ => return Ok(comments),
+            }
+        }
+    }
+}
@@ -0,0 +1,684 @@
+//! Exporting the rows of a database to CSV, and importing CSV rows into a
+//! database.
+//!
+//! Each CSV row is a page of the database and each column is a property,
+//! with the header row holding the property names. An additional [`ID_COLUMN`]
+//! holds the id of the page.
+//!
+//! The type of each property in the database schema decides how its value is
+//! written and read:
+//!
+//! | Property type                 | CSV value                                |
+//! |-------------------------------|------------------------------------------|
+//! | title, rich text              | the plain text                           |
+//! | number                        | the number, e.g. `-12.5`                 |
+//! | checkbox                      | `true` or `false`                        |
+//! | select, status                | the option name                          |
+//! | multi-select                  | option names separated by `, `           |
+//! | date                          | ISO 8601 start, or `start/end` for ranges |
+//! | people                        | user ids separated by `, `               |
+//! | relation                      | page ids separated by `, `               |
+//! | files                         | file URLs separated by `, `              |
+//! | email, phone number, url      | the value                                |
+//! | created/last edited time      | ISO 8601 date time                       |
+//! | created/last edited by        | the user id                              |
+//! | formula, rollup, unique id    | the computed value                       |
+//!
+//! Values computed by Notion, such as formulas, can be exported but are
+//! skipped when importing.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use anyhow::{Context, Result};
+use notion_model::{
+    ids::{DatabaseId, PageId, UserId},
+    objects::{
+        block::FileData,
+        database_property::{DatabaseProperty, DatabasePropertyData},
+        date::DateOrDateTime,
+        page::Page,
+        properties::{
+            Checkbox, Date, Email, Files, FormulaData, MultiSelect, Number, People, PhoneNumber,
+            Property, PropertyData, Relation, RichText, RollupData, Select, SelectOption, Status,
+            StatusOption, Title, UniqueId, Url,
+        },
+        rich_text::{self, DateMention, PageMention, RichTextData},
+        user::PartialUser,
+    },
+    query::DatabaseQuery,
+};
+
+use crate::{client::Notion, errors::Error, transport::HttpTransport};
+
+/// The name of the column holding the id of the page of each row.
+///
+/// When importing, rows with an id update that page instead of creating a new
+/// one.
+pub const ID_COLUMN: &str = "id";
+
+/// Separator used between the items of list values, such as multi-selects.
+const LIST_SEPARATOR: &str = ", ";
+
+/// The maximum length of the content of a single rich text object.
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Options for [`Notion::import_database_csv`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Name of a property used to find the existing page for a row that does
+    /// not have an id. Rows whose value for this property matches the value of
+    /// an existing page update that page.
+    pub match_property: Option<String>,
+}
+
+impl ImportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn match_property(mut self, match_property: Option<&str>) -> Self {
+        self.match_property = match_property.map(|s| s.to_string());
+        self
+    }
+}
+
+/// The result of importing a CSV file.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Pages created for rows without an existing page.
+    pub created: Vec<PageId>,
+    /// Existing pages that were updated.
+    pub updated: Vec<PageId>,
+    /// Columns that were ignored because they are not in the database schema,
+    /// or because their values can't be set through the API.
+    pub skipped_columns: Vec<String>,
+    /// Rows that could not be imported.
+    pub errors: Vec<RowError>,
+}
+
+/// A row that could not be imported.
+#[derive(Debug)]
+pub struct RowError {
+    /// The number of the row, where 1 is the first row after the header.
+    pub row: usize,
+    /// Why the row could not be imported.
+    pub error: anyhow::Error,
+}
+
+enum ImportedRow {
+    Created(PageId),
+    Updated(PageId),
+}
+
+/// A column of the CSV file that maps to a writable database property.
+struct Column<'a> {
+    index: usize,
+    name: &'a str,
+    property: &'a DatabaseProperty,
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Writes every page of a database to `writer` as CSV, returning the
+    /// number of rows written.
+    ///
+    /// The first column is [`ID_COLUMN`], followed by the title property and
+    /// then the other properties in alphabetical order.
+    pub async fn export_database_csv<W: Write>(
+        &self,
+        database_id: DatabaseId,
+        writer: W,
+    ) -> Result<usize> {
+        let database = self.retrieve_database(database_id).await?;
+        let pages = self
+            .query_database_all(database_id, DatabaseQuery::new())
+            .await?;
+
+        let columns = column_order(&database.properties);
+
+        let mut writer = WriterBuilder::new().from_writer(writer);
+
+        writer
+            .write_record(std::iter::once(ID_COLUMN).chain(columns.iter().copied()))
+            .context(Error::CsvWrite)?;
+
+        for page in &pages {
+            let id = page.id.map(|id| id.to_string()).unwrap_or_default();
+            let values = columns.iter().map(|name| {
+                page.properties
+                    .get(*name)
+                    .map(|property| format_property(&property.data))
+                    .unwrap_or_default()
+            });
+
+            writer
+                .write_record(std::iter::once(id).chain(values))
+                .context(Error::CsvWrite)?;
+        }
+
+        writer.flush().context(Error::CsvWrite)?;
+
+        Ok(pages.len())
+    }
+
+    /// Imports the rows of a CSV file into a database.
+    ///
+    /// The header row is matched against the property names of the database
+    /// schema. Rows are imported one at a time, and a row that fails to parse
+    /// or is rejected by the API is recorded in the report instead of stopping
+    /// the import.
+    ///
+    /// A row updates an existing page when it has a value in the
+    /// [`ID_COLUMN`], or when its value for [`ImportOptions::match_property`]
+    /// matches an existing page. Otherwise a new page is created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can't be retrieved, the header row
+    /// can't be read, or the match property is not a column of the CSV file.
+    pub async fn import_database_csv<R: Read>(
+        &self,
+        database_id: DatabaseId,
+        reader: R,
+        options: ImportOptions,
+    ) -> Result<ImportReport> {
+        let database = self.retrieve_database(database_id).await?;
+
+        let mut reader = ReaderBuilder::new().from_reader(reader);
+        let headers = reader.headers().context(Error::CsvRead)?.clone();
+
+        let mut report = ImportReport::default();
+        let mut columns = Vec::new();
+        let mut id_index = None;
+
+        for (index, name) in headers.iter().enumerate() {
+            match database.properties.get(name) {
+                Some(property) if is_importable(&property.data) => columns.push(Column {
+                    index,
+                    name,
+                    property,
+                }),
+                None if name == ID_COLUMN => id_index = Some(index),
+                _ => report.skipped_columns.push(name.to_string()),
+            }
+        }
+
+        let matcher = match &options.match_property {
+            Some(name) => {
+                let index = headers
+                    .iter()
+                    .position(|header| header == name)
+                    .with_context(|| format!("match property {name} is not a CSV column"))?;
+
+                let pages = self
+                    .query_database_all(database_id, DatabaseQuery::new())
+                    .await?;
+
+                let existing = pages
+                    .iter()
+                    .filter_map(|page| {
+                        let value = format_property(&page.properties.get(name)?.data);
+                        Some((value, page.id?))
+                    })
+                    .collect::<HashMap<_, _>>();
+
+                Some((index, existing))
+            },
+            None => None,
+        };
+
+        for (row, record) in reader.records().enumerate() {
+            let imported = match record.context(Error::CsvRead) {
+                Ok(record) => {
+                    let page_id = match id_index.and_then(|index| record.get(index)) {
+                        Some(id) if !id.trim().is_empty() => {
+                            PageId::from_str(id.trim()).map(Some).map_err(Into::into)
+                        },
+                        _ => Ok(matcher.as_ref().and_then(|(index, existing)| {
+                            record
+                                .get(*index)
+                                .and_then(|value| existing.get(value).copied())
+                        })),
+                    };
+
+                    match page_id {
+                        Ok(page_id) => {
+                            self.import_row(database_id, &columns, &record, page_id)
+                                .await
+                        },
+                        Err(e) => Err(e),
+                    }
+                },
+                Err(e) => Err(e),
+            };
+
+            match imported {
+                Ok(ImportedRow::Created(id)) => report.created.push(id),
+                Ok(ImportedRow::Updated(id)) => report.updated.push(id),
+                Err(error) => report.errors.push(RowError {
+                    row: row + 1,
+                    error,
+                }),
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn import_row(
+        &self,
+        database_id: DatabaseId,
+        columns: &[Column<'_>],
+        record: &StringRecord,
+        page_id: Option<PageId>,
+    ) -> Result<ImportedRow> {
+        let mut properties = HashMap::new();
+
+        for column in columns {
+            let value = record.get(column.index).unwrap_or_default();
+
+            let data = parse_property(&column.property.data, value)
+                .with_context(|| format!("invalid value for property {}", column.name))?;
+
+            if let Some(data) = data {
+                properties.insert(column.name.to_string(), Property::new(data));
+            }
+        }
+
+        match page_id {
+            Some(page_id) => {
+                self.update_page(Page::new().id(Some(page_id)).properties(properties))
+                    .await?;
+
+                Ok(ImportedRow::Updated(page_id))
+            },
+            None => {
+                let page = self
+                    .create_page(
+                        Page::new()
+                            .parent(Some(database_id.into()))
+                            .properties(properties),
+                    )
+                    .await?;
+
+                Ok(ImportedRow::Created(page.id.context(Error::MissingPageId)?))
+            },
+        }
+    }
+}
+
+/// The title property first, followed by the other properties in
+/// alphabetical order.
+fn column_order(properties: &HashMap<String, DatabaseProperty>) -> Vec<&str> {
+    let mut columns = properties
+        .iter()
+        .map(|(name, property)| {
+            (
+                !matches!(property.data, DatabasePropertyData::Title(_)),
+                name,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    columns.sort();
+
+    columns.into_iter().map(|(_, name)| name.as_str()).collect()
+}
+
+/// Whether values of a property type can be imported from CSV.
+const fn is_importable(data: &DatabasePropertyData) -> bool {
+    data.is_writable() && !matches!(data, DatabasePropertyData::Files(_))
+}
+
+/// Formats a page property value as a CSV value.
+pub fn format_property(data: &PropertyData) -> String {
+    match data {
+        PropertyData::Checkbox(Checkbox(checked)) => checked.to_string(),
+        PropertyData::CreatedBy(user) | PropertyData::LastEditedBy(user) => user.id.to_string(),
+        PropertyData::CreatedTime(time) | PropertyData::LastEditedTime(time) => time.to_string(),
+        PropertyData::Date(Date(date)) => date.as_ref().map(format_date).unwrap_or_default(),
+        PropertyData::Email(Email(value))
+        | PropertyData::PhoneNumber(PhoneNumber(value))
+        | PropertyData::Url(Url(value)) => value.clone().unwrap_or_default(),
+        PropertyData::Files(Files(files)) => join(files.iter().map(|file| match &file.data {
+            FileData::File(file) => file.url.as_str(),
+            FileData::External(file) => file.url.as_str(),
+            FileData::FileUpload(_) => file.name.as_str(),
+        })),
+        PropertyData::Formula(formula) => match formula {
+            FormulaData::Boolean { boolean } => boolean
+                .map(|boolean| boolean.to_string())
+                .unwrap_or_default(),
+            FormulaData::Date { date } => date.as_ref().map(format_date).unwrap_or_default(),
+            FormulaData::Number { number } => {
+                number.map(|number| number.to_string()).unwrap_or_default()
+            },
+            FormulaData::String { string } => string.clone().unwrap_or_default(),
+        },
+        PropertyData::MultiSelect(MultiSelect(options)) => {
+            join(options.iter().filter_map(|option| option.name.as_deref()))
+        },
+        PropertyData::Number(Number(number)) => {
+            number.map(|number| number.to_string()).unwrap_or_default()
+        },
+        PropertyData::People(People(users)) => join(users.iter().map(|user| user.id.to_string())),
+        PropertyData::Relation(Relation(pages)) => {
+            join(pages.iter().map(|page| page.id.to_string()))
+        },
+        PropertyData::Rollup(rollup) => match &rollup.data {
+            RollupData::Array { array } => join(array.iter().map(format_property)),
+            RollupData::Date { date } => date.as_ref().map(format_date).unwrap_or_default(),
+            RollupData::Number { number } => {
+                number.map(|number| number.to_string()).unwrap_or_default()
+            },
+            RollupData::Incomplete | RollupData::Unsupported => String::new(),
+        },
+        PropertyData::RichText(RichText(text)) | PropertyData::Title(Title(text)) => {
+            plain_text(text)
+        },
+        PropertyData::Select(Select(option)) => option
+            .as_ref()
+            .and_then(|option| option.name.clone())
+            .unwrap_or_default(),
+        PropertyData::Status(Status(option)) => option
+            .as_ref()
+            .and_then(|option| option.name.clone())
+            .unwrap_or_default(),
+        PropertyData::UniqueId(UniqueId {
+            prefix,
+            number: Some(number),
+        }) => match prefix.as_deref() {
+            Some(prefix) if !prefix.is_empty() => format!("{prefix}-{number}"),
+            _ => number.to_string(),
+        },
+        PropertyData::Button(_)
+        | PropertyData::UniqueId(_)
+        | PropertyData::Verification(_)
+        | PropertyData::Unknown { .. } => String::new(),
+    }
+}
+
+/// Parses a CSV value into a page property value of the type given by the
+/// database schema.
+///
+/// Returns `None` for property types whose values can't be set through the
+/// API. Empty values clear the property.
+pub fn parse_property(schema: &DatabasePropertyData, value: &str) -> Result<Option<PropertyData>> {
+    let trimmed = value.trim();
+
+    let data = match schema {
+        DatabasePropertyData::Checkbox(_) => PropertyData::Checkbox(Checkbox(parse_bool(trimmed)?)),
+        DatabasePropertyData::Date(_) => {
+            PropertyData::Date(Date(non_empty(trimmed).map(parse_date).transpose()?))
+        },
+        DatabasePropertyData::Email(_) => {
+            PropertyData::Email(Email(non_empty(trimmed).map(Into::into)))
+        },
+        DatabasePropertyData::MultiSelect(_) => {
+            PropertyData::MultiSelect(MultiSelect::from_names(split(trimmed)))
+        },
+        DatabasePropertyData::Number(_) => PropertyData::Number(Number(
+            non_empty(trimmed)
+                .map(f64::from_str)
+                .transpose()
+                .context("invalid number")?,
+        )),
+        DatabasePropertyData::People(_) => PropertyData::People(People(
+            split(trimmed)
+                .map(|id| {
+                    Ok(PartialUser {
+                        id: UserId::from_str(id)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        )),
+        DatabasePropertyData::PhoneNumber(_) => {
+            PropertyData::PhoneNumber(PhoneNumber(non_empty(trimmed).map(Into::into)))
+        },
+        DatabasePropertyData::Relation(_) => PropertyData::Relation(Relation(
+            split(trimmed)
+                .map(|id| Ok(PageMention::new(PageId::from_str(id)?)))
+                .collect::<Result<_>>()?,
+        )),
+        DatabasePropertyData::RichText(_) => PropertyData::RichText(RichText(text(value))),
+        DatabasePropertyData::Select(_) => {
+            PropertyData::Select(Select(non_empty(trimmed).map(|name| SelectOption {
+                color: None,
+                id: None,
+                name: Some(name.to_string()),
+            })))
+        },
+        DatabasePropertyData::Status(_) => {
+            PropertyData::Status(Status(non_empty(trimmed).map(|name| StatusOption {
+                color: None,
+                id: None,
+                name: Some(name.to_string()),
+            })))
+        },
+        DatabasePropertyData::Title(_) => PropertyData::Title(Title(text(value))),
+        DatabasePropertyData::Url(_) => PropertyData::Url(Url(non_empty(trimmed).map(Into::into))),
+        DatabasePropertyData::Button(_)
+        | DatabasePropertyData::CreatedBy(_)
+        | DatabasePropertyData::CreatedTime(_)
+        | DatabasePropertyData::Files(_)
+        | DatabasePropertyData::Formula(_)
+        | DatabasePropertyData::LastEditedBy(_)
+        | DatabasePropertyData::LastEditedTime(_)
+        | DatabasePropertyData::Rollup(_)
+        | DatabasePropertyData::UniqueId(_)
+        | DatabasePropertyData::Verification(_)
+        | DatabasePropertyData::Unknown { .. } => return Ok(None),
+    };
+
+    Ok(Some(data))
+}
+
+fn join<I, S>(items: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    items
+        .into_iter()
+        .map(|item| item.as_ref().to_string())
+        .collect::<Vec<_>>()
+        .join(LIST_SEPARATOR)
+}
+
+fn split(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    (!value.is_empty()).then_some(value)
+}
+
+pub(crate) fn plain_text(text: &[rich_text::RichText]) -> String {
+    text.iter()
+        .map(|text| match (&text.plain_text, &text.data) {
+            (Some(plain_text), _) => plain_text.as_str(),
+            (None, RichTextData::Text(text)) => text.content.as_str(),
+            (None, RichTextData::Equation(equation)) => equation.expression.as_str(),
+            (None, RichTextData::Mention(_) | RichTextData::Unknown { .. }) => "",
+        })
+        .collect()
+}
+
+/// Splits text into rich text objects that are within the length limit.
+fn text(value: &str) -> Vec<rich_text::RichText> {
+    let chars = value.chars().collect::<Vec<_>>();
+
+    chars
+        .chunks(MAX_TEXT_LENGTH)
+        .map(|chunk| rich_text::RichText::new_text(&chunk.iter().collect::<String>()))
+        .collect()
+}
+
+fn format_date(date: &DateMention) -> String {
+    date.end.map_or_else(
+        || date.start.to_string(),
+        |end| format!("{}/{}", date.start.to_string(), end.to_string()),
+    )
+}
+
+fn parse_date(value: &str) -> Result<DateMention> {
+    let (start, end) = match value.split_once('/') {
+        Some((start, end)) => (start, Some(end)),
+        None => (value, None),
+    };
+
+    Ok(
+        DateMention::new(DateOrDateTime::from_str(start.trim())?).end(
+            end.map(|end| DateOrDateTime::from_str(end.trim()))
+                .transpose()?,
+        ),
+    )
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" | "x" => Ok(true),
+        "false" | "no" | "n" | "0" | "" => Ok(false),
+        _ => anyhow::bail!("invalid checkbox value {value}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notion_model::objects::{
+        database_property::{EmptyConfig, NumberConfig, SelectConfig},
+        properties::{Rollup, RollupFunction},
+    };
+
+    use super::*;
+
+    #[test]
+    fn number() {
+        let schema = DatabasePropertyData::Number(NumberConfig::default());
+        let data = parse_property(&schema, "-12.5").unwrap().unwrap();
+
+        assert_eq!(data, PropertyData::Number(Number(Some(-12.5))));
+        assert_eq!(format_property(&data), "-12.5");
+        assert!(parse_property(&schema, "twelve").is_err());
+    }
+
+    #[test]
+    fn empty_number() {
+        let schema = DatabasePropertyData::Number(NumberConfig::default());
+
+        assert_eq!(
+            parse_property(&schema, "").unwrap(),
+            Some(PropertyData::Number(Number(None)))
+        );
+    }
+
+    #[test]
+    fn formula_and_rollup() {
+        let formula = PropertyData::Formula(FormulaData::Number {
+            number: Some(-0.25),
+        });
+        let rollup = PropertyData::Rollup(Rollup {
+            function: RollupFunction::ShowOriginal,
+            data: RollupData::Array {
+                array: vec![
+                    PropertyData::Number(Number(Some(1.5))),
+                    PropertyData::Number(Number(None)),
+                    PropertyData::Number(Number(Some(-3.0))),
+                ],
+            },
+        });
+
+        assert_eq!(format_property(&formula), "-0.25");
+        assert_eq!(format_property(&rollup), "1.5, , -3");
+        assert_eq!(
+            format_property(&PropertyData::Formula(FormulaData::String { string: None })),
+            ""
+        );
+    }
+
+    #[test]
+    fn multi_select() {
+        let schema = DatabasePropertyData::MultiSelect(SelectConfig::default());
+        let data = parse_property(&schema, "urgent,  backend ,")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(format_property(&data), "urgent, backend");
+    }
+
+    #[test]
+    fn date_range() {
+        let schema = DatabasePropertyData::Date(EmptyConfig {});
+        let data = parse_property(&schema, "2023-01-01/2023-01-05T10:00:00+00:00")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            format_property(&data),
+            "2023-01-01/2023-01-05T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn people() {
+        let schema = DatabasePropertyData::People(EmptyConfig {});
+        let value = "3e1fc0f5-d02e-48ae-84c0-7ae06deece9f, 6e9612c8-1c7d-4356-ba91-53eab009e6f4";
+        let data = parse_property(&schema, value).unwrap().unwrap();
+
+        assert_eq!(format_property(&data), value);
+        assert!(parse_property(&schema, "someone").is_err());
+    }
+
+    #[test]
+    fn checkbox() {
+        let schema = DatabasePropertyData::Checkbox(EmptyConfig {});
+
+        assert_eq!(
+            parse_property(&schema, "Yes").unwrap(),
+            Some(PropertyData::Checkbox(Checkbox(true)))
+        );
+        assert_eq!(
+            parse_property(&schema, "").unwrap(),
+            Some(PropertyData::Checkbox(Checkbox(false)))
+        );
+        assert!(parse_property(&schema, "maybe").is_err());
+    }
+
+    #[test]
+    fn long_title() {
+        let schema = DatabasePropertyData::Title(EmptyConfig {});
+        let value = "a".repeat(MAX_TEXT_LENGTH + 1);
+        let data = parse_property(&schema, &value).unwrap().unwrap();
+
+        assert!(matches!(&data, PropertyData::Title(Title(text)) if text.len() == 2));
+        assert_eq!(format_property(&data), value);
+    }
+
+    #[test]
+    fn read_only() {
+        let schema = DatabasePropertyData::CreatedTime(EmptyConfig {});
+
+        assert_eq!(parse_property(&schema, "2023-01-01").unwrap(), None);
+    }
+
+    #[test]
+    fn columns() {
+        let properties = HashMap::from([
+            DatabaseProperty::new(DatabasePropertyData::Number(NumberConfig::default()))
+                .build_with_name("Points"),
+            DatabaseProperty::new(DatabasePropertyData::Title(EmptyConfig {}))
+                .build_with_name("Name"),
+            DatabaseProperty::new(DatabasePropertyData::Date(EmptyConfig {}))
+                .build_with_name("Due"),
+        ]);
+
+        assert_eq!(column_order(&properties), vec!["Name", "Due", "Points"]);
+    }
+}
@@ -1,13 +1,29 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
-use notion_model::{ids::DatabaseId, objects::database::Database};
+use notion_model::{
+    ids::DatabaseId,
+    objects::{
+        database::Database,
+        database_property::DatabaseProperty,
+        file_and_emoji::{Cover, FileOrEmoji},
+        page::Page,
+        rich_text::RichText,
+    },
+    query::DatabaseQuery,
+};
+use serde::Serialize;
 
 use crate::{
+    cache::CacheKind,
     client::{Notion, SendAndGetText},
     errors::{Error, NotionApiError},
+    model::pagination::List,
     result_types,
+    transport::HttpTransport,
 };
 
-impl Notion {
+impl<T: HttpTransport> Notion<T> {
     /// Create a database
     /// POST
     /// https://api.notion.com/v1/databases
@@ -45,8 +61,7 @@ impl Notion {
             .send_and_get_text()
             .await?;
 
-        let res = serde_json::from_str::<result_types::Database>(&text)
-            .context(Error::SerializeResponse("Database", "create_database"))?;
+        let res = self.parse::<result_types::Database>(&text, "Database", "create_database")?;
 
         match res {
             result_types::Database::Database(database) => Ok(database),
@@ -80,19 +95,18 @@ impl Notion {
     /// Database relations must be shared with your integration
     pub async fn retrieve_database(&self, database_id: DatabaseId) -> Result<Database> {
         let text = self
-            .api_get(&format!("databases/{database_id}"))
-            .send_and_get_text()
+            .send_cached(
+                CacheKind::Database,
+                database_id.to_string(),
+                self.api_get(&format!("databases/{database_id}")),
+            )
             .await?;
 
-
         // let text = include_str!("../../test_data/database.json");
 
-        println!("{}", text);
-
-        let res = serde_json::from_str::<result_types::Database>(&text)
-            .context(Error::SerializeResponse("Database", "retrieve_database"))?;
+        let res = self.parse::<result_types::Database>(&text, "Database", "retrieve_database")?;
         match res {
-            result_types::Database::Database(mut database) => {
+            result_types::Database::Database(database) => {
                 // only keep the first 25 database properties
                 // database.properties = database.properties.into_iter().take(25).collect();
                 Ok(database)
@@ -100,4 +114,138 @@ impl Notion {
             result_types::Database::Error(e) => anyhow::bail!(NotionApiError::from(e)),
         }
     }
+
+    /// # Update a database
+    ///
+    /// Updates the title, description, icon, cover and properties of a
+    /// database.
+    ///
+    /// The database's id field must be set. Only the fields that are given are
+    /// updated, so an empty title or description is left as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the database doesn't exist, or if the
+    /// integration doesn't have access to the database.
+    ///
+    /// Returns a 400 HTTP response if the request body is invalid, such as a
+    /// property that can't be changed.
+    pub async fn update_database(&self, database: Database) -> Result<Database> {
+        #[derive(Serialize)]
+        struct PartialDatabase {
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            title: Vec<RichText>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            description: Vec<RichText>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            icon: Option<FileOrEmoji>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            cover: Option<Cover>,
+            #[serde(skip_serializing_if = "HashMap::is_empty")]
+            properties: HashMap<String, DatabaseProperty>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            archived: Option<bool>,
+        }
+
+        let database_id = database.id.context(Error::MissingDatabaseId)?;
+
+        let partial_database = PartialDatabase {
+            title: database.title,
+            description: database.description,
+            icon: database.icon,
+            cover: database.cover,
+            properties: database.properties,
+            archived: database.archived,
+        };
+
+        let text = self
+            .api_patch(&format!("databases/{database_id}"))
+            .json(&partial_database)
+            .send_and_get_text()
+            .await?;
+
+        self.invalidate(&database_id.to_string());
+
+        let res = self.parse::<result_types::Database>(&text, "Database", "update_database")?;
+
+        match res {
+            result_types::Database::Database(database) => Ok(database),
+            result_types::Database::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// # Query a database
+    ///
+    /// Gets a list of Pages contained in the database, filtered and ordered
+    /// according to the filter conditions and sort criteria provided in the
+    /// request. The response may contain fewer than page_size of results.
+    ///
+    /// Filters are similar to the filters provided in the Notion UI where the
+    /// set of filters and filter groups chained by "And" in the UI is
+    /// equivalent to having each filter in the array of the compound "and"
+    /// filter. Similar a set of filters chained by "Or" in the UI would be
+    /// represented as filters in the array of the "or" compound filter.
+    ///
+    /// Sorts are similar to the sorts provided in the Notion UI. Sorts operate
+    /// on database properties or page timestamps and can be combined. The
+    /// order of the sorts in the request matter, with earlier sorts taking
+    /// precedence over later ones.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have read content capabilities.
+    /// Attempting to call this API without read content capabilities will
+    /// return an HTTP response with a 403 status code. For more information on
+    /// integration capabilities, see the capabilities guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the database doesn't exist, or if the
+    /// integration doesn't have access to the database.
+    ///
+    /// Returns a 400 HTTP response if the request body is invalid, such as a
+    /// filter on a property that doesn't exist.
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn query_database(
+        &self,
+        database_id: DatabaseId,
+        query: DatabaseQuery,
+    ) -> Result<List<Page>> {
+        let text = self
+            .api_post(&format!("databases/{database_id}/query"))
+            .json(&query)
+            .send_and_get_text()
+            .await?;
+
+        let res = self.parse::<result_types::List<Page>>(&text, "List<Page>", "query_database")?;
+
+        match res {
+            result_types::List::List(page_list) => Ok(page_list),
+            result_types::List::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// Queries a database, following `next_cursor` until every matching page
+    /// has been retrieved.
+    ///
+    /// The `start_cursor` of `query` is used for the first request only.
+    pub async fn query_database_all(
+        &self,
+        database_id: DatabaseId,
+        mut query: DatabaseQuery,
+    ) -> Result<Vec<Page>> {
+        let mut pages = Vec::new();
+
+        loop {
+            let list = self.query_database(database_id, query.clone()).await?;
+            pages.extend(list.results);
+
+            match list.next_cursor {
+                Some(cursor) if list.has_more => query.start_cursor = Some(cursor),
+                _ => return Ok(pages),
+            }
+        }
+    }
 }
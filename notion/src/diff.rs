@@ -0,0 +1,839 @@
+//! # Block tree diffing
+//!
+//! [`Notion::sync_block_children`] makes the children of a page or block match
+//! a list of blocks, with as few changes as it can find, so that the blocks
+//! that stay keep their IDs, comments and the links to them.
+//!
+//! [`diff`] plans the changes by aligning the current blocks with the desired
+//! ones, in order, like a text diff. A current block can be kept for a desired
+//! block of the same type, and is updated if its content differs, preferring
+//! blocks with similar text. Other current blocks are deleted, and other
+//! desired blocks are inserted in place. The children of kept blocks are
+//! diffed the same way.
+//!
+//! Content is compared without the fields that the API fills in, such as IDs,
+//! timestamps, `plain_text` and `href`, and values that are the same as
+//! leaving them out, such as `null`, `false` and the default color.
+//!
+//! The API can only insert blocks after another block, not at the start, so
+//! the first desired block is always matched with a current block, or else
+//! every current block is replaced.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use notion::{
+//!     client::Notion,
+//!     model::{ids::BlockId, markup, objects::block::Paragraph},
+//! };
+//!
+//! let notion = Notion::new("secret_token")?;
+//! let page_id = "b55c9c91-384d-452b-81db-d1ef79372b75".parse::<BlockId>()?;
+//!
+//! let status = Paragraph::new()
+//!     .rich_text(markup::parse("All systems **operational**")?)
+//!     .build();
+//!
+//! let plan = notion.sync_block_children(page_id, vec![status]).await?;
+//! println!("{} changes", plan.operations.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use futures::future::{BoxFuture, FutureExt};
+use notion_model::{
+    ids::BlockId,
+    objects::block::{Block, BlockData},
+};
+use serde_json::{Map, Value};
+
+use crate::{client::Notion, errors::Error, transport::HttpTransport};
+
+/// The most blocks that can be appended in one request.
+const APPEND_LIMIT: usize = 100;
+
+/// The most levels of children that can be appended in one request.
+const APPEND_DEPTH: usize = 2;
+
+/// A block and its children, which the API returns separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTree {
+    /// The block, without children in its data.
+    pub block: Block,
+    pub children: Vec<Self>,
+}
+
+impl BlockTree {
+    /// Moves the children nested in the data of a block into a tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the children can't be separated from the block.
+    pub fn from_block(block: Block) -> Result<Self> {
+        let mut data = serde_json::to_value(&block.data).context(Error::SerializeRequest)?;
+
+        let children = match type_object(&mut data).and_then(|fields| fields.remove("children")) {
+            Some(Value::Null) | None => Vec::new(),
+            Some(children) => {
+                serde_json::from_value::<Vec<Block>>(children).context(Error::SerializeRequest)?
+            },
+        };
+
+        Ok(Self {
+            block: Block {
+                data: serde_json::from_value(data).context(Error::SerializeRequest)?,
+                ..block
+            },
+            children: children
+                .into_iter()
+                .map(Self::from_block)
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// The block, with up to `depth` levels of children nested in its data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block's type can't have children.
+    pub fn to_block(&self, depth: usize) -> Result<Block> {
+        if depth == 0 || self.children.is_empty() {
+            return Ok(self.block.clone());
+        }
+
+        let children = self
+            .children
+            .iter()
+            .map(|child| child.to_block(depth - 1))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut data = serde_json::to_value(&self.block.data).context(Error::SerializeRequest)?;
+
+        type_object(&mut data)
+            .context(Error::SerializeRequest)?
+            .insert(
+                "children".to_owned(),
+                serde_json::to_value(children).context(Error::SerializeRequest)?,
+            );
+
+        Ok(Block {
+            data: serde_json::from_value(data).context(Error::SerializeRequest)?,
+            ..self.block.clone()
+        })
+    }
+}
+
+/// How many levels of children there are.
+fn depth(trees: &[BlockTree]) -> usize {
+    trees
+        .iter()
+        .map(|tree| 1 + depth(&tree.children))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The type-specific object in serialized block data.
+fn type_object(data: &mut Value) -> Option<&mut Map<String, Value>> {
+    data.as_object_mut()?
+        .values_mut()
+        .find_map(Value::as_object_mut)
+}
+
+/// One change to the blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// Updates the content of a block, which has its ID set and no children.
+    Update(Block),
+    /// Inserts blocks, with their children, after a child of the parent, or
+    /// at the end if `after` is `None`.
+    Append {
+        parent: BlockId,
+        after: Option<BlockId>,
+        blocks: Vec<Block>,
+    },
+    /// Deletes a block and its children.
+    Delete(BlockId),
+}
+
+/// The changes that make blocks match the desired blocks, in the order to
+/// make them in.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Plan {
+    pub operations: Vec<Operation>,
+}
+
+impl Plan {
+    /// Whether the blocks already match.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+/// Plans the changes that make the children of `parent` match `desired`.
+///
+/// # Errors
+///
+/// Returns an error if a current block doesn't have an ID, or a block can't
+/// be serialized.
+pub fn diff(parent: BlockId, current: &[BlockTree], desired: &[Block]) -> Result<Plan> {
+    let current = current
+        .iter()
+        .map(Node::from_tree)
+        .collect::<Result<Vec<_>>>()?;
+    let desired = desired
+        .iter()
+        .map(|block| Node::from_tree(&BlockTree::from_block(block.clone())?))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Plan {
+        operations: align(parent, &current, &desired)?.operations,
+    })
+}
+
+/// The cost of deleting a block, with its children.
+const DELETE: f64 = 1.0;
+
+/// A block, ready to be compared.
+struct Node {
+    block: Block,
+    type_name: String,
+    /// The type-specific object, without the values that don't matter.
+    content: Option<Value>,
+    /// The words in the content, sorted.
+    words: Vec<String>,
+    children: Vec<Self>,
+    /// The number of blocks in the tree.
+    size: usize,
+}
+
+impl Node {
+    fn from_tree(tree: &BlockTree) -> Result<Self> {
+        let children = tree
+            .children
+            .iter()
+            .map(Self::from_tree)
+            .collect::<Result<Vec<_>>>()?;
+
+        let data = serde_json::to_value(&tree.block.data).context(Error::SerializeRequest)?;
+
+        let type_name = match &tree.block.data {
+            BlockData::Unknown { type_name, .. } => type_name.clone(),
+            _ => data
+                .as_object()
+                .and_then(|data| data.keys().next())
+                .cloned()
+                .unwrap_or_default(),
+        };
+
+        let content = data.get(&type_name).and_then(normalize);
+
+        let mut words = Vec::new();
+        if let Some(content) = &content {
+            collect_words(content, &mut words);
+        }
+        words.sort_unstable();
+
+        Ok(Self {
+            block: tree.block.clone(),
+            type_name,
+            content,
+            words,
+            size: 1 + children.iter().map(|child| child.size).sum::<usize>(),
+            children,
+        })
+    }
+
+    fn tree(&self) -> BlockTree {
+        BlockTree {
+            block: self.block.clone(),
+            children: self.children.iter().map(Self::tree).collect(),
+        }
+    }
+
+    /// Whether the API can update the content of the block.
+    const fn updatable(&self) -> bool {
+        !matches!(
+            self.block.data,
+            BlockData::ChildDatabase(_)
+                | BlockData::ChildPage(_)
+                | BlockData::Unsupported(_)
+                | BlockData::Unknown { .. }
+        )
+    }
+}
+
+/// Removes the fields that the API fills in, and the values that are the same
+/// as leaving them out.
+fn normalize(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null | Value::Bool(false) => None,
+        Value::String(s) if s == "default" => None,
+        Value::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| normalize(value).unwrap_or(Value::Null))
+                .collect::<Vec<_>>();
+
+            (!values.is_empty()).then_some(Value::Array(values))
+        },
+        Value::Object(fields) => {
+            let fields = fields
+                .iter()
+                .filter(|(key, _)| !matches!(key.as_str(), "children" | "href" | "plain_text"))
+                .filter_map(|(key, value)| Some((key.clone(), normalize(value)?)))
+                .collect::<Map<_, _>>();
+
+            (!fields.is_empty()).then_some(Value::Object(fields))
+        },
+        value => Some(value.clone()),
+    }
+}
+
+fn collect_words(value: &Value, words: &mut Vec<String>) {
+    match value {
+        Value::String(s) => words.extend(s.split_whitespace().map(str::to_owned)),
+        Value::Array(values) => values.iter().for_each(|value| collect_words(value, words)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|value| collect_words(value, words)),
+        _ => {},
+    }
+}
+
+/// The share of words that two sorted lists of words have in common, from 0
+/// to 1.
+fn similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let (mut i, mut j, mut common) = (0, 0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            },
+        }
+    }
+
+    (2 * common) as f64 / (a.len() + b.len()) as f64
+}
+
+/// The operations for one level of blocks and the children of the blocks
+/// that are kept, with their cost.
+struct Alignment {
+    cost: f64,
+    operations: Vec<Operation>,
+}
+
+/// Keeping a current block for a desired block.
+struct Match {
+    cost: f64,
+    update: bool,
+    children: Alignment,
+}
+
+#[derive(Clone, Copy)]
+enum Step {
+    Delete,
+    Insert,
+    Match,
+}
+
+/// Whether a block has been kept yet, as blocks can only be inserted before
+/// the first kept block if no block is kept.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Kept,
+    Inserted,
+}
+
+impl State {
+    const ALL: [Self; 3] = [Self::Start, Self::Kept, Self::Inserted];
+}
+
+fn align(parent: BlockId, current: &[Node], desired: &[Node]) -> Result<Alignment> {
+    let (n, m) = (current.len(), desired.len());
+
+    let mut matches = Vec::with_capacity(n * m);
+
+    for c in current {
+        for d in desired {
+            matches.push(match_blocks(c, d)?);
+        }
+    }
+
+    // costs[i][j][state] is the cheapest way to align current[..i] with
+    // desired[..j], with the step that got there.
+    let index = |i: usize, j: usize, state: State| (i * (m + 1) + j) * 3 + state as usize;
+    let mut costs = vec![(f64::INFINITY, None::<(Step, State)>); (n + 1) * (m + 1) * 3];
+    costs[index(0, 0, State::Start)].0 = 0.0;
+
+    for i in 0..=n {
+        for j in 0..=m {
+            for state in State::ALL {
+                let cost = costs[index(i, j, state)].0;
+
+                if cost.is_infinite() {
+                    continue;
+                }
+
+                let mut relax = |i: usize, j: usize, next: State, step_cost: f64, step: Step| {
+                    let entry = &mut costs[index(i, j, next)];
+
+                    if cost + step_cost < entry.0 {
+                        *entry = (cost + step_cost, Some((step, state)));
+                    }
+                };
+
+                if i < n {
+                    relax(i + 1, j, state, DELETE, Step::Delete);
+                }
+
+                if j < m {
+                    let next = match state {
+                        State::Start => State::Inserted,
+                        state => state,
+                    };
+
+                    relax(i, j + 1, next, desired[j].size as f64, Step::Insert);
+                }
+
+                if i < n && j < m && state != State::Inserted {
+                    if let Some(matched) = &matches[i * m + j] {
+                        relax(i + 1, j + 1, State::Kept, matched.cost, Step::Match);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut state = State::ALL
+        .into_iter()
+        .min_by(|a, b| {
+            costs[index(n, m, *a)]
+                .0
+                .total_cmp(&costs[index(n, m, *b)].0)
+        })
+        .unwrap_or(State::Start);
+    let cost = costs[index(n, m, state)].0;
+
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (n, m);
+
+    while let Some((step, previous)) = costs[index(i, j, state)].1 {
+        match step {
+            Step::Delete => i -= 1,
+            Step::Insert => j -= 1,
+            Step::Match => {
+                i -= 1;
+                j -= 1;
+            },
+        }
+
+        steps.push((step, i, j));
+        state = previous;
+    }
+
+    steps.reverse();
+
+    let mut operations = Vec::new();
+    let mut after = None;
+    let mut inserted = Vec::new();
+
+    let flush = |operations: &mut Vec<Operation>, after, inserted: &mut Vec<Block>| {
+        if !inserted.is_empty() {
+            operations.push(Operation::Append {
+                parent,
+                after,
+                blocks: std::mem::take(inserted),
+            });
+        }
+    };
+
+    for (step, i, j) in steps {
+        match step {
+            Step::Delete => operations.push(Operation::Delete(id(&current[i])?)),
+            Step::Insert => inserted.push(desired[j].tree().to_block(usize::MAX)?),
+            Step::Match => {
+                let id = id(&current[i])?;
+                flush(&mut operations, after, &mut inserted);
+
+                if let Some(matched) = matches[i * m + j].take() {
+                    if matched.update {
+                        operations.push(Operation::Update(
+                            Block::new(desired[j].block.data.clone()).id(id),
+                        ));
+                    }
+
+                    operations.extend(matched.children.operations);
+                }
+
+                after = Some(id);
+            },
+        }
+    }
+
+    flush(&mut operations, after, &mut inserted);
+
+    Ok(Alignment { cost, operations })
+}
+
+fn id(node: &Node) -> Result<BlockId> {
+    node.block.id.context(Error::MissingBlockId)
+}
+
+/// The cost of keeping a current block for a desired block, if it can be.
+fn match_blocks(current: &Node, desired: &Node) -> Result<Option<Match>> {
+    if current.type_name != desired.type_name {
+        return Ok(None);
+    }
+
+    let update = current.content != desired.content;
+
+    if update && !current.updatable() {
+        return Ok(None);
+    }
+
+    let children = align(id(current)?, &current.children, &desired.children)?;
+
+    let cost = match update {
+        true => 2.0 - similarity(&current.words, &desired.words),
+        false => 0.0,
+    };
+
+    Ok(Some(Match {
+        cost: cost + children.cost,
+        update,
+        children,
+    }))
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Retrieve a block tree
+    ///
+    /// Retrieves the children of a block, and their children, all the way
+    /// down. The content of child pages and databases isn't retrieved.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Notion::retrieve_block_children`].
+    pub async fn retrieve_block_tree(&self, block_id: BlockId) -> Result<Vec<BlockTree>> {
+        self.block_tree(block_id).await
+    }
+
+    fn block_tree(&self, block_id: BlockId) -> BoxFuture<'_, Result<Vec<BlockTree>>> {
+        async move {
+            let mut trees = Vec::new();
+
+            for block in self.retrieve_block_children_all(block_id).await? {
+                let children = match (&block.data, block.id, block.has_children) {
+                    (BlockData::ChildPage(_) | BlockData::ChildDatabase(_), ..) => Vec::new(),
+                    (_, Some(id), Some(true)) => self.block_tree(id).await?,
+                    _ => Vec::new(),
+                };
+
+                trees.push(BlockTree { block, children });
+            }
+
+            Ok(trees)
+        }
+        .boxed()
+    }
+
+    /// # Sync block children
+    ///
+    /// Makes the children of a page or block match `desired`, with as few
+    /// changes as it can find, and returns the changes that were made. See
+    /// [`diff`](crate::diff) for how blocks are matched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any request fails. The changes before it are kept.
+    pub async fn sync_block_children(
+        &self,
+        block_id: BlockId,
+        desired: Vec<Block>,
+    ) -> Result<Plan> {
+        let current = self.retrieve_block_tree(block_id).await?;
+        let plan = diff(block_id, &current, &desired)?;
+
+        self.apply_plan(&plan).await?;
+
+        Ok(plan)
+    }
+
+    /// # Apply a plan
+    ///
+    /// Makes the changes of a plan from [`diff`], in order. Blocks with more
+    /// levels of children than can be appended at once are appended in
+    /// several requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any request fails. The changes before it are kept.
+    pub async fn apply_plan(&self, plan: &Plan) -> Result<()> {
+        for operation in &plan.operations {
+            match operation {
+                Operation::Update(block) => {
+                    self.update_block(block.clone()).await?;
+                },
+                Operation::Append {
+                    parent,
+                    after,
+                    blocks,
+                } => {
+                    let trees = blocks
+                        .iter()
+                        .map(|block| BlockTree::from_block(block.clone()))
+                        .collect::<Result<Vec<_>>>()?;
+
+                    self.append_trees(*parent, *after, &trees).await?;
+                },
+                Operation::Delete(block_id) => {
+                    self.delete_block(*block_id).await?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends blocks with any number of levels of children.
+    fn append_trees<'a>(
+        &'a self,
+        parent: BlockId,
+        mut after: Option<BlockId>,
+        trees: &'a [BlockTree],
+    ) -> BoxFuture<'a, Result<()>> {
+        async move {
+            for chunk in trees.chunks(APPEND_LIMIT) {
+                let blocks = chunk
+                    .iter()
+                    .map(|tree| tree.to_block(APPEND_DEPTH))
+                    .collect::<Result<Vec<_>>>()?;
+
+                let created = match after {
+                    Some(after) => {
+                        self.append_block_children_after(parent, after, blocks)
+                            .await?
+                    },
+                    None => self.append_block_children(parent, blocks).await?,
+                }
+                .results;
+
+                for (block, tree) in created.iter().zip(chunk) {
+                    let id = block.id.context(Error::MissingBlockId)?;
+                    self.append_deeper(id, &tree.children, APPEND_DEPTH).await?;
+                    after = Some(id);
+                }
+            }
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    /// Appends the children of a new block that weren't appended with it,
+    /// when `appended` levels of them were.
+    fn append_deeper<'a>(
+        &'a self,
+        block_id: BlockId,
+        children: &'a [BlockTree],
+        appended: usize,
+    ) -> BoxFuture<'a, Result<()>> {
+        async move {
+            if depth(children) <= appended {
+                return Ok(());
+            }
+
+            if appended == 0 {
+                return self.append_trees(block_id, None, children).await;
+            }
+
+            let created = self.retrieve_block_children_all(block_id).await?;
+
+            for (block, tree) in created.iter().zip(children) {
+                let id = block.id.context(Error::MissingBlockId)?;
+                self.append_deeper(id, &tree.children, appended - 1).await?;
+            }
+
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notion_model::objects::{
+        block::{Divider, Heading1, Paragraph, Toggle},
+        rich_text::RichText,
+    };
+
+    use super::*;
+
+    fn id(n: u8) -> BlockId {
+        BlockId::from_str_unchecked(&format!("00000000-0000-4000-8000-0000000000{n:02}"))
+    }
+
+    fn paragraph(text: &str) -> Block {
+        Paragraph::new()
+            .rich_text(vec![RichText::new_text(text)])
+            .build()
+    }
+
+    fn heading(text: &str) -> Block {
+        Heading1::new()
+            .rich_text(vec![RichText::new_text(text)])
+            .build()
+    }
+
+    /// A block as the API returns it.
+    fn current(n: u8, block: Block) -> BlockTree {
+        let mut tree = BlockTree::from_block(block).unwrap();
+        tree.block.id = Some(id(n));
+        tree.block.has_children = Some(!tree.children.is_empty());
+        tree
+    }
+
+    #[test]
+    fn unchanged() {
+        let current = [
+            current(1, heading("Status")),
+            current(2, paragraph("All good")),
+        ];
+        let desired = [heading("Status"), paragraph("All good")];
+
+        assert!(diff(id(0), &current, &desired).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ignores_server_fields() {
+        let mut block = paragraph("All good");
+        if let BlockData::Paragraph(paragraph) = &mut block.data {
+            paragraph.rich_text[0].plain_text(Some("All good".to_owned()));
+            paragraph.rich_text[0].annotations = Some(Default::default());
+        }
+
+        let mut current = current(1, block);
+        current.block.created_time = Some("2024-05-01".parse().unwrap());
+        current.block.archived = Some(false);
+
+        assert!(diff(id(0), &[current], &[paragraph("All good")])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn update_insert_delete() {
+        let current = [
+            current(1, heading("Status")),
+            current(2, paragraph("API is down")),
+            current(3, Divider::new().build()),
+            current(4, paragraph("Old footer")),
+        ];
+        let desired = [
+            heading("Status"),
+            paragraph("API is up"),
+            paragraph("Since 10:00"),
+            Divider::new().build(),
+        ];
+
+        assert_eq!(
+            diff(id(0), &current, &desired).unwrap().operations,
+            [
+                Operation::Update(paragraph("API is up").id(id(2))),
+                Operation::Append {
+                    parent: id(0),
+                    after: Some(id(2)),
+                    blocks: vec![paragraph("Since 10:00")],
+                },
+                Operation::Delete(id(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn similar_blocks_are_kept() {
+        let current = [
+            current(1, paragraph("Database latency is normal")),
+            current(2, paragraph("Queue depth is normal")),
+        ];
+        let desired = [paragraph("New incident"), paragraph("Queue depth is high")];
+
+        // the first paragraph can't be inserted before the first current
+        // block, so it's kept and updated, and the second is the closest match
+        assert_eq!(
+            diff(id(0), &current, &desired).unwrap().operations,
+            [
+                Operation::Update(paragraph("New incident").id(id(1))),
+                Operation::Update(paragraph("Queue depth is high").id(id(2))),
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_at_start_replaces() {
+        let current = [current(1, paragraph("Body"))];
+        let desired = [heading("Title"), paragraph("Body")];
+
+        assert_eq!(
+            diff(id(0), &current, &desired).unwrap().operations,
+            [
+                Operation::Delete(id(1)),
+                Operation::Append {
+                    parent: id(0),
+                    after: None,
+                    blocks: desired.to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn children() {
+        let toggle = |children: Vec<Block>| {
+            Toggle::new()
+                .rich_text(vec![RichText::new_text("Details")])
+                .children(Some(children))
+                .build()
+        };
+
+        let mut current = current(1, toggle(vec![paragraph("a")]));
+        current.children[0].block.id = Some(id(2));
+
+        let desired = [toggle(vec![paragraph("a"), paragraph("b")])];
+
+        assert_eq!(
+            diff(id(0), &[current], &desired).unwrap().operations,
+            [Operation::Append {
+                parent: id(1),
+                after: Some(id(2)),
+                blocks: vec![paragraph("b")],
+            }]
+        );
+    }
+
+    #[test]
+    fn tree_round_trip() {
+        let block = Toggle::new()
+            .children(Some(vec![Toggle::new()
+                .children(Some(vec![paragraph("deep")]))
+                .build()]))
+            .build();
+
+        let tree = BlockTree::from_block(block.clone()).unwrap();
+        assert_eq!(depth(&tree.children), 2);
+        assert_eq!(tree.to_block(usize::MAX).unwrap(), block);
+
+        let shallow = tree.to_block(1).unwrap();
+        assert_eq!(depth(&BlockTree::from_block(shallow).unwrap().children), 1);
+    }
+}
@@ -0,0 +1,217 @@
+//! # Schema drift
+//!
+//! The API adds fields to its objects over time, and the model silently drops
+//! the fields it doesn't know about. With [`Notion::drift`], the client
+//! compares every response to what the model captured of it, and reports the
+//! JSON paths of the fields that were dropped, such as
+//! `$.results[0].paragraph.new_field`.
+//!
+//! This re-serializes every response, so it's meant for tests, canaries and
+//! audits rather than for production readers.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! use notion::{client::Notion, drift::DriftMode};
+//!
+//! let notion = Notion::new("secret_token")?.drift(DriftMode::report(|drift| {
+//!     eprintln!("{drift}");
+//! }));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Notion::drift`]: crate::client::Notion::drift
+
+use std::{fmt, sync::Arc};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::errors::Error;
+
+/// What to do with responses that have fields the model doesn't capture.
+#[derive(Clone)]
+pub enum DriftMode {
+    /// Calls a function with the drift of every response that has any.
+    Report(Arc<dyn Fn(&Drift) + Send + Sync>),
+    /// Fails to parse responses that have any drift, with a [`Drift`] error.
+    Deny,
+}
+
+impl DriftMode {
+    pub fn report(report: impl Fn(&Drift) + Send + Sync + 'static) -> Self {
+        Self::Report(Arc::new(report))
+    }
+}
+
+impl fmt::Debug for DriftMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Report(_) => f.write_str("Report(..)"),
+            Self::Deny => f.write_str("Deny"),
+        }
+    }
+}
+
+/// The fields of a response that the model doesn't capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// The endpoint that returned the response, such as `retrieve_block`.
+    pub endpoint: &'static str,
+    /// The JSON paths of the fields.
+    pub paths: Vec<String>,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the {} response has fields that the model doesn't capture: {}",
+            self.endpoint,
+            self.paths.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for Drift {}
+
+/// Finds the fields of `raw` that aren't in `parsed`, the re-serialized
+/// model.
+///
+/// Fields that are `null` are not counted, since the model leaves out most
+/// fields that are `None`. Neither are `type` fields that name a field that
+/// is captured, since the model tags most types by that field alone.
+pub fn uncaptured(raw: &Value, parsed: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    walk(raw, parsed, &mut "$".to_owned(), &mut paths);
+    paths
+}
+
+fn walk(raw: &Value, parsed: &Value, path: &mut String, paths: &mut Vec<String>) {
+    match (raw, parsed) {
+        (Value::Object(raw), Value::Object(parsed)) => {
+            for (key, value) in raw {
+                let len = path.len();
+                push_key(path, key);
+
+                match parsed.get(key) {
+                    Some(parsed) => walk(value, parsed, path, paths),
+                    None if value.is_null() || is_type_tag(key, value, parsed) => {},
+                    None => paths.push(path.clone()),
+                }
+
+                path.truncate(len);
+            }
+        },
+        (Value::Array(raw), Value::Array(parsed)) => {
+            for (i, (raw, parsed)) in raw.iter().zip(parsed).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{i}]"));
+                walk(raw, parsed, path, paths);
+                path.truncate(len);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn is_type_tag(key: &str, value: &Value, parsed: &Map<String, Value>) -> bool {
+    key == "type"
+        && value
+            .as_str()
+            .map_or(false, |name| parsed.contains_key(name))
+}
+
+fn push_key(path: &mut String, key: &str) {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        path.push('.');
+        path.push_str(key);
+    } else {
+        path.push('[');
+        path.push_str(&Value::from(key).to_string());
+        path.push(']');
+    }
+}
+
+/// Checks a parsed response for drift. Error responses aren't checked.
+pub(crate) fn check<R: Serialize>(
+    mode: &DriftMode,
+    endpoint: &'static str,
+    text: &str,
+    value: &R,
+) -> Result<()> {
+    let raw = serde_json::from_str::<Value>(text).context(Error::ParseResponse)?;
+
+    if raw["object"] == "error" {
+        return Ok(());
+    }
+
+    let parsed = serde_json::to_value(value).context(Error::ParseResponse)?;
+    let paths = uncaptured(&raw, &parsed);
+
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let drift = Drift { endpoint, paths };
+
+    match mode {
+        DriftMode::Report(report) => {
+            report(&drift);
+            Ok(())
+        },
+        DriftMode::Deny => Err(drift.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn nothing_dropped() {
+        let raw = json!({ "a": 1, "b": { "c": [1, 2] } });
+
+        assert!(uncaptured(&raw, &raw).is_empty());
+    }
+
+    #[test]
+    fn dropped() {
+        let raw = json!({
+            "a": 1,
+            "new": true,
+            "b": { "c": [{ "d": 1 }, { "d": 2, "e": 3 }] },
+            "Due date": { "f": 4 },
+        });
+        let parsed = json!({
+            "a": 1,
+            "b": { "c": [{ "d": 1 }, { "d": 2 }] },
+            "Due date": {},
+        });
+
+        assert_eq!(
+            uncaptured(&raw, &parsed),
+            [r#"$["Due date"].f"#, "$.b.c[1].e", "$.new"]
+        );
+    }
+
+    #[test]
+    fn nulls_and_type_tags() {
+        let raw = json!({
+            "type": "paragraph",
+            "paragraph": { "color": "default" },
+            "icon": null,
+        });
+        let parsed = json!({ "paragraph": { "color": "default" } });
+
+        assert!(uncaptured(&raw, &parsed).is_empty());
+
+        let raw = json!({ "type": "new_block", "paragraph": {} });
+        let parsed = json!({ "paragraph": {} });
+
+        assert_eq!(uncaptured(&raw, &parsed), ["$.type"]);
+    }
+}
@@ -0,0 +1,669 @@
+//! # Duplicating pages
+//!
+//! [`Notion::duplicate_page`] copies a page, its content and the pages nested
+//! in it, as the API has no endpoint to duplicate a page. The copy is made in
+//! three steps:
+//!
+//! 1. The page is read along with its content, recursively through child pages,
+//!    and child databases and their rows if
+//!    [`DuplicateOptions::child_databases`] is set.
+//! 2. The copies are created in the same order, so that child pages and
+//!    databases keep their place among the other blocks. What can't be set
+//!    through the API is left out, as with [templates](crate::templates):
+//!    properties that Notion computes, files hosted by Notion, and unsupported
+//!    blocks. Status properties of databases can't be created, so they are
+//!    copied as select properties with the same options.
+//! 3. Mentions of and links to the duplicated pages and databases, in the
+//!    content and properties of the copies, are rewritten to point at the
+//!    copies.
+//!
+//! The API can only create pages at the top level of a page, so child pages
+//! nested in other blocks, such as toggles or columns, are copied after the
+//! block they are in. Relations keep pointing at the original pages, as their
+//! database may not have been duplicated. For the same reason, the relation
+//! properties of a duplicated database still relate to the original databases,
+//! even when those were duplicated too, a relation of a database to itself
+//! included.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use notion::{client::Notion, duplicate::DuplicateOptions, model::ids::PageId};
+//!
+//! let notion = Notion::new("secret_token")?;
+//! let template_id = "b55c9c91-384d-452b-81db-d1ef79372b75".parse::<PageId>()?;
+//! let parent_id = "2e3a8d4b-1f6c-4a1e-9d3e-5b7c8a9f0e1d".parse::<PageId>()?;
+//!
+//! let duplicate = notion
+//!     .duplicate_page(
+//!         template_id,
+//!         parent_id.into(),
+//!         DuplicateOptions::new().child_databases(true),
+//!     )
+//!     .await?;
+//!
+//! println!("copied {} pages", duplicate.pages.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::BitOr,
+};
+
+use anyhow::{Context, Result};
+use futures::future::{BoxFuture, FutureExt};
+use notion_model::{
+    ids::{BlockId, DatabaseId, PageId},
+    objects::{
+        block::{Block, BlockData},
+        database::Database,
+        database_property::{DatabaseProperty, DatabasePropertyData, SelectConfig},
+        page::Page,
+        parent::ParentData,
+        properties::{Property, PropertyData, Select, SelectOption, Status},
+    },
+    query::{DatabaseQuery, Sort, SortDirection, SortTimestamp},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    client::Notion,
+    diff::{BlockTree, Operation, Plan},
+    errors::Error,
+    templates::{copyable_cover, copyable_icon, template_block, writable, Template},
+    transport::HttpTransport,
+};
+
+/// How to duplicate a page.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateOptions {
+    /// Whether child databases are duplicated along with their rows.
+    /// Otherwise they are left out of the copy. Their relation properties
+    /// keep relating to the original databases.
+    pub child_databases: bool,
+}
+
+impl DuplicateOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub const fn child_databases(mut self, child_databases: bool) -> Self {
+        self.child_databases = child_databases;
+        self
+    }
+}
+
+/// The copies made by [`Notion::duplicate_page`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    /// The copy of the page.
+    pub page_id: PageId,
+    /// The copy of every page that was duplicated, by original ID, including
+    /// the page itself and the rows of duplicated databases.
+    pub pages: BTreeMap<PageId, PageId>,
+    /// The copy of every database that was duplicated, by original ID.
+    pub databases: BTreeMap<DatabaseId, DatabaseId>,
+}
+
+/// A page to duplicate, and its content.
+#[derive(Debug)]
+struct SourcePage {
+    id: PageId,
+    page: Page,
+    content: Vec<Content>,
+}
+
+/// A part of the content of a page, in order.
+#[derive(Debug)]
+enum Content {
+    Blocks(Vec<BlockTree>),
+    ChildPage(SourcePage),
+    ChildDatabase(SourceDatabase),
+}
+
+#[derive(Debug)]
+struct SourceDatabase {
+    id: DatabaseId,
+    database: Database,
+    rows: Vec<SourcePage>,
+}
+
+/// The IDs of originals and their copies, in both the hyphenated and simple
+/// forms, as links use the simple form.
+struct Replacements(Vec<(String, String)>);
+
+impl Replacements {
+    fn new(duplicate: &Duplicate) -> Self {
+        let pages = duplicate
+            .pages
+            .iter()
+            .map(|(old, new)| (*old.into_inner(), *new.into_inner()));
+        let databases = duplicate
+            .databases
+            .iter()
+            .map(|(old, new)| (*old.into_inner(), *new.into_inner()));
+
+        Self(
+            pages
+                .chain(databases)
+                .flat_map(|(old, new): (Uuid, Uuid)| {
+                    [
+                        (old.hyphenated().to_string(), new.hyphenated().to_string()),
+                        (old.simple().to_string(), new.simple().to_string()),
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    /// Replaces the IDs of originals in every string of a value, returning
+    /// whether any was replaced.
+    fn rewrite(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(s) => {
+                let mut changed = false;
+
+                for (old, new) in &self.0 {
+                    if s.contains(old.as_str()) {
+                        *s = s.replace(old.as_str(), new);
+                        changed = true;
+                    }
+                }
+
+                changed
+            },
+            Value::Array(values) => values
+                .iter_mut()
+                .map(|value| self.rewrite(value))
+                .fold(false, BitOr::bitor),
+            Value::Object(map) => map
+                .values_mut()
+                .map(|value| self.rewrite(value))
+                .fold(false, BitOr::bitor),
+            _ => false,
+        }
+    }
+
+    /// The value with the IDs of originals replaced, if any was.
+    fn rewritten<T: Serialize + DeserializeOwned>(&self, value: &T) -> Result<Option<T>> {
+        let mut value = serde_json::to_value(value).context(Error::SerializeRequest)?;
+
+        if !self.rewrite(&mut value) {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            serde_json::from_value(value).context(Error::SerializeRequest)?,
+        ))
+    }
+}
+
+/// Whether a property is rewritten. Relations are copied, but keep pointing at
+/// the original pages.
+const fn rewritable(property: &PropertyData) -> bool {
+    writable(property) && !matches!(property, PropertyData::Relation(_))
+}
+
+/// The property to create in the copy of a database, if it can be created.
+/// Status properties can't be, so they are copied as select properties with
+/// the same options.
+fn creatable(data: &DatabasePropertyData) -> Option<DatabasePropertyData> {
+    match data {
+        DatabasePropertyData::Status(config) => Some(DatabasePropertyData::Select(SelectConfig {
+            options: config
+                .options
+                .iter()
+                .map(|option| SelectOption {
+                    id: None,
+                    ..option.clone()
+                })
+                .collect(),
+        })),
+        DatabasePropertyData::Button(_)
+        | DatabasePropertyData::Verification(_)
+        | DatabasePropertyData::Unknown { .. } => None,
+        data => Some(data.clone()),
+    }
+}
+
+/// Turns the values of status properties into the values of the select
+/// properties they are copied as, see [`creatable`].
+fn status_as_select(properties: &mut HashMap<String, Property>) {
+    for property in properties.values_mut() {
+        if let PropertyData::Status(Status(status)) = &property.data {
+            let option = status.as_ref().map(|status| SelectOption {
+                color: status.color,
+                id: None,
+                name: status.name.clone(),
+            });
+
+            *property = Property::new(PropertyData::Select(Select(option)));
+        }
+    }
+}
+
+/// Whether blocks mention or link to any of the originals.
+fn references(trees: &[BlockTree], replacements: &Replacements) -> Result<bool> {
+    for tree in trees {
+        if replacements.rewritten(&tree.block.data)?.is_some()
+            || references(&tree.children, replacements)?
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// The child pages and databases nested in blocks, in order.
+fn nested_children(trees: &[BlockTree], children: &mut Vec<Block>) {
+    for tree in trees {
+        match tree.block.data {
+            BlockData::ChildPage(_) | BlockData::ChildDatabase(_) => {
+                children.push(tree.block.clone());
+            },
+            _ => nested_children(&tree.children, children),
+        }
+    }
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Duplicate a page
+    ///
+    /// Copies a page under `parent`, with its properties, content, child pages
+    /// and optionally child databases, and returns the IDs of the copies. See
+    /// [`crate::duplicate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any request fails. The copies made before it are
+    /// kept.
+    pub async fn duplicate_page(
+        &self,
+        page_id: PageId,
+        parent: ParentData,
+        options: DuplicateOptions,
+    ) -> Result<Duplicate> {
+        let source = self.read_page(page_id, options).await?;
+
+        let mut duplicate = Duplicate {
+            page_id,
+            pages: BTreeMap::new(),
+            databases: BTreeMap::new(),
+        };
+
+        duplicate.page_id = self.copy_page(&source, parent, &mut duplicate).await?;
+
+        let replacements = Replacements::new(&duplicate);
+        self.rewrite_page(&source, &duplicate, &replacements)
+            .await?;
+
+        Ok(duplicate)
+    }
+
+    /// Reads a page and its content, recursively.
+    fn read_page(
+        &self,
+        page_id: PageId,
+        options: DuplicateOptions,
+    ) -> BoxFuture<'_, Result<SourcePage>> {
+        async move {
+            let page = self.retrieve_page_untruncated(page_id, None).await?;
+            let trees = self
+                .retrieve_block_tree(BlockId::from(*page_id.into_inner()))
+                .await?;
+
+            let mut content = Vec::new();
+            let mut blocks = Vec::new();
+
+            for tree in trees {
+                let mut children = Vec::new();
+
+                match tree.block.data {
+                    BlockData::ChildPage(_) | BlockData::ChildDatabase(_) => {
+                        children.push(tree.block);
+                    },
+                    _ => {
+                        nested_children(&tree.children, &mut children);
+                        blocks.push(tree);
+                    },
+                }
+
+                if children.is_empty() {
+                    continue;
+                }
+
+                if !blocks.is_empty() {
+                    content.push(Content::Blocks(std::mem::take(&mut blocks)));
+                }
+
+                for child in children {
+                    let id = *child.id.context(Error::MissingBlockId)?.into_inner();
+
+                    match child.data {
+                        BlockData::ChildPage(_) => {
+                            let page = self.read_page(PageId::from(id), options).await?;
+                            content.push(Content::ChildPage(page));
+                        },
+                        BlockData::ChildDatabase(_) if options.child_databases => {
+                            let database =
+                                self.read_database(DatabaseId::from(id), options).await?;
+                            content.push(Content::ChildDatabase(database));
+                        },
+                        _ => {},
+                    }
+                }
+            }
+
+            if !blocks.is_empty() {
+                content.push(Content::Blocks(blocks));
+            }
+
+            Ok(SourcePage {
+                id: page_id,
+                page,
+                content,
+            })
+        }
+        .boxed()
+    }
+
+    async fn read_database(
+        &self,
+        database_id: DatabaseId,
+        options: DuplicateOptions,
+    ) -> Result<SourceDatabase> {
+        let database = self.retrieve_database(database_id).await?;
+
+        let query = DatabaseQuery::new().sorts(vec![Sort::Timestamp {
+            timestamp: SortTimestamp::CreatedTime,
+            direction: SortDirection::Ascending,
+        }]);
+
+        let mut rows = Vec::new();
+
+        for row in self.query_database_all(database_id, query).await? {
+            let row_id = row.id.context(Error::MissingPageId)?;
+            rows.push(self.read_page(row_id, options).await?);
+        }
+
+        Ok(SourceDatabase {
+            id: database_id,
+            database,
+            rows,
+        })
+    }
+
+    /// Creates the copy of a page and its content, returning its ID.
+    fn copy_page<'a>(
+        &'a self,
+        source: &'a SourcePage,
+        parent: ParentData,
+        duplicate: &'a mut Duplicate,
+    ) -> BoxFuture<'a, Result<PageId>> {
+        async move {
+            let template = Template::from_page(source.page.clone(), &[])?;
+            let mut properties = template.properties;
+
+            // rows of duplicated databases, whose status properties are
+            // selects in the copy
+            if let Some(ParentData::DatabaseId { database_id }) = source.page.parent {
+                if duplicate.databases.contains_key(&database_id) {
+                    status_as_select(&mut properties);
+                }
+            }
+
+            let page = self
+                .create_page(
+                    Page::new()
+                        .parent(Some(parent))
+                        .properties(properties)
+                        .icon(template.icon)
+                        .cover(template.cover),
+                )
+                .await?;
+
+            let page_id = page.id.context(Error::MissingPageId)?;
+            duplicate.pages.insert(source.id, page_id);
+
+            for content in &source.content {
+                match content {
+                    Content::Blocks(trees) => {
+                        let blocks = trees
+                            .iter()
+                            .filter_map(template_block)
+                            .map(|tree| tree.to_block(usize::MAX))
+                            .collect::<Result<Vec<_>>>()?;
+
+                        if blocks.is_empty() {
+                            continue;
+                        }
+
+                        self.apply_plan(&Plan {
+                            operations: vec![Operation::Append {
+                                parent: BlockId::from(*page_id.into_inner()),
+                                after: None,
+                                blocks,
+                            }],
+                        })
+                        .await?;
+                    },
+                    Content::ChildPage(child) => {
+                        self.copy_page(child, ParentData::PageId { page_id }, duplicate)
+                            .await?;
+                    },
+                    Content::ChildDatabase(database) => {
+                        self.copy_database(database, page_id, duplicate).await?;
+                    },
+                }
+            }
+
+            Ok(page_id)
+        }
+        .boxed()
+    }
+
+    async fn copy_database(
+        &self,
+        source: &SourceDatabase,
+        page_id: PageId,
+        duplicate: &mut Duplicate,
+    ) -> Result<()> {
+        let properties = source
+            .database
+            .properties
+            .iter()
+            .filter_map(|(name, property)| {
+                let property = DatabaseProperty {
+                    id: None,
+                    name: None,
+                    description: property.description.clone(),
+                    data: creatable(&property.data)?,
+                };
+
+                Some((name.clone(), property))
+            })
+            .collect();
+
+        let database = Database {
+            is_inline: source.database.is_inline,
+            ..Database::new()
+                .parent(Some(ParentData::PageId { page_id }))
+                .title(source.database.title.clone())
+                .description(source.database.description.clone())
+                .icon(copyable_icon(source.database.icon.clone()))
+                .cover(copyable_cover(source.database.cover.clone()))
+                .properties(properties)
+        };
+
+        let database = self.create_database(database).await?;
+        let database_id = database.id.context(Error::MissingDatabaseId)?;
+        duplicate.databases.insert(source.id, database_id);
+
+        for row in &source.rows {
+            self.copy_page(row, ParentData::DatabaseId { database_id }, duplicate)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the mentions and links in the copy of a page and the pages
+    /// in it.
+    fn rewrite_page<'a>(
+        &'a self,
+        source: &'a SourcePage,
+        duplicate: &'a Duplicate,
+        replacements: &'a Replacements,
+    ) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let copy_id = duplicate.pages[&source.id];
+
+            let mut properties = HashMap::new();
+
+            for (name, property) in &source.page.properties {
+                if !rewritable(&property.data) {
+                    continue;
+                }
+
+                if let Some(data) = replacements.rewritten(&property.data)? {
+                    properties.insert(name.clone(), Property::new(data));
+                }
+            }
+
+            if !properties.is_empty() {
+                self.update_page(Page::new().id(Some(copy_id)).properties(properties))
+                    .await?;
+            }
+
+            let mut blocks = false;
+
+            for content in &source.content {
+                match content {
+                    Content::Blocks(trees) => {
+                        blocks |= references(trees, replacements)?;
+                    },
+                    Content::ChildPage(child) => {
+                        self.rewrite_page(child, duplicate, replacements).await?;
+                    },
+                    Content::ChildDatabase(database) => {
+                        for row in &database.rows {
+                            self.rewrite_page(row, duplicate, replacements).await?;
+                        }
+                    },
+                }
+            }
+
+            if blocks {
+                let trees = self
+                    .retrieve_block_tree(BlockId::from(*copy_id.into_inner()))
+                    .await?;
+
+                self.rewrite_blocks(&trees, replacements).await?;
+            }
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn rewrite_blocks<'a>(
+        &'a self,
+        trees: &'a [BlockTree],
+        replacements: &'a Replacements,
+    ) -> BoxFuture<'a, Result<()>> {
+        async move {
+            for tree in trees {
+                if matches!(
+                    tree.block.data,
+                    BlockData::ChildPage(_) | BlockData::ChildDatabase(_)
+                ) {
+                    continue;
+                }
+
+                if let Some(data) = replacements.rewritten(&tree.block.data)? {
+                    self.update_block(Block {
+                        id: tree.block.id,
+                        ..Block::new(data)
+                    })
+                    .await?;
+                }
+
+                self.rewrite_blocks(&tree.children, replacements).await?;
+            }
+
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const OLD: &str = "8e5d0a3c-3f40-4cbf-bc0e-0a2b1e5f3f3a";
+    const NEW: &str = "4f1c2b7e-1c2d-4e5f-8a9b-0c1d2e3f4a5b";
+
+    fn replacements() -> Replacements {
+        Replacements::new(&Duplicate {
+            page_id: NEW.parse().unwrap(),
+            pages: BTreeMap::from([(OLD.parse().unwrap(), NEW.parse().unwrap())]),
+            databases: BTreeMap::new(),
+        })
+    }
+
+    #[test]
+    fn mentions_and_links() {
+        let mut value = json!({
+            "rich_text": [
+                { "type": "mention", "mention": { "type": "page", "page": { "id": OLD } } },
+                {
+                    "type": "text",
+                    "text": {
+                        "content": "plan",
+                        "link": { "url": "/8e5d0a3c3f404cbfbc0e0a2b1e5f3f3a#3c1f" },
+                    },
+                },
+            ],
+        });
+
+        assert!(replacements().rewrite(&mut value));
+        assert_eq!(value["rich_text"][0]["mention"]["page"]["id"], NEW);
+        assert_eq!(
+            value["rich_text"][1]["text"]["link"]["url"],
+            "/4f1c2b7e1c2d4e5f8a9b0c1d2e3f4a5b#3c1f"
+        );
+
+        let mut other = json!({ "page_id": "1a2b3c4d-0000-4000-8000-000000000001" });
+        assert!(!replacements().rewrite(&mut other));
+    }
+
+    #[test]
+    fn link_to_page() {
+        let block = serde_json::from_value::<BlockData>(json!({
+            "type": "link_to_page",
+            "link_to_page": { "type": "page_id", "page_id": OLD },
+        }))
+        .unwrap();
+
+        let rewritten = replacements().rewritten(&block).unwrap().unwrap();
+        assert_eq!(
+            serde_json::to_value(rewritten).unwrap()["link_to_page"]["page_id"],
+            NEW
+        );
+
+        let divider = serde_json::from_value::<BlockData>(json!({
+            "type": "divider",
+            "divider": {},
+        }))
+        .unwrap();
+        assert_eq!(replacements().rewritten(&divider).unwrap(), None);
+    }
+}
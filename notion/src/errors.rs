@@ -9,12 +9,50 @@ pub enum Error<'a> {
     ClientBuild,
     #[error("could not send API request")]
     RequestSend,
+    #[error("could not serialize API request")]
+    SerializeRequest,
     #[error("could not parse API response")]
     ParseResponse,
     #[error("could not serialize a {0} in {1}")]
     SerializeResponse(&'a str, &'a str),
     #[error("missing id in block")]
     MissingBlockId,
+    #[error("missing id in page")]
+    MissingPageId,
+    #[error("missing id in database")]
+    MissingDatabaseId,
+    #[error("could not read CSV")]
+    CsvRead,
+    #[error("could not write CSV")]
+    CsvWrite,
+    #[error("could not open mirror")]
+    MirrorOpen,
+    #[error("invalid OAuth authorization URL")]
+    OAuthUrl,
+    #[error("could not read cassette")]
+    CassetteRead,
+    #[error("could not write cassette")]
+    CassetteWrite,
+    #[error("no recorded response matches the request")]
+    UnmatchedRequest,
+    #[error("could not read the file to upload")]
+    UploadRead,
+    #[error("could not read template")]
+    TemplateRead,
+    #[error("invalid webhook signature")]
+    WebhookSignature,
+    #[error("could not parse webhook payload")]
+    WebhookPayload,
+    #[error("could not read watch checkpoint")]
+    CheckpointRead,
+    #[error("could not write watch checkpoint")]
+    CheckpointWrite,
+    #[error("could not read batch state")]
+    BatchStateRead,
+    #[error("could not write batch state")]
+    BatchStateWrite,
+    #[error("batch state is of other operations")]
+    BatchStateMismatch,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -68,6 +106,9 @@ pub enum NotionApiError {
     #[error("Gateway timeout (504): {0}")]
     GatewayTimeout(String),
 
+    #[error("OAuth error: {0}")]
+    OAuth(String),
+
     #[error("Unknown error")]
     Unknown,
 }
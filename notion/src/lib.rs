@@ -12,21 +12,32 @@
 
 pub use notion_model as model;
 
+pub mod authentication;
+pub mod batch;
+pub mod cache;
+pub mod cassette;
 pub mod client;
+pub mod csv;
+pub mod diff;
+pub mod drift;
+pub mod duplicate;
 pub mod errors;
-pub mod utils;
+pub mod metrics;
+pub mod resolve;
+#[cfg(feature = "sync")]
+pub mod sync;
+pub mod templates;
+pub mod transport;
+pub mod uploads;
+pub mod url;
+pub mod watch;
+pub mod webhooks;
 
 pub(crate) mod result_types;
 
-mod authentication;
 mod blocks;
 mod comments;
 mod databases;
 mod pages;
 mod search;
 mod users;
-
-#[allow(unused)]
-pub(crate) fn test_json() -> String {
-    String::from_utf8_lossy(include_bytes!("../../test_data/block.json")).to_string()
-}
@@ -0,0 +1,311 @@
+//! # Request metrics
+//!
+//! Every request the client sends is measured, and reported to the
+//! [`Metrics`] hook set with [`Notion::metrics`], if any. [`MemoryMetrics`]
+//! counts requests and keeps latency histograms in memory, to be exported to
+//! a monitoring system; implement [`Metrics`] to forward them elsewhere.
+//!
+//! Requests are also traced with [`tracing`] spans named `notion.request`,
+//! with the fields:
+//!
+//! - `method`, `endpoint` and `object_id`: the first ID in the path
+//! - `status` and `request_id` of the last response
+//! - `duration_ms` and `retries`, see [`Notion::retries`]
+//!
+//! At the debug level, every request and response is logged with its body,
+//! and the `Authorization` header redacted.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use notion::{client::Notion, metrics::MemoryMetrics};
+//!
+//! # fn run() -> anyhow::Result<()> {
+//! let metrics = Arc::new(MemoryMetrics::new());
+//! let notion = Notion::new("secret_token")?.metrics(metrics.clone());
+//!
+//! // ...
+//!
+//! let snapshot = metrics.snapshot();
+//! println!("{} requests were rate limited", snapshot.rate_limited);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Notion::retries`]: crate::client::Notion::retries
+//! [`Notion::metrics`]: crate::client::Notion::metrics
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use http::{header, HeaderValue, Method, StatusCode};
+use serde_json::Value;
+use tracing::Level;
+use uuid::Uuid;
+
+use crate::transport::{HttpRequest, HttpResponse};
+
+/// The header with the ID Notion gives every request.
+pub const REQUEST_ID_HEADER: &str = "x-notion-request-id";
+
+/// What happened to a request to the API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestMetrics {
+    pub method: Method,
+    /// The path of the endpoint, with IDs replaced by `{id}`, e.g.
+    /// `blocks/{id}/children`.
+    pub endpoint: String,
+    /// The status of the last response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+    /// The time from the first attempt to the last response, including the
+    /// time waited between retries.
+    pub duration: Duration,
+    /// How many times the request was sent again after a 429 or 5xx response.
+    pub retries: u32,
+    /// How many of the responses were 429 `rate_limited`.
+    pub rate_limited: u32,
+}
+
+/// Receives the metrics of every request of a client.
+pub trait Metrics: Debug + Send + Sync + 'static {
+    fn record(&self, request: &RequestMetrics);
+}
+
+impl<M: Metrics + ?Sized> Metrics for Arc<M> {
+    fn record(&self, request: &RequestMetrics) {
+        (**self).record(request);
+    }
+}
+
+/// The upper bounds of the buckets of [`Histogram`], in milliseconds.
+pub const LATENCY_BUCKETS: [u64; 12] =
+    [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/// A histogram of request durations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// The number of requests in each bucket of [`LATENCY_BUCKETS`], and the
+    /// number of slower requests last. Buckets are not cumulative.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum: Duration,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len() + 1];
+        }
+
+        let millis = duration.as_millis();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| millis <= u128::from(*bound))
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum += duration;
+    }
+}
+
+/// The counters and histograms of a [`MemoryMetrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// The number of requests by method and endpoint, e.g. `GET pages/{id}`,
+    /// and status. The status is 0 for requests without a response.
+    pub requests: BTreeMap<(String, u16), u64>,
+    /// The latency of requests by method and endpoint.
+    pub latency: BTreeMap<String, Histogram>,
+    /// The number of 429 `rate_limited` responses.
+    pub rate_limited: u64,
+    /// The number of requests sent again after a 429 or 5xx response.
+    pub retries: u64,
+}
+
+/// Metrics kept in memory.
+#[derive(Debug, Default)]
+pub struct MemoryMetrics {
+    snapshot: Mutex<MetricsSnapshot>,
+}
+
+impl MemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The metrics of the requests so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.lock().clone()
+    }
+
+    /// Resets every counter and histogram, such as after exporting them.
+    pub fn reset(&self) {
+        *self.lock() = MetricsSnapshot::default();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MetricsSnapshot> {
+        self.snapshot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Metrics for MemoryMetrics {
+    fn record(&self, request: &RequestMetrics) {
+        let endpoint = format!("{} {}", request.method, request.endpoint);
+        let status = request.status.map_or(0, |status| status.as_u16());
+
+        let mut snapshot = self.lock();
+
+        *snapshot
+            .requests
+            .entry((endpoint.clone(), status))
+            .or_default() += 1;
+        snapshot
+            .latency
+            .entry(endpoint)
+            .or_default()
+            .observe(request.duration);
+        snapshot.rate_limited += u64::from(request.rate_limited);
+        snapshot.retries += u64::from(request.retries);
+    }
+}
+
+/// What IDs are replaced by in endpoints.
+const ID_SEGMENT: &str = "{id}";
+
+/// The endpoint of a path relative to the base URL, with IDs replaced by
+/// `{id}`, and the first ID in it.
+pub(crate) fn endpoint(path: &str) -> (String, Option<String>) {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut object_id = None;
+
+    let endpoint = path
+        .split('/')
+        .map(|segment| {
+            Uuid::try_parse(segment).map_or(segment, |id| {
+                object_id.get_or_insert_with(|| id.hyphenated().to_string());
+                ID_SEGMENT
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    (endpoint, object_id)
+}
+
+/// The ID Notion gave the request of a response, from its header, or from the
+/// body of an error.
+pub(crate) fn request_id(response: &HttpResponse) -> Option<String> {
+    if let Some(request_id) = response.headers.get(REQUEST_ID_HEADER) {
+        return request_id.to_str().ok().map(str::to_owned);
+    }
+
+    if response.status.is_success() {
+        return None;
+    }
+
+    serde_json::from_str::<Value>(&response.body)
+        .ok()?
+        .get("request_id")?
+        .as_str()
+        .map(str::to_owned)
+}
+
+pub(crate) fn log_request(request: &HttpRequest) {
+    if !tracing::enabled!(Level::DEBUG) {
+        return;
+    }
+
+    let mut headers = request.headers.clone();
+    if headers.contains_key(header::AUTHORIZATION) {
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("[redacted]"),
+        );
+    }
+
+    let is_json = request
+        .headers
+        .get(header::CONTENT_TYPE)
+        .map_or(true, |content_type| {
+            content_type.as_bytes().starts_with(b"application/json")
+        });
+
+    let body = match &request.body {
+        None => String::new(),
+        Some(body) if is_json => String::from_utf8_lossy(body).into_owned(),
+        // file uploads
+        Some(body) => format!("<{} bytes>", body.len()),
+    };
+
+    tracing::debug!(url = %request.url, ?headers, body, "sending request");
+}
+
+pub(crate) fn log_response(response: &HttpResponse) {
+    tracing::debug!(
+        status = response.status.as_u16(),
+        body = response.body,
+        "received response"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints() {
+        assert_eq!(
+            endpoint("blocks/8e5d0a3c-3f40-4cbf-bc0e-0a2b1e5f3f3a/children?page_size=100"),
+            (
+                "blocks/{id}/children".to_owned(),
+                Some("8e5d0a3c-3f40-4cbf-bc0e-0a2b1e5f3f3a".to_owned())
+            )
+        );
+        assert_eq!(
+            endpoint(
+                "pages/8e5d0a3c3f404cbfbc0e0a2b1e5f3f3a/properties/4f1c2b7e-1c2d-4e5f-8a9b-0c1d2e3f4a5b"
+            ),
+            (
+                "pages/{id}/properties/{id}".to_owned(),
+                Some("8e5d0a3c-3f40-4cbf-bc0e-0a2b1e5f3f3a".to_owned())
+            )
+        );
+        assert_eq!(endpoint("users/me"), ("users/me".to_owned(), None));
+    }
+
+    #[test]
+    fn memory() {
+        let metrics = MemoryMetrics::new();
+
+        for (status, millis, retries) in [(200, 3, 0), (200, 120, 0), (429, 40_000, 2)] {
+            metrics.record(&RequestMetrics {
+                method: Method::GET,
+                endpoint: "pages/{id}".to_owned(),
+                status: Some(StatusCode::from_u16(status).unwrap()),
+                duration: Duration::from_millis(millis),
+                retries,
+                rate_limited: retries + u32::from(status == 429),
+            });
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests[&("GET pages/{id}".to_owned(), 200)], 2);
+        assert_eq!(snapshot.requests[&("GET pages/{id}".to_owned(), 429)], 1);
+        assert_eq!(snapshot.rate_limited, 3);
+        assert_eq!(snapshot.retries, 2);
+
+        let latency = &snapshot.latency["GET pages/{id}"];
+        assert_eq!(latency.count, 3);
+        assert_eq!(latency.buckets[0], 1);
+        assert_eq!(latency.buckets[5], 1);
+        assert_eq!(latency.buckets[LATENCY_BUCKETS.len()], 1);
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+}
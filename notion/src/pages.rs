@@ -1,13 +1,25 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
-use notion_model::{ids::PageId, objects::page::Page};
+use notion_model::{
+    ids::PageId,
+    objects::{
+        file_and_emoji::{Cover, FileOrEmoji},
+        page::Page,
+        properties::Property,
+    },
+};
+use serde::Serialize;
 
 use crate::{
+    cache::CacheKind,
     client::{Notion, SendAndGetText},
     errors::{Error, NotionApiError},
     result_types,
+    transport::HttpTransport,
 };
 
-impl Notion {
+impl<T: HttpTransport> Notion<T> {
     /// # Create a page
     ///
     /// Creates a new page that is a child of an existing page or database.
@@ -51,11 +63,13 @@ impl Notion {
             .send_and_get_text()
             .await?;
 
-        let res = serde_json::from_str::<result_types::Page>(&text)
-            .context(Error::SerializeResponse("Page", "create_page"))?;
+        let res = self.parse::<result_types::Page>(&text, "Page", "create_page")?;
 
         match res {
-            result_types::Page::Page(page) => Ok(page),
+            result_types::Page::Page(page) => {
+                self.invalidate_parent(page.parent.as_ref());
+                Ok(page)
+            },
             result_types::Page::Error(e) => anyhow::bail!(NotionApiError::from(e)),
         }
     }
@@ -128,29 +142,122 @@ impl Notion {
         &self,
         page_id: PageId,
         filter_properties: Option<Vec<&str>>,
+    ) -> Result<Page> {
+        let mut page = self
+            .retrieve_page_untruncated(page_id, filter_properties)
+            .await?;
+
+        // only keep the first 25 page properties
+        page.properties = page.properties.into_iter().take(25).collect();
+
+        Ok(page)
+    }
+
+    /// Retrieves a page with every one of its properties, unlike
+    /// [`Notion::retrieve_page`].
+    pub(crate) async fn retrieve_page_untruncated(
+        &self,
+        page_id: PageId,
+        filter_properties: Option<Vec<&str>>,
     ) -> Result<Page> {
         let query = filter_properties
             .iter()
+            .flatten()
             .map(|p| ("filter_properties", p))
             .collect::<Vec<_>>();
 
         let text = self
-            .api_get(&format!("pages/{page_id}"))
-            .query(&query)
-            .send_and_get_text()
+            .send_cached(
+                CacheKind::Page,
+                page_id.to_string(),
+                self.api_get(&format!("pages/{page_id}")).query(&query),
+            )
             .await?;
 
-        println!("{}", text);
-
         // let text = include_str!("../../test_data/page.json");
 
-        let res = serde_json::from_str::<result_types::Page>(&text)
-            .context(Error::SerializeResponse("Page", "retrieve_page"))?;
+        let res = self.parse::<result_types::Page>(&text, "Page", "retrieve_page")?;
+
+        match res {
+            result_types::Page::Page(page) => Ok(page),
+            result_types::Page::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// # Update page properties
+    ///
+    /// Updates the properties of a page in a database. The properties body
+    /// param of this endpoint can only be used to update the properties of a
+    /// page that is a child of a database. The page's properties schema must
+    /// match the parent database's properties.
+    ///
+    /// This endpoint can be used to update any page icon or cover, and can be
+    /// used to archive or restore any page.
+    ///
+    /// To add page content instead of page properties, use the append block
+    /// children endpoint. The page_id can be passed as the block_id when adding
+    /// block children to the page.
+    ///
+    /// Returns the updated page object.
+    ///
+    /// # 🚧
+    /// A page's parent cannot be changed.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have update content
+    /// capabilities. Attempting to call this API without update content
+    /// capabilities will return an HTTP response with a 403 status code. For
+    /// more information on integration capabilities, see the capabilities
+    /// guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the page doesn't exist, or if the
+    /// integration doesn't have access to the page.
+    ///
+    /// Returns a 400 HTTP response if the request exceeds the request limits,
+    /// or if a property that can't be updated, such as a rollup, is given.
+    ///
+    /// Returns a 409 HTTP response if the page is being updated concurrently.
+    ///
+    /// The page's id field must be set. Only the properties, icon and cover
+    /// that are given are updated.
+    pub async fn update_page(&self, page: Page) -> Result<Page> {
+        #[derive(Serialize)]
+        struct PartialPage {
+            #[serde(skip_serializing_if = "HashMap::is_empty")]
+            properties: HashMap<String, Property>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            archived: Option<bool>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            icon: Option<FileOrEmoji>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            cover: Option<Cover>,
+        }
+
+        let page_id = page.id.context(Error::MissingPageId)?;
+
+        let partial_page = PartialPage {
+            properties: page.properties,
+            archived: page.archived,
+            icon: page.icon,
+            cover: page.cover,
+        };
+
+        let text = self
+            .api_patch(&format!("pages/{page_id}"))
+            .json(&partial_page)
+            .send_and_get_text()
+            .await?;
+
+        self.invalidate(&page_id.to_string());
+
+        let res = self.parse::<result_types::Page>(&text, "Page", "update_page")?;
 
         match res {
-            result_types::Page::Page(mut page) => {
-                // only keep the first 25 page properties
-                page.properties = page.properties.into_iter().take(25).collect();
+            result_types::Page::Page(page) => {
+                self.invalidate_parent(page.parent.as_ref());
                 Ok(page)
             },
             result_types::Page::Error(e) => anyhow::bail!(NotionApiError::from(e)),
@@ -0,0 +1,103 @@
+//! # Resolving IDs
+//!
+//! Pages, databases, blocks and users all have UUIDs, and nothing in an ID
+//! tells them apart. [`Notion::resolve`] finds out what an ID refers to.
+
+use anyhow::Result;
+use notion_model::{
+    ids::{BlockId, DatabaseId, PageId, UserId},
+    objects::{
+        block::{Block, BlockData},
+        database::Database,
+        page::Page,
+        user::User,
+    },
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{client::Notion, errors::NotionApiError, transport::HttpTransport};
+
+/// An object of any kind, as returned by [`Notion::resolve`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyObject {
+    Page(Page),
+    Database(Database),
+    Block(Block),
+    User(User),
+}
+
+impl AnyObject {
+    /// The kind of object, as in its `object` field.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Page(_) => "page",
+            Self::Database(_) => "database",
+            Self::Block(_) => "block",
+            Self::User(_) => "user",
+        }
+    }
+}
+
+/// Whether an error means that the ID isn't an object of the kind that was
+/// tried.
+///
+/// Besides `object_not_found`, the API answers with a `validation_error` when
+/// the ID is of another kind of object, such as a database ID given to the
+/// pages endpoint.
+fn is_miss(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<NotionApiError>(),
+        Some(NotionApiError::ObjectNotFound(_) | NotionApiError::ValidationError(_))
+    )
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Finds the page, database, block or user that an ID refers to.
+    ///
+    /// Pages and databases are blocks too, so the blocks endpoint is tried
+    /// first, and tells which endpoint to retrieve the object from. Then the
+    /// pages, databases and users endpoints are tried in turn, for objects
+    /// that the blocks endpoint doesn't know about.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `object_not_found` error of the last endpoint if the ID
+    /// isn't anything the integration has access to, or the first error that
+    /// isn't a miss, such as a rate limit.
+    pub async fn resolve(&self, id: Uuid) -> Result<AnyObject> {
+        match self.retrieve_block(BlockId::from(id)).await {
+            Ok(block) => {
+                return Ok(match block.data {
+                    BlockData::ChildPage(_) => {
+                        AnyObject::Page(self.retrieve_page(PageId::from(id), None).await?)
+                    },
+                    BlockData::ChildDatabase(_) => {
+                        AnyObject::Database(self.retrieve_database(DatabaseId::from(id)).await?)
+                    },
+                    _ => AnyObject::Block(block),
+                });
+            },
+            Err(e) if is_miss(&e) => {},
+            Err(e) => return Err(e),
+        }
+
+        match self.retrieve_page(PageId::from(id), None).await {
+            Ok(page) => return Ok(AnyObject::Page(page)),
+            Err(e) if is_miss(&e) => {},
+            Err(e) => return Err(e),
+        }
+
+        match self.retrieve_database(DatabaseId::from(id)).await {
+            Ok(database) => return Ok(AnyObject::Database(database)),
+            Err(e) if is_miss(&e) => {},
+            Err(e) => return Err(e),
+        }
+
+        self.retrieve_user(UserId::from(id))
+            .await
+            .map(AnyObject::User)
+    }
+}
@@ -6,9 +6,9 @@
 //!
 //! For the user-facing API, we return the deserialized result or an error,
 //! rather than a struct in this module.
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "object", rename_all = "snake_case")]
 pub enum Block {
     Block(crate::model::objects::block::Block),
@@ -16,7 +16,7 @@ pub enum Block {
 }
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "object", rename_all = "snake_case")]
 pub enum Page {
     Page(crate::model::objects::page::Page),
@@ -24,16 +24,50 @@ pub enum Page {
 }
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "object", rename_all = "snake_case")]
 pub enum Database {
     Database(crate::model::objects::database::Database),
     Error(crate::errors::ErrorInfo),
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum User {
+    User(crate::model::objects::user::User),
+    Error(crate::errors::ErrorInfo),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum Comment {
+    Comment(crate::model::objects::comment::Comment),
+    Error(crate::errors::ErrorInfo),
+}
+
+#[derive(Serialize, Deserialize)]
 #[serde(tag = "object", rename_all = "snake_case")]
 pub enum List<T> {
     List(crate::model::pagination::List<T>),
     Error(crate::errors::ErrorInfo),
 }
+
+/// The OAuth endpoints either return a Notion error object, or an OAuth 2.0
+/// error when a grant is rejected.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum OAuth<T> {
+    Error(crate::errors::ErrorInfo),
+    Rejected {
+        error: String,
+        error_description: Option<String>,
+    },
+    Ok(T),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum FileUpload {
+    FileUpload(crate::model::objects::file_upload::FileUpload),
+    Error(crate::errors::ErrorInfo),
+}
@@ -1 +1,75 @@
+use anyhow::Result;
+use notion_model::search::{PageOrDatabase, SearchQuery};
 
+use crate::{
+    client::{Notion, SendAndGetText},
+    errors::NotionApiError,
+    model::pagination::List,
+    result_types,
+    transport::HttpTransport,
+};
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Search by title
+    ///
+    /// Searches all parent or child pages and databases that have been shared
+    /// with an integration.
+    ///
+    /// Returns all pages or databases, excluding duplicated linked databases,
+    /// that have titles that include the query param. If no query param is
+    /// provided, then the response contains all pages or databases that have
+    /// been shared with the integration. The results adhere to any limitations
+    /// related to an integration’s capabilities.
+    ///
+    /// To limit the request to search only pages or to search only databases,
+    /// use the filter param.
+    ///
+    /// # 📘 Search indexing is not immediate
+    ///
+    /// If an integration performs a search quickly after a page is shared with
+    /// the integration (such as immediately after a user performs OAuth), then
+    /// the response may not contain the page.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if the request body is invalid.
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn search(&self, query: SearchQuery) -> Result<List<PageOrDatabase>> {
+        let text = self
+            .api_post("search")
+            .json(&query)
+            .send_and_get_text()
+            .await?;
+
+        let res = self.parse::<result_types::List<PageOrDatabase>>(
+            &text,
+            "List<PageOrDatabase>",
+            "search",
+        )?;
+
+        match res {
+            result_types::List::List(results) => Ok(results),
+            result_types::List::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// Searches, following `next_cursor` until every result has been
+    /// retrieved.
+    ///
+    /// The `start_cursor` of `query` is used for the first request only.
+    pub async fn search_all(&self, mut query: SearchQuery) -> Result<Vec<PageOrDatabase>> {
+        let mut results = Vec::new();
+
+        loop {
+            let list = self.search(query.clone()).await?;
+            results.extend(list.results);
+
+            match list.next_cursor {
+                Some(cursor) if list.has_more => query.start_cursor = Some(cursor),
+                _ => return Ok(results),
+            }
+        }
+    }
+}
@@ -0,0 +1,781 @@
+//! Mirroring databases, page trees and users into a local SQLite database.
+//!
+//! A [`Mirror`] is a SQLite file with one table per kind of object. Every row
+//! keeps the full JSON of the object next to a few columns that are useful for
+//! querying:
+//!
+//! - `databases`: `id`, `title`, `last_edited_time`, `archived`, `json`
+//! - `pages`: `id`, `parent_id`, `database_id`, `title`, `created_time`,
+//!   `last_edited_time`, `archived`, `url`, `json`
+//! - `properties`: `page_id`, `name`, `type`, `value`, `json`, where `value` is
+//!   formatted the same way as [`crate::csv`]
+//! - `blocks`: `id`, `page_id`, `parent_id`, `position`, `type`,
+//!   `has_children`, `last_edited_time`, `archived`, `json`
+//! - `users`: `id`, `name`, `type`, `email`, `json`
+//!
+//! # Incremental sync
+//!
+//! The first sync of a database lists every page. Later syncs only list the
+//! pages whose `last_edited_time` is on or after the latest `last_edited_time`
+//! seen by the previous sync.
+//!
+//! `last_edited_time` is only precise to the minute, so a page edited again in
+//! the same minute as the latest edit seen by the previous sync keeps its
+//! `last_edited_time`. Such pages are compared with the mirror, and their
+//! content is retrieved again, instead of being skipped. Only pages and blocks
+//! that differ from the mirror are written and counted in the [`SyncReport`].
+//!
+//! Pages that are archived are not returned when querying a database, so an
+//! incremental sync can't notice them. A full sync (see [`SyncOptions::full`])
+//! marks every mirrored page that is no longer in the database as archived.
+//!
+//! Page trees are walked from their root, and retrieving each page tells
+//! whether it was archived. The content of a page is only retrieved again when
+//! its `last_edited_time` changed, or is in the minute of the latest edit seen
+//! in the tree by the previous sync. Blocks that disappear from a page are
+//! marked as archived, and pages that disappear from it are retrieved once
+//! more.
+//!
+//! Rows are never deleted, archived objects only have `archived` set to 1.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use notion_model::{
+    ids::{BlockId, DatabaseId, PageId},
+    objects::{
+        block::{Block, BlockData},
+        database::Database,
+        date::DateOrDateTime,
+        page::Page,
+        parent::ParentData,
+        properties::PropertyData,
+        user::{User, UserData},
+    },
+    query::{DatabaseQuery, Sort, SortDirection, SortTimestamp},
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    cache::parent_id,
+    client::Notion,
+    csv::{format_property, plain_text},
+    errors::{Error, NotionApiError},
+    transport::HttpTransport,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS databases (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    last_edited_time TEXT,
+    archived INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pages (
+    id TEXT PRIMARY KEY NOT NULL,
+    parent_id TEXT,
+    database_id TEXT,
+    title TEXT NOT NULL,
+    created_time TEXT,
+    last_edited_time TEXT,
+    archived INTEGER NOT NULL,
+    url TEXT,
+    json TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS pages_database_id ON pages (database_id);
+
+CREATE TABLE IF NOT EXISTS properties (
+    page_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    value TEXT NOT NULL,
+    json TEXT NOT NULL,
+    PRIMARY KEY (page_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS blocks (
+    id TEXT PRIMARY KEY NOT NULL,
+    page_id TEXT NOT NULL,
+    parent_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    has_children INTEGER NOT NULL,
+    last_edited_time TEXT,
+    archived INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS blocks_page_id ON blocks (page_id);
+
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT,
+    type TEXT,
+    email TEXT,
+    json TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS checkpoints (
+    source TEXT PRIMARY KEY NOT NULL,
+    last_edited_time TEXT NOT NULL
+);
+";
+
+/// A local SQLite copy of Notion objects.
+#[derive(Debug)]
+pub struct Mirror {
+    conn: Connection,
+}
+
+/// What to sync into a [`Mirror`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Databases whose pages are mirrored.
+    pub databases: Vec<DatabaseId>,
+    /// Pages that are mirrored along with their content and every page nested
+    /// in them.
+    pub pages: Vec<PageId>,
+    /// Whether the content of the pages of the databases is mirrored too. The
+    /// content of pages in page trees is always mirrored.
+    pub content: bool,
+    /// Whether the users of the workspace are mirrored.
+    pub users: bool,
+    /// Whether to ignore the state of the previous sync, retrieving every
+    /// object again and detecting pages that were archived.
+    pub full: bool,
+}
+
+impl SyncOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn databases(mut self, databases: Vec<DatabaseId>) -> Self {
+        self.databases = databases;
+        self
+    }
+
+    pub fn pages(mut self, pages: Vec<PageId>) -> Self {
+        self.pages = pages;
+        self
+    }
+
+    pub const fn content(mut self, content: bool) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub const fn users(mut self, users: bool) -> Self {
+        self.users = users;
+        self
+    }
+
+    pub const fn full(mut self, full: bool) -> Self {
+        self.full = full;
+        self
+    }
+}
+
+/// What changed in the mirror during a sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Pages that were created or updated.
+    pub pages_updated: usize,
+    /// Pages that were marked as archived.
+    pub pages_archived: usize,
+    /// Blocks that were created or updated.
+    pub blocks_updated: usize,
+    /// Users that were created or updated.
+    pub users_updated: usize,
+}
+
+impl Mirror {
+    /// Opens the mirror at `path`, creating it if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_connection(Connection::open(path).context(Error::MirrorOpen)?)
+    }
+
+    /// Opens a mirror that only lives in memory.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory().context(Error::MirrorOpen)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).context(Error::MirrorOpen)?;
+
+        Ok(Self { conn })
+    }
+
+    /// The underlying SQLite connection, for querying the mirrored data.
+    pub const fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    fn checkpoint(&self, source: &str) -> Result<Option<DateTime<FixedOffset>>> {
+        let time = self
+            .conn
+            .query_row(
+                "SELECT last_edited_time FROM checkpoints WHERE source = ?1",
+                [source],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        Ok(time
+            .map(|time| DateTime::parse_from_rfc3339(&time))
+            .transpose()?)
+    }
+
+    fn set_checkpoint(&self, source: &str, time: DateTime<FixedOffset>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO checkpoints (source, last_edited_time) VALUES (?1, ?2)
+             ON CONFLICT (source) DO UPDATE SET last_edited_time = excluded.last_edited_time",
+            params![source, time.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    fn page_last_edited_time(&self, page_id: PageId) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_edited_time FROM pages WHERE id = ?1 AND archived = 0",
+                [page_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    fn page_json(&self, page_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT json FROM pages WHERE id = ?1 AND archived = 0",
+                [page_id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn database_page_ids(&self, database_id: DatabaseId) -> Result<Vec<String>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT id FROM pages WHERE database_id = ?1 AND archived = 0")?;
+
+        let ids = statement
+            .query_map([database_id.to_string()], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(ids)
+    }
+
+    fn child_page_ids(&self, page_id: PageId) -> Result<Vec<PageId>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id FROM blocks WHERE page_id = ?1 AND type = 'child_page' AND archived = 0",
+        )?;
+
+        let ids = statement
+            .query_map([page_id.to_string()], |row| row.get::<_, String>(0))?
+            .map(|id| Ok(id?.parse()?))
+            .collect::<Result<_>>()?;
+
+        Ok(ids)
+    }
+
+    fn upsert_database(&self, database: &Database) -> Result<()> {
+        let id = database.id.context(Error::MissingDatabaseId)?;
+
+        self.conn.execute(
+            "INSERT INTO databases (id, title, last_edited_time, archived, json)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET
+                title = excluded.title,
+                last_edited_time = excluded.last_edited_time,
+                archived = excluded.archived,
+                json = excluded.json",
+            params![
+                id.to_string(),
+                plain_text(&database.title),
+                database.last_edited_time.map(|time| time.to_string()),
+                database.archived.unwrap_or_default(),
+                serde_json::to_string(database)?,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Writes a page and its properties, returning whether it differed from
+    /// the mirror.
+    fn upsert_page(&mut self, page: &Page) -> Result<bool> {
+        let id = page.id.context(Error::MissingPageId)?.to_string();
+        let json = serde_json::to_string(page)?;
+
+        if self.page_json(&id)?.as_ref() == Some(&json) {
+            return Ok(false);
+        }
+
+        let title = page
+            .properties
+            .values()
+            .find(|property| matches!(property.data, PropertyData::Title(_)))
+            .map(|property| format_property(&property.data))
+            .unwrap_or_default();
+
+        let database_id = match page.parent {
+            Some(ParentData::DatabaseId { database_id }) => Some(database_id.to_string()),
+            _ => None,
+        };
+
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO pages (
+                id, parent_id, database_id, title, created_time, last_edited_time, archived,
+                url, json
+             )
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT (id) DO UPDATE SET
+                parent_id = excluded.parent_id,
+                database_id = excluded.database_id,
+                title = excluded.title,
+                created_time = excluded.created_time,
+                last_edited_time = excluded.last_edited_time,
+                archived = excluded.archived,
+                url = excluded.url,
+                json = excluded.json",
+            params![
+                id,
+                page.parent.as_ref().map(parent_id),
+                database_id,
+                title,
+                page.created_time.map(|time| time.to_string()),
+                page.last_edited_time.map(|time| time.to_string()),
+                page.archived.unwrap_or_default(),
+                page.url,
+                json,
+            ],
+        )?;
+
+        tx.execute("DELETE FROM properties WHERE page_id = ?1", [&id])?;
+
+        for (name, property) in &page.properties {
+            tx.execute(
+                "INSERT INTO properties (page_id, name, type, value, json)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    id,
+                    name,
+                    type_name(&property.data)?,
+                    format_property(&property.data),
+                    serde_json::to_string(&property.data)?,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(true)
+    }
+
+    /// Marks pages as archived, returning how many were not already archived.
+    fn archive_pages(&mut self, page_ids: &[String]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut archived = 0;
+
+        for page_id in page_ids {
+            archived += tx.execute(
+                "UPDATE pages SET archived = 1 WHERE id = ?1 AND archived = 0",
+                [page_id],
+            )?;
+            tx.execute(
+                "UPDATE blocks SET archived = 1 WHERE page_id = ?1",
+                [page_id],
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(archived)
+    }
+
+    /// Replaces the content of a page, returning how many blocks differed from
+    /// the mirror. Blocks that are no longer part of the page are marked as
+    /// archived.
+    fn replace_blocks(&mut self, page_id: PageId, blocks: &[ContentBlock]) -> Result<usize> {
+        let page_id = page_id.to_string();
+        let tx = self.conn.transaction()?;
+        let mut updated = 0;
+
+        for ContentBlock {
+            parent_id,
+            position,
+            block,
+        } in blocks
+        {
+            let id = block.id.context(Error::MissingBlockId)?;
+
+            let unchanged = tx
+                .query_row(
+                    "SELECT 1 FROM blocks
+                     WHERE id = ?1 AND page_id = ?2 AND parent_id = ?3 AND position = ?4
+                        AND archived = 0 AND json = ?5",
+                    params![
+                        id.to_string(),
+                        page_id,
+                        parent_id.to_string(),
+                        position,
+                        serde_json::to_string(block)?,
+                    ],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+
+            if !unchanged {
+                updated += 1;
+            }
+        }
+
+        tx.execute(
+            "UPDATE blocks SET archived = 1 WHERE page_id = ?1",
+            [&page_id],
+        )?;
+
+        for ContentBlock {
+            parent_id,
+            position,
+            block,
+        } in blocks
+        {
+            let id = block.id.context(Error::MissingBlockId)?;
+
+            tx.execute(
+                "INSERT INTO blocks (
+                    id, page_id, parent_id, position, type, has_children, last_edited_time,
+                    archived, json
+                 )
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT (id) DO UPDATE SET
+                    page_id = excluded.page_id,
+                    parent_id = excluded.parent_id,
+                    position = excluded.position,
+                    type = excluded.type,
+                    has_children = excluded.has_children,
+                    last_edited_time = excluded.last_edited_time,
+                    archived = excluded.archived,
+                    json = excluded.json",
+                params![
+                    id.to_string(),
+                    page_id,
+                    parent_id.to_string(),
+                    position,
+                    type_name(&block.data)?,
+                    block.has_children.unwrap_or_default(),
+                    block.last_edited_time.map(|time| time.to_string()),
+                    block.archived.unwrap_or_default(),
+                    serde_json::to_string(block)?,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(updated)
+    }
+
+    fn upsert_user(&self, user: &User) -> Result<()> {
+        let (user_type, email) = match &user.data {
+            Some(UserData::Person(person)) => (Some("person"), person.email.clone()),
+            Some(UserData::Bot(_)) => (Some("bot"), None),
+            None => (None, None),
+        };
+
+        self.conn.execute(
+            "INSERT INTO users (id, name, type, email, json) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET
+                name = excluded.name,
+                type = excluded.type,
+                email = excluded.email,
+                json = excluded.json",
+            params![
+                user.id.to_string(),
+                user.name,
+                user_type,
+                email,
+                serde_json::to_string(user)?,
+            ],
+        )?;
+
+        Ok(())
+    }
+}
+
+/// A block along with its place in the content of a page.
+struct ContentBlock {
+    parent_id: BlockId,
+    position: usize,
+    block: Block,
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Brings a [`Mirror`] up to date with Notion.
+    ///
+    /// See the [module documentation](crate::sync) for how changes are
+    /// detected.
+    pub async fn sync(&self, mirror: &mut Mirror, options: &SyncOptions) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+
+        if options.users {
+            for user in self.list_users_all().await? {
+                mirror.upsert_user(&user)?;
+                report.users_updated += 1;
+            }
+        }
+
+        for database_id in &options.databases {
+            self.sync_database(mirror, *database_id, options, &mut report)
+                .await?;
+        }
+
+        for page_id in &options.pages {
+            self.sync_page_tree(mirror, *page_id, options, &mut report)
+                .await?;
+        }
+
+        Ok(report)
+    }
+
+    async fn sync_database(
+        &self,
+        mirror: &mut Mirror,
+        database_id: DatabaseId,
+        options: &SyncOptions,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let database = self.retrieve_database(database_id).await?;
+        mirror.upsert_database(&database)?;
+
+        let source = format!("database:{database_id}");
+        let checkpoint = match options.full {
+            true => None,
+            false => mirror.checkpoint(&source)?,
+        };
+
+        let filter = checkpoint.map(|time| {
+            serde_json::json!({
+                "timestamp": "last_edited_time",
+                "last_edited_time": { "on_or_after": time.to_rfc3339() },
+            })
+        });
+
+        let query = DatabaseQuery::new()
+            .filter(filter)
+            .sorts(vec![Sort::Timestamp {
+                timestamp: SortTimestamp::LastEditedTime,
+                direction: SortDirection::Ascending,
+            }]);
+
+        let pages = self.query_database_all(database_id, query).await?;
+
+        let mut latest = checkpoint;
+
+        // the pages listed by an incremental sync were edited since the
+        // previous sync, or in the minute of its latest edit, so each of them
+        // is compared with the mirror
+        for page in &pages {
+            let page_id = page.id.context(Error::MissingPageId)?;
+
+            latest = latest.max(edited_at(page));
+
+            if mirror.upsert_page(page)? {
+                report.pages_updated += 1;
+            }
+
+            if options.content {
+                self.sync_page_content(mirror, page_id, report).await?;
+            }
+        }
+
+        if checkpoint.is_none() {
+            let seen = pages
+                .iter()
+                .filter_map(|page| page.id.map(|id| id.to_string()))
+                .collect::<Vec<_>>();
+
+            let missing = mirror
+                .database_page_ids(database_id)?
+                .into_iter()
+                .filter(|id| !seen.contains(id))
+                .collect::<Vec<_>>();
+
+            report.pages_archived += mirror.archive_pages(&missing)?;
+        }
+
+        if let Some(latest) = latest {
+            mirror.set_checkpoint(&source, latest)?;
+        }
+
+        Ok(())
+    }
+
+    async fn sync_page_tree(
+        &self,
+        mirror: &mut Mirror,
+        root: PageId,
+        options: &SyncOptions,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let source = format!("page:{root}");
+        let checkpoint = match options.full {
+            true => None,
+            false => mirror.checkpoint(&source)?,
+        };
+
+        let mut latest = checkpoint;
+        let mut queue = vec![root];
+
+        while let Some(page_id) = queue.pop() {
+            let page = match self.retrieve_page(page_id, None).await {
+                Ok(page) => page,
+                Err(e) => match e.downcast_ref::<NotionApiError>() {
+                    Some(NotionApiError::ObjectNotFound(_)) => {
+                        report.pages_archived += mirror.archive_pages(&[page_id.to_string()])?;
+                        continue;
+                    },
+                    _ => return Err(e),
+                },
+            };
+
+            if page.archived.unwrap_or_default() {
+                report.pages_archived += mirror.archive_pages(&[page_id.to_string()])?;
+                continue;
+            }
+
+            let edited = edited_at(&page);
+            latest = latest.max(edited);
+
+            // edits after the previous sync are at least in the minute of the
+            // latest edit it saw, so only earlier pages are known to be current
+            let settled = checkpoint.map_or(false, |checkpoint| edited < Some(checkpoint));
+            let last_edited_time = page.last_edited_time.map(|time| time.to_string());
+
+            if settled && mirror.page_last_edited_time(page_id)? == last_edited_time {
+                queue.extend(mirror.child_page_ids(page_id)?);
+                continue;
+            }
+
+            if mirror.upsert_page(&page)? {
+                report.pages_updated += 1;
+            }
+
+            // pages that are no longer in the content are retrieved once more,
+            // to tell whether they were archived
+            let previous = mirror.child_page_ids(page_id)?;
+            let children = self.sync_page_content(mirror, page_id, report).await?;
+
+            queue.extend(previous.into_iter().filter(|id| !children.contains(id)));
+            queue.extend(children);
+        }
+
+        if let Some(latest) = latest {
+            mirror.set_checkpoint(&source, latest)?;
+        }
+
+        Ok(())
+    }
+
+    /// Mirrors the content of a page, returning the ids of the pages nested in
+    /// it.
+    async fn sync_page_content(
+        &self,
+        mirror: &mut Mirror,
+        page_id: PageId,
+        report: &mut SyncReport,
+    ) -> Result<Vec<PageId>> {
+        let mut blocks = Vec::new();
+        let mut child_pages = Vec::new();
+        let mut queue = vec![BlockId::from(*page_id.into_inner())];
+
+        while let Some(parent_id) = queue.pop() {
+            let children = self.retrieve_block_children_all(parent_id).await?;
+
+            for (position, block) in children.into_iter().enumerate() {
+                let id = block.id.context(Error::MissingBlockId)?;
+
+                match block.data {
+                    BlockData::ChildPage(_) => child_pages.push(PageId::from(*id.into_inner())),
+                    // the content of child databases is only mirrored when the
+                    // database is synced
+                    BlockData::ChildDatabase(_) => {},
+                    _ if block.has_children.unwrap_or_default() => queue.push(id),
+                    _ => {},
+                }
+
+                blocks.push(ContentBlock {
+                    parent_id,
+                    position,
+                    block,
+                });
+            }
+        }
+
+        report.blocks_updated += mirror.replace_blocks(page_id, &blocks)?;
+
+        Ok(child_pages)
+    }
+}
+
+/// When a page was last edited, to the minute.
+const fn edited_at(page: &Page) -> Option<DateTime<FixedOffset>> {
+    match page.last_edited_time {
+        Some(DateOrDateTime::DateTime(time)) => Some(time),
+        _ => None,
+    }
+}
+
+/// The name of the variant of an externally tagged enum, such as
+/// [`BlockData`] or [`PropertyData`]. Unknown variants serialize with their
+/// `type` next to their data.
+fn type_name<T: serde::Serialize>(data: &T) -> Result<String> {
+    match serde_json::to_value(data)? {
+        serde_json::Value::Object(mut map) => match map.remove("type") {
+            Some(serde_json::Value::String(name)) => Some(name),
+            _ => map.into_iter().next().map(|(key, _)| key),
+        },
+        serde_json::Value::String(name) => Some(name),
+        _ => None,
+    }
+    .context(Error::SerializeResponse("type name", "sync"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn type_names() {
+        let data = BlockData::Unknown {
+            type_name: "widget".to_owned(),
+            raw: json!({}),
+        };
+        assert_eq!(type_name(&data).unwrap(), "widget");
+
+        let data = PropertyData::Unknown {
+            type_name: "vote".to_owned(),
+            raw: json!({ "count": 3 }),
+        };
+        assert_eq!(type_name(&data).unwrap(), "vote");
+
+        let data = serde_json::from_value::<BlockData>(json!({ "divider": {} })).unwrap();
+        assert_eq!(type_name(&data).unwrap(), "divider");
+    }
+}
@@ -0,0 +1,781 @@
+//! # Page templates
+//!
+//! A [`Template`] is the properties and content of a page with `{{name}}`
+//! placeholders in its text, which [`Template::render`] fills in from
+//! variables. Placeholders are replaced inside each piece of rich text, so
+//! their annotations, links and colors are kept. Properties, such as the
+//! title or a select option, can have placeholders too.
+//!
+//! Code blocks, equations, and text formatted as inline code are left as they
+//! are, since `{{` is common in code and LaTeX. The caption of a code block
+//! can still have placeholders.
+//!
+//! Variables are any value that serializes to a JSON object. Placeholders can
+//! reach into nested objects with dots, like `{{incident.title}}`.
+//!
+//! A block whose only text is `{{#each name}}` starts a loop over the list
+//! `name`, which ends at a later sibling block whose only text is
+//! `{{/each}}`. The blocks between them are repeated for each item, in which
+//! `{{this}}` is the item, `{{@index}}` is its position from 0, and the fields
+//! of an item that is an object can be used by name. The marker blocks are
+//! left out of the page.
+//!
+//! Templates can be read from an existing page with
+//! [`Notion::retrieve_template`], or from a JSON file with
+//! [`Template::from_file`].
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use notion::{client::Notion, model::ids::PageId};
+//! use serde_json::json;
+//!
+//! let notion = Notion::new("secret_token")?;
+//! let template_id = "b55c9c91-384d-452b-81db-d1ef79372b75".parse::<PageId>()?;
+//! let parent_id = "2e3a8d4b-1f6c-4a1e-9d3e-5b7c8a9f0e1d".parse::<PageId>()?;
+//!
+//! let template = notion.retrieve_template(template_id).await?;
+//!
+//! let page = notion
+//!     .create_page_from_template(
+//!         parent_id.into(),
+//!         &template,
+//!         &json!({
+//!             "title": "API outage",
+//!             "timeline": [
+//!                 { "time": "10:02", "event": "Alerts fire" },
+//!                 { "time": "10:15", "event": "Rolled back" },
+//!             ],
+//!         }),
+//!     )
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{borrow::Cow, collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use notion_model::{
+    ids::{BlockId, PageId},
+    objects::{
+        block::{Block, BlockData},
+        file_and_emoji::{Cover, FileOrEmoji},
+        page::Page,
+        parent::ParentData,
+        properties::{Property, PropertyData},
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    client::Notion,
+    diff::{BlockTree, Operation, Plan},
+    errors::Error,
+    transport::HttpTransport,
+};
+
+/// The properties and content of a page, with placeholders.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Template {
+    #[serde(default)]
+    pub properties: HashMap<String, Property>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<FileOrEmoji>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<Cover>,
+    /// The content, with children nested in the blocks.
+    #[serde(default)]
+    pub children: Vec<Block>,
+}
+
+impl Template {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn properties(mut self, properties: HashMap<String, Property>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    pub fn icon(mut self, icon: Option<FileOrEmoji>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn cover(mut self, cover: Option<Cover>) -> Self {
+        self.cover = cover;
+        self
+    }
+
+    pub fn children(mut self, children: Vec<Block>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Reads a template from a JSON file, which is either a template object or
+    /// just a list of blocks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or isn't a template.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let text = fs::read_to_string(path).context(Error::TemplateRead)?;
+        let value = serde_json::from_str::<Value>(&text).context(Error::TemplateRead)?;
+
+        match value {
+            Value::Array(_) => {
+                Ok(Self::new()
+                    .children(serde_json::from_value(value).context(Error::TemplateRead)?))
+            },
+            value => serde_json::from_value(value).context(Error::TemplateRead),
+        }
+    }
+
+    /// Fills in the placeholders and loops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a placeholder has no variable, or isn't text, or a
+    /// loop isn't over a list or isn't closed.
+    pub fn render(&self, variables: &impl Serialize) -> Result<Self> {
+        let variables = serde_json::to_value(variables).context(Error::SerializeRequest)?;
+        let scopes = [Scope {
+            value: &variables,
+            index: None,
+        }];
+
+        let properties = self
+            .properties
+            .iter()
+            .map(|(name, property)| Ok((name.clone(), substitute(property, &scopes)?)))
+            .collect::<Result<_>>()?;
+
+        let children = render_blocks(&self.children, &scopes)?
+            .iter()
+            .map(|tree| tree.to_block(usize::MAX))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            properties,
+            icon: self.icon.clone(),
+            cover: self.cover.clone(),
+            children,
+        })
+    }
+
+    /// The template of a page and its content. Properties that Notion
+    /// computes, files hosted by Notion, and child pages and databases are left
+    /// out, as they can't be copied to a new page.
+    pub(crate) fn from_page(page: Page, trees: &[BlockTree]) -> Result<Self> {
+        let properties = page
+            .properties
+            .into_iter()
+            .filter(|(_, property)| writable(&property.data))
+            .map(|(name, property)| (name, Property::new(property.data)))
+            .collect();
+
+        let children = trees
+            .iter()
+            .filter_map(template_block)
+            .map(|tree| tree.to_block(usize::MAX))
+            .collect::<Result<_>>()?;
+
+        Ok(Self::new()
+            .properties(properties)
+            .icon(copyable_icon(page.icon))
+            .cover(copyable_cover(page.cover))
+            .children(children))
+    }
+}
+
+/// A value that placeholders are looked up in, innermost last.
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    value: &'a Value,
+    /// The position of the item, in a loop.
+    index: Option<usize>,
+}
+
+fn render_blocks(blocks: &[Block], scopes: &[Scope<'_>]) -> Result<Vec<BlockTree>> {
+    let mut rendered = Vec::new();
+    let mut i = 0;
+
+    while i < blocks.len() {
+        match marker(&blocks[i])? {
+            Some(Marker::Each(path)) => {
+                let end = loop_end(blocks, i)?;
+
+                let items = lookup(&path, scopes)?;
+                let Value::Array(items) = items.as_ref() else {
+                    anyhow::bail!("`{{{{#each {path}}}}}` over something that isn't a list");
+                };
+
+                for (index, item) in items.iter().enumerate() {
+                    let mut scopes = scopes.to_vec();
+                    scopes.push(Scope {
+                        value: item,
+                        index: Some(index),
+                    });
+
+                    rendered.extend(render_blocks(&blocks[i + 1..end], &scopes)?);
+                }
+
+                i = end + 1;
+            },
+            Some(Marker::End) => anyhow::bail!("`{{{{/each}}}}` without a `{{{{#each}}}}`"),
+            None => {
+                let tree = BlockTree::from_block(blocks[i].clone())?;
+                let children = tree
+                    .children
+                    .iter()
+                    .map(|tree| tree.to_block(usize::MAX))
+                    .collect::<Result<Vec<_>>>()?;
+
+                rendered.push(BlockTree {
+                    block: Block::new(substitute_block(&tree.block.data, scopes)?),
+                    children: render_blocks(&children, scopes)?,
+                });
+
+                i += 1;
+            },
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// The index of the `{{/each}}` that closes the `{{#each}}` at `start`.
+fn loop_end(blocks: &[Block], start: usize) -> Result<usize> {
+    let mut depth = 0;
+
+    for (i, block) in blocks.iter().enumerate().skip(start) {
+        match marker(block)? {
+            Some(Marker::Each(_)) => depth += 1,
+            Some(Marker::End) if depth == 1 => return Ok(i),
+            Some(Marker::End) => depth -= 1,
+            None => {},
+        }
+    }
+
+    anyhow::bail!("a `{{{{#each}}}}` that isn't closed with `{{{{/each}}}}`")
+}
+
+enum Marker {
+    Each(String),
+    End,
+}
+
+/// Whether the only text of a block is a loop marker.
+fn marker(block: &Block) -> Result<Option<Marker>> {
+    if let BlockData::Unknown { .. } = block.data {
+        return Ok(None);
+    }
+
+    let data = serde_json::to_value(&block.data).context(Error::SerializeRequest)?;
+
+    let text = data
+        .as_object()
+        .and_then(|data| data.values().next())
+        .and_then(|fields| fields.get("rich_text"))
+        .and_then(Value::as_array)
+        .map(|rich_text| {
+            rich_text
+                .iter()
+                .filter_map(|text| text.pointer("/text/content").and_then(Value::as_str))
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    let Some(tag) = text
+        .trim()
+        .strip_prefix("{{")
+        .and_then(|text| text.strip_suffix("}}"))
+    else {
+        return Ok(None);
+    };
+
+    Ok(match tag.trim() {
+        "/each" => Some(Marker::End),
+        tag => tag
+            .strip_prefix("#each ")
+            .map(|path| Marker::Each(path.trim().to_owned())),
+    })
+}
+
+/// Fills in the placeholders of a block, leaving the text of code blocks and
+/// equations as it is.
+fn substitute_block(data: &BlockData, scopes: &[Scope<'_>]) -> Result<BlockData> {
+    match data {
+        BlockData::Code(code) => {
+            let caption = code
+                .caption
+                .as_ref()
+                .map(|caption| substitute(caption, scopes))
+                .transpose()?;
+
+            Ok(BlockData::Code(code.clone().caption(caption)))
+        },
+        BlockData::Equation(_) => Ok(data.clone()),
+        data => substitute(data, scopes),
+    }
+}
+
+/// Fills in the placeholders in every string in a value.
+fn substitute<T: Serialize + for<'de> Deserialize<'de>>(
+    value: &T,
+    scopes: &[Scope<'_>],
+) -> Result<T> {
+    let mut value = serde_json::to_value(value).context(Error::SerializeRequest)?;
+    substitute_value(&mut value, scopes)?;
+
+    serde_json::from_value(value).context(Error::SerializeRequest)
+}
+
+fn substitute_value(value: &mut Value, scopes: &[Scope<'_>]) -> Result<()> {
+    if verbatim(value) {
+        return Ok(());
+    }
+
+    match value {
+        Value::String(s) if s.contains("{{") => *s = substitute_str(s, scopes)?,
+        Value::Array(values) => {
+            for value in values {
+                substitute_value(value, scopes)?;
+            }
+        },
+        Value::Object(fields) => {
+            for value in fields.values_mut() {
+                substitute_value(value, scopes)?;
+            }
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// Whether a value is rich text that is an inline equation or inline code.
+fn verbatim(value: &Value) -> bool {
+    value.pointer("/equation/expression").is_some()
+        || value.pointer("/annotations/code").and_then(Value::as_bool) == Some(true)
+}
+
+/// Fills in the placeholders in a string. A `{{` without a `}}` is kept.
+fn substitute_str(s: &str, scopes: &[Scope<'_>]) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        out.push_str(&rest[..start]);
+
+        let path = rest[start + 2..start + 2 + len].trim();
+
+        match lookup(path, scopes)?.as_ref() {
+            Value::Null => {},
+            Value::String(s) => out.push_str(s),
+            value @ (Value::Bool(_) | Value::Number(_)) => out.push_str(&value.to_string()),
+            Value::Array(_) | Value::Object(_) => {
+                anyhow::bail!("`{{{{{path}}}}}` is a list or object, not text")
+            },
+        }
+
+        rest = &rest[start + 2 + len + 2..];
+    }
+
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+/// Looks up a dotted path, starting from the innermost scope that has its
+/// first name.
+fn lookup<'a>(path: &str, scopes: &[Scope<'a>]) -> Result<Cow<'a, Value>> {
+    let innermost = scopes.last().copied().unwrap_or(Scope {
+        value: &Value::Null,
+        index: None,
+    });
+
+    if path == "@index" {
+        return match innermost.index {
+            Some(index) => Ok(Cow::Owned(Value::from(index))),
+            None => anyhow::bail!("`{{{{@index}}}}` outside of a loop"),
+        };
+    }
+
+    let mut names = path.split('.');
+    let first = names.next().unwrap_or_default();
+
+    let value = if first == "this" {
+        Some(innermost.value)
+    } else {
+        scopes.iter().rev().find_map(|scope| scope.value.get(first))
+    };
+
+    names
+        .try_fold(value, |value, name| Some(value?.get(name)))
+        .flatten()
+        .map(Cow::Borrowed)
+        .with_context(|| format!("no value for `{{{{{path}}}}}`"))
+}
+
+/// The icon, unless it's a file hosted by Notion, which can't be reused.
+pub(crate) fn copyable_icon(icon: Option<FileOrEmoji>) -> Option<FileOrEmoji> {
+    icon.filter(|icon| !matches!(icon, FileOrEmoji::File { .. }))
+}
+
+/// The cover, unless it's a file hosted by Notion, which can't be reused.
+pub(crate) fn copyable_cover(cover: Option<Cover>) -> Option<Cover> {
+    cover.filter(|cover| !matches!(cover, Cover::File { .. }))
+}
+
+/// Whether a property can be set through the API.
+pub(crate) const fn writable(property: &PropertyData) -> bool {
+    !matches!(
+        property,
+        PropertyData::Button(_)
+            | PropertyData::CreatedBy(_)
+            | PropertyData::CreatedTime(_)
+            | PropertyData::Formula(_)
+            | PropertyData::LastEditedBy(_)
+            | PropertyData::LastEditedTime(_)
+            | PropertyData::Rollup(_)
+            | PropertyData::UniqueId(_)
+            | PropertyData::Verification(_)
+            | PropertyData::Unknown { .. }
+    )
+}
+
+/// The block without the fields the API fills in, and its children, leaving
+/// out blocks that can't be created by appending them.
+pub(crate) fn template_block(tree: &BlockTree) -> Option<BlockTree> {
+    match tree.block.data {
+        BlockData::ChildDatabase(_)
+        | BlockData::ChildPage(_)
+        | BlockData::Unsupported(_)
+        | BlockData::Unknown { .. } => None,
+        _ => Some(BlockTree {
+            block: Block::new(tree.block.data.clone()),
+            children: tree.children.iter().filter_map(template_block).collect(),
+        }),
+    }
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Retrieve a template
+    ///
+    /// Reads a page as a template, with its properties, icon, cover and
+    /// content. Properties that Notion computes, files hosted by Notion, and
+    /// child pages and databases are left out, as they can't be copied to a
+    /// new page.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Notion::retrieve_page`] and
+    /// [`Notion::retrieve_block_tree`].
+    pub async fn retrieve_template(&self, page_id: PageId) -> Result<Template> {
+        let page = self.retrieve_page(page_id, None).await?;
+        let trees = self
+            .retrieve_block_tree(BlockId::from(*page_id.into_inner()))
+            .await?;
+
+        Template::from_page(page, &trees)
+    }
+
+    /// # Create a page from a template
+    ///
+    /// Renders a template with variables, and creates a page with the result
+    /// under `parent`. The content is appended after the page is created, in
+    /// as many requests as it needs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Template::render`], [`Notion::create_page`] and
+    /// [`Notion::apply_plan`]. If appending the content fails, the page is
+    /// left with the content appended before it.
+    pub async fn create_page_from_template(
+        &self,
+        parent: ParentData,
+        template: &Template,
+        variables: &(impl Serialize + Sync),
+    ) -> Result<Page> {
+        let rendered = template.render(variables)?;
+
+        let page = self
+            .create_page(
+                Page::new()
+                    .parent(Some(parent))
+                    .properties(rendered.properties)
+                    .icon(rendered.icon)
+                    .cover(rendered.cover),
+            )
+            .await?;
+
+        if !rendered.children.is_empty() {
+            let page_id = page.id.context(Error::MissingPageId)?;
+
+            self.apply_plan(&Plan {
+                operations: vec![Operation::Append {
+                    parent: BlockId::from(*page_id.into_inner()),
+                    after: None,
+                    blocks: rendered.children,
+                }],
+            })
+            .await?;
+        }
+
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notion_model::objects::{
+        block::{BulletedListItem, Code, Equation, Paragraph, Toggle},
+        color::Color,
+        properties::Title,
+        rich_text::RichText,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn paragraph(rich_text: Vec<RichText>) -> Block {
+        Paragraph::new().rich_text(rich_text).build()
+    }
+
+    fn text(text: &str) -> Block {
+        paragraph(vec![RichText::new_text(text)])
+    }
+
+    fn bullet(text: &str) -> Block {
+        BulletedListItem::new()
+            .rich_text(vec![RichText::new_text(text)])
+            .build()
+    }
+
+    fn render(children: Vec<Block>, variables: Value) -> Result<Vec<Block>> {
+        Ok(Template::new()
+            .children(children)
+            .render(&variables)?
+            .children)
+    }
+
+    #[test]
+    fn placeholders() {
+        let rendered = render(
+            vec![paragraph(vec![
+                RichText::new_text("Owner: "),
+                RichText::new_text("{{ owner.name }}")
+                    .bold(true)
+                    .color(Color::Red),
+                RichText::new_text(" ({{count}} {{missing_ok}})"),
+            ])],
+            json!({ "owner": { "name": "Ada" }, "count": 3, "missing_ok": null }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            [paragraph(vec![
+                RichText::new_text("Owner: "),
+                RichText::new_text("Ada").bold(true).color(Color::Red),
+                RichText::new_text(" (3 )"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn code_and_equations() {
+        let code = Code::new()
+            .rich_text(vec![RichText::new_text(
+                "let s = format!(\"{{}}\", {{x}});",
+            )])
+            .caption(Some(vec![RichText::new_text("{{ language }} example")]));
+        let equation = Equation::new().expression("\\frac{{a}}{{b}}");
+        let inline = paragraph(vec![
+            RichText::new_text("Run "),
+            RichText::new_text("{{ command }}").code(true),
+            RichText::new_equation("x^{{2}}"),
+            RichText::new_text(" as {{ user }}"),
+        ]);
+
+        let rendered = render(
+            vec![code.clone().build(), equation.clone().build(), inline],
+            json!({ "language": "Rust", "user": "root" }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            [
+                code.caption(Some(vec![RichText::new_text("Rust example")]))
+                    .build(),
+                equation.build(),
+                paragraph(vec![
+                    RichText::new_text("Run "),
+                    RichText::new_text("{{ command }}").code(true),
+                    RichText::new_equation("x^{{2}}"),
+                    RichText::new_text(" as root"),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn properties() {
+        let template = Template::new().properties(HashMap::from([
+            Title::new("Incident {{id}}").build_with_name("title")
+        ]));
+
+        let rendered = template.render(&json!({ "id": 42 })).unwrap();
+
+        assert_eq!(
+            rendered.properties["title"].data,
+            Title::new("Incident 42").build()
+        );
+    }
+
+    #[test]
+    fn loops() {
+        let rendered = render(
+            vec![
+                text("Timeline"),
+                text("{{#each timeline}}"),
+                bullet("{{@index}}. {{time}} {{event}} ({{severity}})"),
+                text("{{/each}}"),
+                text("{{#each tags}}"),
+                bullet("{{this}}"),
+                text("{{/each}}"),
+            ],
+            json!({
+                "severity": "high",
+                "timeline": [
+                    { "time": "10:02", "event": "Alerts fire" },
+                    { "time": "10:15", "event": "Rolled back", "severity": "low" },
+                ],
+                "tags": ["api", "db"],
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            [
+                text("Timeline"),
+                bullet("0. 10:02 Alerts fire (high)"),
+                bullet("1. 10:15 Rolled back (low)"),
+                bullet("api"),
+                bullet("db"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_loops() {
+        let toggle = |text: &str, children: Vec<Block>| {
+            Toggle::new()
+                .rich_text(vec![RichText::new_text(text)])
+                .children(Some(children))
+                .build()
+        };
+
+        let rendered = render(
+            vec![
+                text("{{#each teams}}"),
+                toggle(
+                    "{{name}}",
+                    vec![
+                        text("{{#each members}}"),
+                        bullet("{{this}} of {{name}}"),
+                        text("{{/each}}"),
+                    ],
+                ),
+                text("{{/each}}"),
+            ],
+            json!({
+                "teams": [
+                    { "name": "API", "members": ["Ada", "Lin"] },
+                    { "name": "Web", "members": [] },
+                ],
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            [
+                toggle("API", vec![bullet("Ada of API"), bullet("Lin of API")]),
+                Toggle::new()
+                    .rich_text(vec![RichText::new_text("Web")])
+                    .build(),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |children, variables| render(children, variables).unwrap_err().to_string();
+
+        assert_eq!(
+            error(vec![text("{{name}}")], json!({})),
+            "no value for `{{name}}`"
+        );
+        assert_eq!(
+            error(vec![text("{{name}}")], json!({ "name": ["a"] })),
+            "`{{name}}` is a list or object, not text"
+        );
+        assert_eq!(
+            error(
+                vec![text("{{#each items}}"), text("x")],
+                json!({ "items": [] })
+            ),
+            "a `{{#each}}` that isn't closed with `{{/each}}`"
+        );
+        assert_eq!(
+            error(
+                vec![text("{{#each items}}"), text("{{/each}}")],
+                json!({ "items": "a" })
+            ),
+            "`{{#each items}}` over something that isn't a list"
+        );
+        assert_eq!(
+            error(vec![text("{{/each}}")], json!({})),
+            "`{{/each}}` without a `{{#each}}`"
+        );
+
+        // not a placeholder
+        assert_eq!(
+            render(vec![text("a {{ b")], json!({})).unwrap(),
+            [text("a {{ b")]
+        );
+    }
+
+    #[test]
+    fn from_file() {
+        let path = std::env::temp_dir().join("notion-template-from-file.json");
+        fs::write(
+            &path,
+            json!([{
+                "type": "paragraph",
+                "paragraph": {
+                    "rich_text": [{ "type": "text", "text": { "content": "Hi {{name}}" } }],
+                    "color": "default",
+                },
+            }])
+            .to_string(),
+        )
+        .unwrap();
+
+        let template = Template::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(template.children, [text("Hi {{name}}")]);
+        assert!(Template::from_file(&path).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! # HTTP transport
+//!
+//! [`Notion`] sends its requests through an [`HttpTransport`], which defaults
+//! to [`ReqwestTransport`]. Use [`Notion::with_transport`] to send them some
+//! other way, such as through an in-process fake, a different HTTP stack, or a
+//! wrapper around another transport that signs, audits or measures requests.
+//!
+//! Requests are complete when they reach the transport: the URL includes the
+//! query, and the headers include the API token and version.
+//!
+//! ```
+//! use anyhow::Result;
+//! use notion::transport::{HttpRequest, HttpResponse, HttpTransport, ReqwestTransport};
+//!
+//! /// Logs the method and URL of every request.
+//! #[derive(Debug)]
+//! struct Logged(ReqwestTransport);
+//!
+//! impl HttpTransport for Logged {
+//!     async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+//!         println!("{} {}", request.method, request.url);
+//!         self.0.send(request).await
+//!     }
+//! }
+//! ```
+//!
+//! [`Notion`]: crate::client::Notion
+//! [`Notion::with_transport`]: crate::client::Notion::with_transport
+
+use std::{fmt::Debug, future::Future};
+
+use anyhow::{Context, Result};
+pub use http::{HeaderMap, Method, StatusCode};
+use reqwest::Client;
+
+use crate::errors::Error;
+
+/// A request to the API.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// The absolute URL, including the query.
+    pub url: String,
+    pub headers: HeaderMap,
+    /// The body, if there is one. It is JSON, except for file uploads.
+    pub body: Option<Vec<u8>>,
+}
+
+/// A response from the API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// Sends HTTP requests for a [`Notion`](crate::client::Notion) client.
+///
+/// Only failures to get a response are errors. Responses with an error status
+/// are returned as is, and turned into a
+/// [`NotionApiError`](crate::errors::NotionApiError) by the client.
+pub trait HttpTransport: Debug + Send + Sync + 'static {
+    fn send(&self, request: HttpRequest) -> impl Future<Output = Result<HttpResponse>> + Send;
+}
+
+/// The default transport, using [`reqwest`].
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Result<Self> {
+        let client = Client::builder().build().context(Error::ClientBuild)?;
+
+        Ok(Self { client })
+    }
+
+    /// Uses an existing client, such as one with a proxy or custom timeouts.
+    pub const fn from_client(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let res = builder.send().await.context(Error::RequestSend)?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let body = res.text().await.context(Error::ParseResponse)?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
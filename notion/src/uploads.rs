@@ -0,0 +1,435 @@
+//! # File uploads
+//!
+//! [`Notion::upload_file`] uploads a file with the File Upload API, sending
+//! it in a single part if it's small enough, or in parts otherwise. Attach the
+//! returned upload by its ID, such as with
+//! [`Image::file_upload`](crate::model::objects::block::Image::file_upload).
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use notion::{client::Notion, model::objects::block::Image};
+//!
+//! let notion = Notion::new("secret_token")?;
+//!
+//! let upload = notion
+//!     .upload_file("screenshots/home.png", "image/png")
+//!     .await?;
+//! let image = Image::file_upload(upload.id).build();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use notion_model::{
+    ids::FileUploadId,
+    objects::file_upload::{CreateFileUpload, FileUpload},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    client::{Notion, SendAndGetText},
+    errors::{Error, NotionApiError},
+    result_types,
+    transport::HttpTransport,
+};
+
+/// The largest file that is sent in a single part.
+pub const SINGLE_PART_LIMIT: u64 = 20 * 1024 * 1024;
+
+/// The size of each part of a multi-part upload, except for the last one.
+pub const PART_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A file to upload.
+pub enum UploadSource {
+    /// A file on disk. Its name is the name of the upload.
+    Path(PathBuf),
+    /// Any reader, with the name of the upload, and the number of bytes to
+    /// read from it.
+    Reader {
+        filename: String,
+        len: u64,
+        reader: Box<dyn Read + Send>,
+    },
+}
+
+impl UploadSource {
+    pub fn reader(filename: &str, len: u64, reader: impl Read + Send + 'static) -> Self {
+        Self::Reader {
+            filename: filename.to_owned(),
+            len,
+            reader: Box::new(reader),
+        }
+    }
+}
+
+impl fmt::Debug for UploadSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Self::Reader { filename, len, .. } => f
+                .debug_struct("Reader")
+                .field("filename", filename)
+                .field("len", len)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl From<PathBuf> for UploadSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for UploadSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_owned())
+    }
+}
+
+impl From<&str> for UploadSource {
+    fn from(path: &str) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// # Upload a file
+    ///
+    /// Uploads a file, and returns the file upload, which is ready to be
+    /// attached.
+    ///
+    /// Files of up to [`SINGLE_PART_LIMIT`] are sent in a single part. Larger
+    /// files are sent in parts of [`PART_SIZE`], which are read one at a time.
+    /// Reading the file blocks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or is shorter than its
+    /// given length.
+    ///
+    /// Returns a 400 HTTP response if the file is too large for the workspace,
+    /// or its type isn't supported.
+    pub async fn upload_file(
+        &self,
+        source: impl Into<UploadSource>,
+        content_type: &str,
+    ) -> Result<FileUpload> {
+        let (filename, len, mut reader) = match source.into() {
+            UploadSource::Path(path) => {
+                let file = File::open(&path).context(Error::UploadRead)?;
+                let len = file.metadata().context(Error::UploadRead)?.len();
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                (filename, len, Box::new(file) as Box<dyn Read + Send>)
+            },
+            UploadSource::Reader {
+                filename,
+                len,
+                reader,
+            } => (filename, len, reader),
+        };
+
+        if len <= SINGLE_PART_LIMIT {
+            let upload = self
+                .create_file_upload(
+                    CreateFileUpload::new()
+                        .filename(Some(filename.clone()))
+                        .content_type(Some(content_type.to_owned())),
+                )
+                .await?;
+
+            let contents = read_part(&mut reader, len)?;
+
+            return self
+                .send_file_upload(upload.id, &filename, content_type, None, contents)
+                .await;
+        }
+
+        let number_of_parts =
+            u32::try_from((len + PART_SIZE - 1) / PART_SIZE).context(Error::UploadRead)?;
+
+        let upload = self
+            .create_file_upload(
+                CreateFileUpload::multi_part(number_of_parts)
+                    .filename(Some(filename.clone()))
+                    .content_type(Some(content_type.to_owned())),
+            )
+            .await?;
+
+        for part_number in 1..=number_of_parts {
+            let size = PART_SIZE.min(len - u64::from(part_number - 1) * PART_SIZE);
+            let contents = read_part(&mut reader, size)?;
+
+            self.send_file_upload(
+                upload.id,
+                &filename,
+                content_type,
+                Some(part_number),
+                contents,
+            )
+            .await?;
+        }
+
+        self.complete_file_upload(upload.id).await
+    }
+
+    /// # Create a file upload
+    ///
+    /// Starts a file upload, whose contents are then sent with
+    /// [`Notion::send_file_upload`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if the request is invalid, such as a
+    /// multi-part upload without a number of parts.
+    pub async fn create_file_upload(&self, request: CreateFileUpload) -> Result<FileUpload> {
+        let text = self
+            .api_post("file_uploads")
+            .json(&request)
+            .send_and_get_text()
+            .await?;
+
+        self.file_upload_response(&text, "create_file_upload")
+    }
+
+    /// # Send a file upload
+    ///
+    /// Sends the contents of a single-part upload, or one part of a
+    /// multi-part upload. Parts are numbered from 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if the upload isn't pending, or the part is
+    /// too large.
+    ///
+    /// Returns a 404 HTTP response if the upload doesn't exist.
+    pub async fn send_file_upload(
+        &self,
+        file_upload_id: FileUploadId,
+        filename: &str,
+        content_type: &str,
+        part_number: Option<u32>,
+        contents: Vec<u8>,
+    ) -> Result<FileUpload> {
+        let mut form = Multipart::new();
+
+        if let Some(part_number) = part_number {
+            form.field("part_number", &part_number.to_string());
+        }
+
+        form.file("file", filename, content_type, contents);
+
+        let (body, content_type) = form.finish();
+
+        let text = self
+            .api_post(&format!("file_uploads/{file_upload_id}/send"))
+            .body(body, &content_type)
+            .send_and_get_text()
+            .await?;
+
+        self.file_upload_response(&text, "send_file_upload")
+    }
+
+    /// # Complete a file upload
+    ///
+    /// Completes a multi-part upload once every part has been sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 HTTP response if a part is missing.
+    ///
+    /// Returns a 404 HTTP response if the upload doesn't exist.
+    pub async fn complete_file_upload(&self, file_upload_id: FileUploadId) -> Result<FileUpload> {
+        let text = self
+            .api_post(&format!("file_uploads/{file_upload_id}/complete"))
+            .json(&serde_json::json!({}))
+            .send_and_get_text()
+            .await?;
+
+        self.file_upload_response(&text, "complete_file_upload")
+    }
+
+    /// # Retrieve a file upload
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the upload doesn't exist.
+    pub async fn retrieve_file_upload(&self, file_upload_id: FileUploadId) -> Result<FileUpload> {
+        let text = self
+            .api_get(&format!("file_uploads/{file_upload_id}"))
+            .send_and_get_text()
+            .await?;
+
+        self.file_upload_response(&text, "retrieve_file_upload")
+    }
+
+    fn file_upload_response(&self, text: &str, endpoint: &'static str) -> Result<FileUpload> {
+        match self.parse::<result_types::FileUpload>(text, "FileUpload", endpoint)? {
+            result_types::FileUpload::FileUpload(upload) => Ok(upload),
+            result_types::FileUpload::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+}
+
+/// Reads exactly `len` bytes.
+fn read_part(reader: &mut dyn Read, len: u64) -> Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut contents)
+        .context(Error::UploadRead)?;
+
+    if contents.len() as u64 != len {
+        anyhow::bail!(Error::UploadRead);
+    }
+
+    Ok(contents)
+}
+
+/// A `multipart/form-data` body.
+///
+/// The boundary is a hash of the contents rather than random, so that
+/// requests can be matched by cassettes. SHA-256 is used as it doesn't change
+/// between Rust releases, unlike the hasher of the standard library.
+struct Multipart {
+    parts: Vec<(String, Vec<u8>)>,
+}
+
+impl Multipart {
+    const fn new() -> Self {
+        Self { parts: Vec::new() }
+    }
+
+    fn field(&mut self, name: &str, value: &str) {
+        self.parts.push((
+            format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n"),
+            value.as_bytes().to_vec(),
+        ));
+    }
+
+    fn file(&mut self, name: &str, filename: &str, content_type: &str, contents: Vec<u8>) {
+        let filename = filename
+            .replace('"', "%22")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A");
+
+        self.parts.push((
+            format!(
+                "Content-Disposition: form-data; name=\"{name}\"; \
+                 filename=\"{filename}\"\r\nContent-Type: {content_type}\r\n\r\n"
+            ),
+            contents,
+        ));
+    }
+
+    /// Returns the body and its content type.
+    fn finish(self) -> (Vec<u8>, String) {
+        let boundary = self.boundary();
+
+        let mut body = Vec::new();
+
+        for (headers, contents) in self.parts {
+            body.extend_from_slice(format!("--{boundary}\r\n{headers}").as_bytes());
+            body.extend_from_slice(&contents);
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+        (body, format!("multipart/form-data; boundary={boundary}"))
+    }
+
+    /// A boundary that isn't in any of the parts.
+    fn boundary(&self) -> String {
+        (0_u32..)
+            .map(|attempt| {
+                let mut hasher = Sha256::new();
+
+                for (headers, contents) in &self.parts {
+                    hasher.update((headers.len() as u64).to_be_bytes());
+                    hasher.update(headers);
+                    hasher.update((contents.len() as u64).to_be_bytes());
+                    hasher.update(contents);
+                }
+                hasher.update(attempt.to_be_bytes());
+
+                format!("notion-{}", hex::encode(&hasher.finalize()[..8]))
+            })
+            .find(|boundary| {
+                !self.parts.iter().any(|(headers, contents)| {
+                    contains(headers.as_bytes(), boundary.as_bytes())
+                        || contains(contents, boundary.as_bytes())
+                })
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart() {
+        let mut form = Multipart::new();
+        form.field("part_number", "2");
+        form.file("file", "a \"b\".txt", "text/plain", b"kale".to_vec());
+
+        let (body, content_type) = form.finish();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            format!(
+                "--{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"part_number\"\r\n\r\n\
+                 2\r\n\
+                 --{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"file\"; filename=\"a %22b%22.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\n\
+                 kale\r\n\
+                 --{boundary}--\r\n"
+            )
+        );
+    }
+
+    #[test]
+    fn boundary_is_stable() {
+        let form = || {
+            let mut form = Multipart::new();
+            form.file("file", "a.bin", "application/octet-stream", vec![1, 2, 3]);
+            form
+        };
+
+        assert_eq!(form().finish().1, form().finish().1);
+        assert_eq!(
+            form().finish().1,
+            "multipart/form-data; boundary=notion-78d3fe118e486f19"
+        );
+    }
+
+    #[test]
+    fn short_read() {
+        assert!(read_part(&mut &b"abc"[..], 4).is_err());
+        assert_eq!(read_part(&mut &b"abcd"[..], 3).unwrap(), b"abc");
+    }
+}
@@ -0,0 +1,514 @@
+//! # Notion URLs
+//!
+//! [`NotionUrl`] parses the URLs that Notion shows for pages, databases and
+//! blocks, and builds canonical ones from IDs.
+//!
+//! Parsing accepts:
+//!
+//! - `notion.so` and `notion.com` URLs, with or without `www.` and the scheme,
+//!   and public `*.notion.site` URLs
+//! - `notion://` deep links
+//! - page titles before the ID, like `My-Page-67ace61a7fd24ab78e892b1dc9b252e4`
+//! - IDs with or without dashes
+//! - database views, like `?v=<view id>`
+//! - peeked pages, like `?p=<page id>`, which open a page on top of a database
+//! - block anchors, like `#<block id>`
+//!
+//! Other query parameters, such as `pvs`, are ignored.
+
+use std::{fmt, str::FromStr};
+
+use notion_model::ids::{BlockId, DatabaseId, PageId, ViewId, WorkspaceId};
+use reqwest::Url;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The base of canonical URLs.
+const BASE_URL: &str = "https://www.notion.so";
+
+/// The hosts of Notion URLs, other than `*.notion.site`.
+const HOSTS: [&str; 4] = ["notion.so", "www.notion.so", "notion.com", "www.notion.com"];
+
+/// What a Notion URL points to.
+///
+/// The workspace is the domain of the workspace, like `erics118` in
+/// `notion.so/erics118/...` or in `erics118.notion.site/...`, if the URL has
+/// one. It isn't needed to open the URL.
+///
+/// A URL to a database without a view can't be told apart from a URL to a
+/// page, so it is parsed as a page.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NotionUrl {
+    Page {
+        workspace: Option<WorkspaceId>,
+        page_id: PageId,
+    },
+    Database {
+        workspace: Option<WorkspaceId>,
+        database_id: DatabaseId,
+        view_id: Option<ViewId>,
+    },
+    /// A block on a page.
+    Block {
+        workspace: Option<WorkspaceId>,
+        page_id: PageId,
+        block_id: BlockId,
+    },
+    /// The home of a workspace.
+    Workspace { workspace: WorkspaceId },
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseNotionUrlError {
+    #[error("invalid URL")]
+    InvalidUrl,
+    #[error("not a Notion URL")]
+    NotNotion,
+    #[error("missing ID in Notion URL")]
+    MissingId,
+    #[error("invalid ID in Notion URL")]
+    InvalidId,
+}
+
+impl NotionUrl {
+    /// The URL of a block on a page.
+    pub const fn block(page_id: PageId, block_id: BlockId) -> Self {
+        Self::Block {
+            workspace: None,
+            page_id,
+            block_id,
+        }
+    }
+
+    /// The URL of a view of a database.
+    pub const fn database_view(database_id: DatabaseId, view_id: ViewId) -> Self {
+        Self::Database {
+            workspace: None,
+            database_id,
+            view_id: Some(view_id),
+        }
+    }
+
+    /// Sets the workspace, which is included in the generated URL.
+    pub fn workspace(mut self, workspace: Option<WorkspaceId>) -> Self {
+        match &mut self {
+            Self::Page { workspace: w, .. }
+            | Self::Database { workspace: w, .. }
+            | Self::Block { workspace: w, .. } => *w = workspace,
+            Self::Workspace { workspace: w } => {
+                if let Some(workspace) = workspace {
+                    *w = workspace;
+                }
+            },
+        }
+        self
+    }
+
+    /// The domain of the workspace, if the URL has one.
+    pub const fn workspace_id(&self) -> Option<&WorkspaceId> {
+        match self {
+            Self::Page { workspace, .. }
+            | Self::Database { workspace, .. }
+            | Self::Block { workspace, .. } => workspace.as_ref(),
+            Self::Workspace { workspace } => Some(workspace),
+        }
+    }
+
+    /// The ID of the page or database the URL opens, or the page the block is
+    /// on.
+    pub fn object_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Page { page_id, .. } | Self::Block { page_id, .. } => Some(*page_id.into_inner()),
+            Self::Database { database_id, .. } => Some(*database_id.into_inner()),
+            Self::Workspace { .. } => None,
+        }
+    }
+}
+
+impl From<PageId> for NotionUrl {
+    fn from(page_id: PageId) -> Self {
+        Self::Page {
+            workspace: None,
+            page_id,
+        }
+    }
+}
+
+impl From<DatabaseId> for NotionUrl {
+    fn from(database_id: DatabaseId) -> Self {
+        Self::Database {
+            workspace: None,
+            database_id,
+            view_id: None,
+        }
+    }
+}
+
+impl FromStr for NotionUrl {
+    type Err = ParseNotionUrlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let url = match s.contains("://") {
+            true => Url::parse(s),
+            false => Url::parse(&format!("https://{s}")),
+        }
+        .map_err(|_| ParseNotionUrlError::InvalidUrl)?;
+
+        let host = url.host_str().ok_or(ParseNotionUrlError::NotNotion)?;
+
+        let site = host
+            .strip_suffix(".notion.site")
+            .filter(|site| !site.is_empty() && !site.contains('.'));
+
+        if site.is_none() && !HOSTS.contains(&host) {
+            return Err(ParseNotionUrlError::NotNotion);
+        }
+
+        let mut segments = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        let id = segments.last().and_then(|last| id_of(last));
+
+        if id.is_some() {
+            segments.pop();
+        }
+
+        let workspace = match (site, segments.as_slice()) {
+            (Some(site), []) => Some(WorkspaceId::from_str_unchecked(site)),
+            (None, [workspace]) => Some(WorkspaceId::from_str_unchecked(workspace)),
+            (_, []) => None,
+            _ => return Err(ParseNotionUrlError::InvalidId),
+        };
+
+        let Some(id) = id else {
+            return match (workspace, url.fragment()) {
+                (Some(workspace), None) => Ok(Self::Workspace { workspace }),
+                _ => Err(ParseNotionUrlError::MissingId),
+            };
+        };
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+                .filter(|value| !value.is_empty())
+        };
+
+        let parse = |id: &str| id_of(id).ok_or(ParseNotionUrlError::InvalidId);
+
+        if let Some(peeked) = param("p") {
+            return Ok(Self::Page {
+                workspace,
+                page_id: PageId::from(parse(&peeked)?),
+            });
+        }
+
+        if let Some(block) = url.fragment().filter(|fragment| !fragment.is_empty()) {
+            return Ok(Self::Block {
+                workspace,
+                page_id: PageId::from(id),
+                block_id: BlockId::from(parse(block)?),
+            });
+        }
+
+        if let Some(view) = param("v") {
+            return Ok(Self::Database {
+                workspace,
+                database_id: DatabaseId::from(id),
+                view_id: Some(ViewId::from(parse(&view)?)),
+            });
+        }
+
+        Ok(Self::Page {
+            workspace,
+            page_id: PageId::from(id),
+        })
+    }
+}
+
+impl fmt::Display for NotionUrl {
+    /// Formats the canonical URL, with IDs without dashes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(BASE_URL)?;
+
+        if let Some(workspace) = self.workspace_id() {
+            write!(f, "/{workspace}")?;
+        }
+
+        match self {
+            Self::Page { page_id, .. } => write!(f, "/{}", page_id.into_inner().simple()),
+            Self::Database {
+                database_id,
+                view_id,
+                ..
+            } => {
+                write!(f, "/{}", database_id.into_inner().simple())?;
+
+                if let Some(view_id) = view_id {
+                    write!(f, "?v={}", view_id.into_inner().simple())?;
+                }
+
+                Ok(())
+            },
+            Self::Block {
+                page_id, block_id, ..
+            } => write!(
+                f,
+                "/{}#{}",
+                page_id.into_inner().simple(),
+                block_id.into_inner().simple()
+            ),
+            Self::Workspace { .. } => Ok(()),
+        }
+    }
+}
+
+/// The ID at the end of a path segment, after the page title if there is one.
+fn id_of(segment: &str) -> Option<Uuid> {
+    // a title of three characters also makes a segment of 36 characters, such
+    // as `API-67ace61a7fd24ab78e892b1dc9b252e4`
+    if segment.len() == 36 {
+        if let Ok(id) = Uuid::try_parse(segment) {
+            return Some(id);
+        }
+    }
+
+    let start = segment.len().checked_sub(32)?;
+    let (title, id) = (segment.get(..start)?, segment.get(start..)?);
+
+    if !(title.is_empty() || title.ends_with('-')) || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Uuid::try_parse(id).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "67ace61a7fd24ab78e892b1dc9b252e4";
+    const BLOCK: &str = "6e9612c81c7d4356ba9153eab009e6f4";
+    const VIEW: &str = "0f6e8ad3e4c84c5a9e5a0f2f1b3ce2a1";
+
+    fn page(workspace: Option<&str>) -> NotionUrl {
+        NotionUrl::Page {
+            workspace: workspace.map(WorkspaceId::from_str_unchecked),
+            page_id: PageId::from_str_unchecked(PAGE),
+        }
+    }
+
+    fn block(workspace: Option<&str>) -> NotionUrl {
+        NotionUrl::Block {
+            workspace: workspace.map(WorkspaceId::from_str_unchecked),
+            page_id: PageId::from_str_unchecked(PAGE),
+            block_id: BlockId::from_str_unchecked(BLOCK),
+        }
+    }
+
+    fn database(workspace: Option<&str>) -> NotionUrl {
+        NotionUrl::Database {
+            workspace: workspace.map(WorkspaceId::from_str_unchecked),
+            database_id: DatabaseId::from_str_unchecked(PAGE),
+            view_id: Some(ViewId::from_str_unchecked(VIEW)),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let cases = [
+            (
+                format!("https://www.notion.so/erics118/{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (
+                format!("www.notion.so/erics118/{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (
+                format!("https://notion.so/erics118/{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (format!("notion.so/erics118/{PAGE}"), page(Some("erics118"))),
+            (
+                format!("https://www.notion.com/erics118/{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (format!("https://www.notion.so/{PAGE}"), page(None)),
+            (format!("https://www.notion.so/{PAGE}/"), page(None)),
+            (
+                format!("https://www.notion.so/erics118/{PAGE}?pvs=4"),
+                page(Some("erics118")),
+            ),
+            (
+                format!("https://www.notion.so/erics118/My-Page-Title-{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (format!("https://www.notion.so/Title-{PAGE}"), page(None)),
+            (format!("https://www.notion.so/{PAGE}-{PAGE}"), page(None)),
+            (format!("https://www.notion.so/API-{PAGE}"), page(None)),
+            (
+                format!("https://www.notion.so/ws/FAQ-{PAGE}"),
+                page(Some("ws")),
+            ),
+            (
+                "https://www.notion.so/erics118/67ace61a-7fd2-4ab7-8e89-2b1dc9b252e4".to_owned(),
+                page(Some("erics118")),
+            ),
+            (
+                format!("https://erics118.notion.site/Title-{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (
+                format!("notion://www.notion.so/erics118/{PAGE}"),
+                page(Some("erics118")),
+            ),
+            (format!("  https://www.notion.so/{PAGE}  "), page(None)),
+            (
+                format!("https://www.notion.so/erics118/{PAGE}?pvs=4#{BLOCK}"),
+                block(Some("erics118")),
+            ),
+            (format!("notion.so/Title-{PAGE}#{BLOCK}"), block(None)),
+            (
+                format!("https://www.notion.so/erics118/{PAGE}?v={VIEW}"),
+                database(Some("erics118")),
+            ),
+            (
+                format!("https://www.notion.so/{PAGE}?v={VIEW}&pvs=4"),
+                database(None),
+            ),
+            (
+                format!("https://www.notion.so/{BLOCK}?v={VIEW}&p={PAGE}&pm=s"),
+                page(None),
+            ),
+            (
+                "https://www.notion.so/erics118".to_owned(),
+                NotionUrl::Workspace {
+                    workspace: WorkspaceId::from_str_unchecked("erics118"),
+                },
+            ),
+            (
+                "https://erics118.notion.site".to_owned(),
+                NotionUrl::Workspace {
+                    workspace: WorkspaceId::from_str_unchecked("erics118"),
+                },
+            ),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(url.parse::<NotionUrl>(), Ok(expected), "{url}");
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let cases = [
+            ("", ParseNotionUrlError::InvalidUrl),
+            ("https://", ParseNotionUrlError::InvalidUrl),
+            (
+                "https://example.com/67ace61a7fd24ab78e892b1dc9b252e4",
+                ParseNotionUrlError::NotNotion,
+            ),
+            (
+                "https://notion.so.example.com/67ace61a7fd24ab78e892b1dc9b252e4",
+                ParseNotionUrlError::NotNotion,
+            ),
+            ("https://notion.site/Title", ParseNotionUrlError::NotNotion),
+            ("https://www.notion.so", ParseNotionUrlError::MissingId),
+            (
+                "https://www.notion.so/erics118#anchor",
+                ParseNotionUrlError::MissingId,
+            ),
+            (
+                "https://www.notion.so/erics118/Title",
+                ParseNotionUrlError::InvalidId,
+            ),
+            (
+                "https://www.notion.so/a/b/67ace61a7fd24ab78e892b1dc9b252e4",
+                ParseNotionUrlError::InvalidId,
+            ),
+            (
+                "https://www.notion.so/erics118/Title67ace61a7fd24ab78e892b1dc9b252e4",
+                ParseNotionUrlError::InvalidId,
+            ),
+            (
+                "https://www.notion.so/67ace61a7fd24ab78e892b1dc9b252e4#nope",
+                ParseNotionUrlError::InvalidId,
+            ),
+            (
+                "https://www.notion.so/67ace61a7fd24ab78e892b1dc9b252e4?v=nope",
+                ParseNotionUrlError::InvalidId,
+            ),
+            (
+                "https://www.notion.so/67ace61a7fd24ab78e892b1dc9b252e4?p=nope",
+                ParseNotionUrlError::InvalidId,
+            ),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(url.parse::<NotionUrl>(), Err(expected), "{url}");
+        }
+    }
+
+    #[test]
+    fn generate() {
+        let page_id = PageId::from_str_unchecked(PAGE);
+        let block_id = BlockId::from_str_unchecked(BLOCK);
+        let database_id = DatabaseId::from_str_unchecked(PAGE);
+        let view_id = ViewId::from_str_unchecked(VIEW);
+
+        let cases = [
+            (
+                NotionUrl::from(page_id),
+                format!("https://www.notion.so/{PAGE}"),
+            ),
+            (
+                NotionUrl::from(page_id)
+                    .workspace(Some(WorkspaceId::from_str_unchecked("erics118"))),
+                format!("https://www.notion.so/erics118/{PAGE}"),
+            ),
+            (
+                NotionUrl::block(page_id, block_id),
+                format!("https://www.notion.so/{PAGE}#{BLOCK}"),
+            ),
+            (
+                NotionUrl::from(database_id),
+                format!("https://www.notion.so/{PAGE}"),
+            ),
+            (
+                NotionUrl::database_view(database_id, view_id),
+                format!("https://www.notion.so/{PAGE}?v={VIEW}"),
+            ),
+            (
+                NotionUrl::Workspace {
+                    workspace: WorkspaceId::from_str_unchecked("erics118"),
+                },
+                "https://www.notion.so/erics118".to_owned(),
+            ),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(url.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let urls = [
+            page(None),
+            page(Some("erics118")),
+            block(Some("erics118")),
+            database(None),
+        ];
+
+        for url in urls {
+            assert_eq!(url.to_string().parse::<NotionUrl>(), Ok(url));
+        }
+    }
+}
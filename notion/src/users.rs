@@ -1 +1,128 @@
+use anyhow::Result;
+use notion_model::{ids::UserId, objects::user::User};
 
+use crate::{
+    client::{Notion, SendAndGetText},
+    errors::NotionApiError,
+    model::pagination::List,
+    result_types,
+    transport::HttpTransport,
+};
+
+impl<T: HttpTransport> Notion<T> {
+    /// # List all users
+    ///
+    /// Returns a paginated list of Users for the workspace. The response may
+    /// contain fewer than page_size of results.
+    ///
+    /// Guests are not included in the response.
+    ///
+    /// See Pagination for details about how to use a cursor to iterate through
+    /// the list. Pass the `next_cursor` of the previous response as
+    /// `start_cursor` to retrieve the next page of users.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have user information
+    /// capabilities. Attempting to call this API without user information
+    /// capabilities will return an HTTP response with a 403 status code. For
+    /// more information on integration capabilities, see the capabilities
+    /// guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn list_users(&self, start_cursor: Option<String>) -> Result<List<User>> {
+        let query = start_cursor
+            .iter()
+            .map(|cursor| ("start_cursor", cursor))
+            .collect::<Vec<_>>();
+
+        let text = self
+            .api_get("users")
+            .query(&query)
+            .send_and_get_text()
+            .await?;
+
+        let res = self.parse::<result_types::List<User>>(&text, "List<User>", "list_users")?;
+
+        match res {
+            result_types::List::List(user_list) => Ok(user_list),
+            result_types::List::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// Lists the users of the workspace, following `next_cursor` until every
+    /// user has been retrieved.
+    pub async fn list_users_all(&self) -> Result<Vec<User>> {
+        let mut users = Vec::new();
+        let mut start_cursor = None;
+
+        loop {
+            let list = self.list_users(start_cursor).await?;
+            users.extend(list.results);
+
+            match list.next_cursor {
+                Some(cursor) if list.has_more => start_cursor = Some(cursor),
+                _ => return Ok(users),
+            }
+        }
+    }
+
+    /// # Retrieve a user
+    ///
+    /// Retrieves a User using the ID specified.
+    ///
+    /// # 📘 Integration capabilities
+    ///
+    /// This endpoint requires an integration to have user information
+    /// capabilities. Attempting to call this API without user information
+    /// capabilities will return an HTTP response with a 403 status code. For
+    /// more information on integration capabilities, see the capabilities
+    /// guide.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 404 HTTP response if the user doesn't exist, or if the
+    /// integration doesn't have access to the user.
+    ///
+    /// Returns a 400 or 429 HTTP response if the request exceeds the request
+    /// limits.
+    pub async fn retrieve_user(&self, user_id: UserId) -> Result<User> {
+        let text = self
+            .api_get(&format!("users/{user_id}"))
+            .send_and_get_text()
+            .await?;
+
+        let res = self.parse::<result_types::User>(&text, "User", "retrieve_user")?;
+
+        match res {
+            result_types::User::User(user) => Ok(user),
+            result_types::User::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+
+    /// # Retrieve your token's bot user
+    ///
+    /// Retrieves the bot User associated with the API token provided in the
+    /// authorization header. The bot will have an owner field with information
+    /// about the person who authorized the integration.
+    ///
+    /// This endpoint doesn't need any capabilities, so it can be used to check
+    /// that a token is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns a 401 HTTP response if the token is invalid.
+    pub async fn retrieve_bot_user(&self) -> Result<User> {
+        let text = self.api_get("users/me").send_and_get_text().await?;
+
+        let res = self.parse::<result_types::User>(&text, "User", "retrieve_bot_user")?;
+
+        match res {
+            result_types::User::User(user) => Ok(user),
+            result_types::User::Error(e) => anyhow::bail!(NotionApiError::from(e)),
+        }
+    }
+}
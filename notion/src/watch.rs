@@ -0,0 +1,523 @@
+//! Watching a database for changes by polling it, for workspaces where
+//! [webhooks](crate::webhooks) aren't available.
+//!
+//! Every poll queries the pages whose `last_edited_time` is on or after the
+//! latest one seen so far, and compares their property values to a snapshot
+//! of the previous poll:
+//!
+//! - a page that isn't in the snapshot is [`WatchEvent::Created`]
+//! - a page whose `last_edited_time` or property values changed is
+//!   [`WatchEvent::Updated`], with the names of the properties whose values
+//!   changed. The list is empty when only the content of the page changed.
+//!
+//! Pages that are archived are not returned when querying a database, so only
+//! a full listing notices them, every [`WatchOptions::full_every`] polls. The
+//! pages of the snapshot that are missing from it are
+//! [`WatchEvent::Archived`].
+//!
+//! The first poll only takes a snapshot: the pages that already exist don't
+//! produce events.
+//!
+//! # Checkpoints
+//!
+//! With [`WatchOptions::checkpoint`], the snapshot is saved to a JSON file
+//! once every event of a poll was taken from the stream, and loaded when the
+//! watch starts, so a restarted process resumes where it stopped. Events are
+//! delivered at least once: the events of a poll whose snapshot wasn't saved
+//! are produced again.
+//!
+//! ```no_run
+//! # async fn run(notion: notion::client::Notion) -> anyhow::Result<()> {
+//! use std::time::Duration;
+//!
+//! use futures::StreamExt;
+//! use notion::watch::{WatchEvent, WatchOptions};
+//!
+//! let database_id = "1a2b3c4d-0000-4000-8000-000000000001".parse()?;
+//! let options = WatchOptions::new().checkpoint(Some("tasks.json".into()));
+//!
+//! let events = notion.watch_database(database_id, Duration::from_secs(30), options);
+//! futures::pin_mut!(events);
+//!
+//! while let Some(event) = events.next().await {
+//!     if let WatchEvent::Updated { changed_properties, .. } = event? {
+//!         println!("changed: {changed_properties:?}");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fs,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use futures::Stream;
+use notion_model::{
+    ids::{DatabaseId, PageId},
+    objects::{date::DateOrDateTime, page::Page},
+    query::{DatabaseQuery, Sort, SortDirection, SortTimestamp},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    client::Notion,
+    errors::{Error, NotionApiError},
+    transport::HttpTransport,
+};
+
+/// A change to a page of a watched database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// A page was added to the database.
+    Created(Box<Page>),
+    /// A page was edited.
+    Updated {
+        page: Box<Page>,
+        /// The names of the properties whose values changed, sorted.
+        changed_properties: Vec<String>,
+    },
+    /// A page was archived, or removed from the database.
+    Archived(PageId),
+}
+
+/// How to watch a database.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// A file to save the snapshot to after every poll, and to resume from.
+    pub checkpoint: Option<PathBuf>,
+    /// Every how many polls the whole database is listed, to notice archived
+    /// pages. `0` never lists it again after the first poll.
+    pub full_every: u32,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            checkpoint: None,
+            full_every: 10,
+        }
+    }
+}
+
+impl WatchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn checkpoint(mut self, checkpoint: Option<PathBuf>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    pub const fn full_every(mut self, full_every: u32) -> Self {
+        self.full_every = full_every;
+        self
+    }
+}
+
+/// What a watch remembers between polls, and saves to its checkpoint file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Snapshot {
+    /// The latest `last_edited_time` of the pages of the database.
+    last_edited_time: Option<DateTime<FixedOffset>>,
+    pages: BTreeMap<PageId, PageSnapshot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct PageSnapshot {
+    last_edited_time: Option<DateOrDateTime>,
+    /// The value of every property, by name.
+    properties: BTreeMap<String, Value>,
+}
+
+impl PageSnapshot {
+    fn of(page: &Page) -> Result<Self> {
+        let properties = page
+            .properties
+            .iter()
+            .map(|(name, property)| Ok((name.clone(), serde_json::to_value(&property.data)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            last_edited_time: page.last_edited_time,
+            properties,
+        })
+    }
+
+    /// The names of the properties whose values differ, including properties
+    /// that were added or removed.
+    fn changed_properties(&self, other: &Self) -> Vec<String> {
+        let mut names = self
+            .properties
+            .iter()
+            .filter(|(name, value)| other.properties.get(*name) != Some(value))
+            .map(|(name, _)| name.clone())
+            .chain(
+                other
+                    .properties
+                    .keys()
+                    .filter(|name| !self.properties.contains_key(*name))
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names
+    }
+}
+
+impl Snapshot {
+    fn load(options: &WatchOptions) -> Result<Option<Self>> {
+        let Some(path) = &options.checkpoint else {
+            return Ok(None);
+        };
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let text = fs::read_to_string(path).context(Error::CheckpointRead)?;
+
+        serde_json::from_str(&text)
+            .context(Error::CheckpointRead)
+            .map(Some)
+    }
+
+    fn save(&self, options: &WatchOptions) -> Result<()> {
+        let Some(path) = &options.checkpoint else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).context(Error::CheckpointWrite)?;
+        }
+
+        // write next to the checkpoint and rename over it, so that a process
+        // stopped while writing doesn't leave half a file
+        let temp = path.with_extension("tmp");
+        let text = serde_json::to_string(self).context(Error::CheckpointWrite)?;
+
+        fs::write(&temp, text).context(Error::CheckpointWrite)?;
+        fs::rename(&temp, path).context(Error::CheckpointWrite)
+    }
+
+    /// Applies the pages returned by a poll, returning the events. When `full`
+    /// is set, `pages` is every page of the database.
+    fn apply(&mut self, pages: Vec<Page>, full: bool) -> Result<Vec<WatchEvent>> {
+        let mut events = Vec::new();
+        let mut seen = HashSet::new();
+
+        for page in pages {
+            let page_id = page.id.context(Error::MissingPageId)?;
+            seen.insert(page_id);
+
+            if let Some(DateOrDateTime::DateTime(time)) = page.last_edited_time {
+                self.last_edited_time = self.last_edited_time.max(Some(time));
+            }
+
+            if page.archived.unwrap_or_default() {
+                if self.pages.remove(&page_id).is_some() {
+                    events.push(WatchEvent::Archived(page_id));
+                }
+                continue;
+            }
+
+            let snapshot = PageSnapshot::of(&page)?;
+
+            let Some(previous) = self.pages.insert(page_id, snapshot.clone()) else {
+                events.push(WatchEvent::Created(Box::new(page)));
+                continue;
+            };
+
+            // `last_edited_time` is rounded to the minute, so edits within the
+            // same minute only show in the values
+            let changed_properties = previous.changed_properties(&snapshot);
+
+            if previous.last_edited_time != snapshot.last_edited_time
+                || !changed_properties.is_empty()
+            {
+                events.push(WatchEvent::Updated {
+                    changed_properties,
+                    page: Box::new(page),
+                });
+            }
+        }
+
+        if full {
+            let missing = self
+                .pages
+                .keys()
+                .filter(|page_id| !seen.contains(page_id))
+                .copied()
+                .collect::<Vec<_>>();
+
+            for page_id in missing {
+                self.pages.remove(&page_id);
+                events.push(WatchEvent::Archived(page_id));
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// The state of the stream returned by [`Notion::watch_database`].
+struct Watch {
+    database_id: DatabaseId,
+    interval: Duration,
+    options: WatchOptions,
+    /// Whether the checkpoint was loaded.
+    loaded: bool,
+    /// `None` until the first poll.
+    snapshot: Option<Snapshot>,
+    /// Events of the last poll that weren't taken from the stream yet.
+    pending: VecDeque<WatchEvent>,
+    polls: u32,
+    /// Whether the snapshot changed since it was last saved.
+    unsaved: bool,
+}
+
+impl<T: HttpTransport> Notion<T> {
+    /// Polls a database every `interval`, producing an event for every page
+    /// that was created, updated or archived.
+    ///
+    /// The stream never ends. Errors are produced as items, and the next item
+    /// polls again.
+    ///
+    /// See the [module documentation](crate::watch) for how changes are
+    /// detected.
+    pub fn watch_database(
+        &self,
+        database_id: DatabaseId,
+        interval: Duration,
+        options: WatchOptions,
+    ) -> impl Stream<Item = Result<WatchEvent>> + '_ {
+        let watch = Watch {
+            database_id,
+            interval,
+            options,
+            loaded: false,
+            snapshot: None,
+            pending: VecDeque::new(),
+            polls: 0,
+            unsaved: false,
+        };
+
+        futures::stream::unfold(watch, move |mut watch| async move {
+            let result = self.next_event(&mut watch).await;
+            Some((result, watch))
+        })
+    }
+
+    async fn next_event(&self, watch: &mut Watch) -> Result<WatchEvent> {
+        loop {
+            if let Some(event) = watch.pending.pop_front() {
+                return Ok(event);
+            }
+
+            if watch.unsaved {
+                if let Some(snapshot) = &watch.snapshot {
+                    snapshot.save(&watch.options)?;
+                }
+                watch.unsaved = false;
+            }
+
+            if watch.polls > 0 {
+                tokio::time::sleep(watch.interval).await;
+            }
+
+            watch.polls = watch.polls.saturating_add(1);
+
+            let events = self.poll_database(watch).await?;
+            watch.pending.extend(events);
+        }
+    }
+
+    async fn poll_database(&self, watch: &mut Watch) -> Result<Vec<WatchEvent>> {
+        if !watch.loaded {
+            watch.snapshot = Snapshot::load(&watch.options)?;
+            watch.loaded = true;
+        }
+
+        let Some(snapshot) = &mut watch.snapshot else {
+            let pages = self
+                .query_database_all(watch.database_id, DatabaseQuery::new())
+                .await?;
+
+            let mut snapshot = Snapshot::default();
+            snapshot.apply(pages, true)?;
+
+            watch.snapshot = Some(snapshot);
+            watch.unsaved = true;
+
+            return Ok(Vec::new());
+        };
+
+        let full = snapshot.last_edited_time.is_none()
+            || (watch.options.full_every > 0 && watch.polls % watch.options.full_every == 0);
+
+        let filter = match (full, snapshot.last_edited_time) {
+            (false, Some(time)) => Some(serde_json::json!({
+                "timestamp": "last_edited_time",
+                "last_edited_time": { "on_or_after": time.to_rfc3339() },
+            })),
+            _ => None,
+        };
+
+        let query = DatabaseQuery::new()
+            .filter(filter)
+            .sorts(vec![Sort::Timestamp {
+                timestamp: SortTimestamp::LastEditedTime,
+                direction: SortDirection::Ascending,
+            }]);
+
+        let pages = match self.query_database_all(watch.database_id, query).await {
+            Ok(pages) => pages,
+            Err(e) => match e.downcast_ref::<NotionApiError>() {
+                // the database itself was archived
+                Some(NotionApiError::ObjectNotFound(_)) if full => Vec::new(),
+                _ => return Err(e),
+            },
+        };
+
+        let events = snapshot.apply(pages, full)?;
+        watch.unsaved |= !events.is_empty() || full;
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PAGE_1: &str = "1a2b3c4d-0000-4000-8000-000000000020";
+    const PAGE_2: &str = "1a2b3c4d-0000-4000-8000-000000000021";
+
+    fn page(id: &str, last_edited_time: &str, name: &str, price: f64) -> Page {
+        serde_json::from_value(json!({
+            "object": "page",
+            "id": id,
+            "last_edited_time": last_edited_time,
+            "archived": false,
+            "properties": {
+                "Name": { "id": "title", "type": "title", "title": [{
+                    "type": "text",
+                    "text": { "content": name, "link": null },
+                    "plain_text": name,
+                }] },
+                "Price": { "id": "a%3Bc", "type": "number", "number": price },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn events() {
+        let mut snapshot = Snapshot::default();
+
+        let pages = vec![page(PAGE_1, "2024-01-01T00:00:00.000Z", "Kale", 3.0)];
+        let events = snapshot.apply(pages, true).unwrap();
+        assert!(matches!(&events[..], [WatchEvent::Created(_)]));
+        assert_eq!(
+            snapshot.last_edited_time,
+            Some(DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap())
+        );
+
+        // returned again by an `on_or_after` filter, without changes
+        let pages = vec![page(PAGE_1, "2024-01-01T00:00:00.000Z", "Kale", 3.0)];
+        assert!(snapshot.apply(pages, false).unwrap().is_empty());
+
+        let pages = vec![
+            page(PAGE_1, "2024-01-02T00:00:00.000Z", "Kale", 4.0),
+            page(PAGE_2, "2024-01-02T00:00:00.000Z", "Bread", 1.5),
+        ];
+        let events = snapshot.apply(pages, false).unwrap();
+        assert!(matches!(
+            &events[..],
+            [WatchEvent::Updated { changed_properties, .. }, WatchEvent::Created(_)]
+                if changed_properties == &["Price"]
+        ));
+
+        // a change to the content only
+        let pages = vec![page(PAGE_2, "2024-01-03T00:00:00.000Z", "Bread", 1.5)];
+        let events = snapshot.apply(pages, false).unwrap();
+        assert!(matches!(
+            &events[..],
+            [WatchEvent::Updated { changed_properties, .. }] if changed_properties.is_empty()
+        ));
+
+        // edited again within the same minute, which only shows in the values
+        let pages = vec![page(PAGE_2, "2024-01-03T00:00:00.000Z", "Rye bread", 1.5)];
+        let events = snapshot.apply(pages, false).unwrap();
+        assert!(matches!(
+            &events[..],
+            [WatchEvent::Updated { changed_properties, .. }] if changed_properties == &["Name"]
+        ));
+
+        // an incremental poll can't notice archived pages
+        assert!(snapshot.apply(Vec::new(), false).unwrap().is_empty());
+
+        let pages = vec![page(PAGE_2, "2024-01-03T00:00:00.000Z", "Rye bread", 1.5)];
+        let events = snapshot.apply(pages, true).unwrap();
+        assert_eq!(events, [WatchEvent::Archived(PAGE_1.parse().unwrap())]);
+        assert_eq!(snapshot.pages.len(), 1);
+    }
+
+    #[test]
+    fn changed_properties() {
+        let before = PageSnapshot {
+            last_edited_time: None,
+            properties: BTreeMap::from([
+                ("Done".to_owned(), json!({ "checkbox": false })),
+                ("Name".to_owned(), json!({ "title": [] })),
+                ("Tags".to_owned(), json!({ "multi_select": [] })),
+            ]),
+        };
+        let after = PageSnapshot {
+            last_edited_time: None,
+            properties: BTreeMap::from([
+                ("Done".to_owned(), json!({ "checkbox": true })),
+                ("Name".to_owned(), json!({ "title": [] })),
+                ("Price".to_owned(), json!({ "number": 1 })),
+            ]),
+        };
+
+        assert_eq!(before.changed_properties(&after), ["Done", "Price", "Tags"]);
+    }
+
+    #[test]
+    fn checkpoint() {
+        let path = std::env::temp_dir().join(format!("notion-watch-{}.json", std::process::id()));
+        let options = WatchOptions::new().checkpoint(Some(path.clone()));
+
+        assert_eq!(Snapshot::load(&options).unwrap(), None);
+
+        let mut snapshot = Snapshot::default();
+        let pages = vec![page(PAGE_1, "2024-01-01T00:00:00.000Z", "Kale", 3.0)];
+        snapshot.apply(pages, true).unwrap();
+        snapshot.save(&options).unwrap();
+
+        assert_eq!(Snapshot::load(&options).unwrap(), Some(snapshot));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(
+            Snapshot::load(&options).unwrap_err().to_string(),
+            "could not read watch checkpoint"
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+//! # Webhooks
+//!
+//! Receiving the events of a webhook subscription, whose types are in
+//! [`notion_model::webhooks`].
+//!
+//! When a subscription is created, Notion sends a verification token to its
+//! URL, which has to be pasted in the integration's settings to start
+//! receiving events. Every event is then signed with that token, in the
+//! [`SIGNATURE_HEADER`] header.
+//!
+//! [`WebhookHandler`] checks signatures and parses deliveries. It doesn't
+//! depend on any HTTP server: pass it the headers and body of each request,
+//! or an [`http::Request`].
+//!
+//! ```no_run
+//! # fn run(headers: http::HeaderMap, body: Vec<u8>) -> anyhow::Result<()> {
+//! use notion::{
+//!     model::webhooks::{Delivery, EventData},
+//!     webhooks::WebhookHandler,
+//! };
+//!
+//! let handler = WebhookHandler::new().verification_token(Some("secret_token"));
+//!
+//! if let Delivery::Event(event) = handler.receive(&headers, &body)? {
+//!     if let EventData::PageContentUpdated(_) = event.data {
+//!         println!("page {} changed", event.entity.id);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use http::{HeaderMap, Method, Request, Response, StatusCode};
+use notion_model::webhooks::{Delivery, Event};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::errors::Error;
+
+/// The header with the signature of an event.
+pub const SIGNATURE_HEADER: &str = "x-notion-signature";
+
+/// The signature of a body, as Notion sends it in [`SIGNATURE_HEADER`]:
+/// `sha256=` and the hex HMAC-SHA256 of the body, keyed with the verification
+/// token.
+pub fn sign(verification_token: &str, body: &[u8]) -> String {
+    format!(
+        "sha256={}",
+        hex::encode(mac(verification_token, body).finalize().into_bytes())
+    )
+}
+
+/// Checks the signature of a body, in constant time.
+///
+/// # Errors
+///
+/// Returns [`Error::WebhookSignature`] if the signature doesn't match.
+pub fn verify(verification_token: &str, body: &[u8], signature: &str) -> Result<()> {
+    let signature = signature
+        .strip_prefix("sha256=")
+        .and_then(|signature| hex::decode(signature).ok())
+        .context(Error::WebhookSignature)?;
+
+    mac(verification_token, body)
+        .verify_slice(&signature)
+        .context(Error::WebhookSignature)
+}
+
+fn mac(verification_token: &str, body: &[u8]) -> Hmac<Sha256> {
+    // HMAC takes keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(verification_token.as_bytes())
+        .unwrap_or_else(|_| unreachable!());
+    mac.update(body);
+    mac
+}
+
+/// Checks and parses the requests to a webhook URL.
+#[derive(Debug, Default)]
+pub struct WebhookHandler {
+    verification_token: Mutex<Option<String>>,
+    capture_verification: bool,
+}
+
+impl WebhookHandler {
+    /// A handler without a verification token, which rejects every event.
+    ///
+    /// Set the token of an existing subscription with
+    /// [`WebhookHandler::verification_token`], or take the token of the first
+    /// verification request with [`WebhookHandler::capture_verification`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn verification_token(self, verification_token: Option<&str>) -> Self {
+        *self.token() = verification_token.map(str::to_owned);
+        self
+    }
+
+    /// Whether the token of a verification request is kept when the handler
+    /// doesn't have one yet. Verification requests aren't signed, so anyone
+    /// who can reach the URL first could choose the token: only enable this
+    /// while waiting for the verification request of a new subscription.
+    pub const fn capture_verification(mut self, capture_verification: bool) -> Self {
+        self.capture_verification = capture_verification;
+        self
+    }
+
+    /// The token that signatures are checked with.
+    pub fn current_verification_token(&self) -> Option<String> {
+        self.token().clone()
+    }
+
+    fn token(&self) -> MutexGuard<'_, Option<String>> {
+        self.verification_token
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Checks the signature of a request and parses its body.
+    ///
+    /// Verification requests aren't signed. Their token is only kept if the
+    /// handler doesn't have one yet, and
+    /// [`WebhookHandler::capture_verification`] is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WebhookSignature`] if an event isn't signed with the
+    /// verification token, or there is no token yet, and
+    /// [`Error::WebhookPayload`] if the body isn't a delivery.
+    pub fn receive(&self, headers: &HeaderMap, body: &[u8]) -> Result<Delivery> {
+        let value = serde_json::from_slice::<Value>(body).context(Error::WebhookPayload)?;
+
+        if let Some(verification_token) = value.get("verification_token").and_then(Value::as_str) {
+            if self.capture_verification {
+                self.token()
+                    .get_or_insert_with(|| verification_token.to_owned());
+            }
+
+            return Ok(Delivery::Verification {
+                verification_token: verification_token.to_owned(),
+            });
+        }
+
+        let verification_token = self
+            .current_verification_token()
+            .context(Error::WebhookSignature)?;
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|signature| signature.to_str().ok())
+            .context(Error::WebhookSignature)?;
+
+        verify(&verification_token, body, signature)?;
+
+        let event = serde_json::from_value::<Event>(value).context(Error::WebhookPayload)?;
+
+        Ok(Delivery::Event(Box::new(event)))
+    }
+
+    /// Handles a request, returning the response to send, and the delivery.
+    ///
+    /// The response is `200 OK` for deliveries, `401 Unauthorized` for bad
+    /// signatures, `400 Bad Request` for bad bodies, and
+    /// `405 Method Not Allowed` for requests other than `POST`. Notion retries
+    /// events that don't get a `200 OK`.
+    pub fn handle<B: AsRef<[u8]>>(
+        &self,
+        request: &Request<B>,
+    ) -> (Response<String>, Result<Delivery>) {
+        let result = if request.method() == Method::POST {
+            self.receive(request.headers(), request.body().as_ref())
+        } else {
+            Err(anyhow::anyhow!(
+                "{} request to the webhook URL",
+                request.method()
+            ))
+        };
+
+        let status = match &result {
+            Ok(_) => StatusCode::OK,
+            Err(_) if request.method() != Method::POST => StatusCode::METHOD_NOT_ALLOWED,
+            Err(e) => match e.downcast_ref::<Error>() {
+                Some(Error::WebhookSignature) => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::BAD_REQUEST,
+            },
+        };
+
+        let body = match &result {
+            Ok(_) => String::new(),
+            Err(e) => e.to_string(),
+        };
+
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+
+        (response, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const TOKEN: &str = "secret_tMrlL1qK5vuQAh1b6cZGhFChZTSYJlce98V0pYn7yBl";
+
+    fn event() -> Vec<u8> {
+        json!({
+            "id": "367cba44-b6f3-4c92-81e7-6a2e9659efd4",
+            "timestamp": "2024-12-05T23:55:34.285Z",
+            "workspace_id": "13950b26-c203-4f3b-b97d-93ec06319565",
+            "subscription_id": "29d75c0d-5546-4414-8459-7b7a92f1fc4b",
+            "integration_id": "0ef2e755-4912-8096-91c1-00376a88a5ca",
+            "type": "page.deleted",
+            "authors": [{ "id": "c7c11cca-1d73-471d-9b6e-bdef51470190", "type": "person" }],
+            "attempt_number": 2,
+            "entity": { "id": "153104cd-477e-809d-8dc4-ff2d96ae3090", "type": "page" },
+            "data": {
+                "parent": { "id": "13950b26-c203-4f3b-b97d-93ec06319565", "type": "space" },
+            },
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn request(body: Vec<u8>, signature: Option<&str>) -> Request<Vec<u8>> {
+        let mut request = Request::post("/webhooks");
+
+        if let Some(signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        request.body(body).unwrap()
+    }
+
+    #[test]
+    fn signature() {
+        let signature = sign(TOKEN, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+
+        verify(TOKEN, b"{}", &signature).unwrap();
+        assert!(verify(TOKEN, b"{ }", &signature).is_err());
+        assert!(verify("secret_other", b"{}", &signature).is_err());
+        assert!(verify(TOKEN, b"{}", &signature["sha256=".len()..]).is_err());
+        assert!(verify(TOKEN, b"{}", "sha256=zz").is_err());
+    }
+
+    #[test]
+    fn handshake() {
+        let handler = WebhookHandler::new().capture_verification(true);
+
+        // events are rejected until there is a token
+        let (response, _) = handler.handle(&request(event(), Some(&sign(TOKEN, &event()))));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let body = json!({ "verification_token": TOKEN })
+            .to_string()
+            .into_bytes();
+        let (response, delivery) = handler.handle(&request(body, None));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            delivery.unwrap(),
+            Delivery::Verification {
+                verification_token: TOKEN.to_owned()
+            }
+        );
+        assert_eq!(handler.current_verification_token().as_deref(), Some(TOKEN));
+
+        let (response, delivery) = handler.handle(&request(event(), Some(&sign(TOKEN, &event()))));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(delivery.unwrap(), Delivery::Event(event) if event.attempt_number == 2));
+
+        // a later verification request doesn't replace the token
+        let body = json!({ "verification_token": "secret_other" })
+            .to_string()
+            .into_bytes();
+        handler.handle(&request(body, None)).1.unwrap();
+        assert_eq!(handler.current_verification_token().as_deref(), Some(TOKEN));
+    }
+
+    #[test]
+    fn capture_is_opt_in() {
+        let body = json!({ "verification_token": TOKEN })
+            .to_string()
+            .into_bytes();
+
+        // the verification request is still delivered, for its token to be
+        // shown
+        let handler = WebhookHandler::new();
+        let (response, delivery) = handler.handle(&request(body.clone(), None));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(delivery.unwrap(), Delivery::Verification { .. }));
+        assert_eq!(handler.current_verification_token(), None);
+
+        let (response, _) = handler.handle(&request(event(), Some(&sign(TOKEN, &event()))));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // an unsigned body can't replace a token that was set
+        let handler = WebhookHandler::new()
+            .verification_token(Some("secret_configured"))
+            .capture_verification(true);
+        handler.handle(&request(body, None)).1.unwrap();
+        assert_eq!(
+            handler.current_verification_token().as_deref(),
+            Some("secret_configured")
+        );
+
+        let (response, _) = handler.handle(&request(event(), Some(&sign(TOKEN, &event()))));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn rejected() {
+        let handler = WebhookHandler::new().verification_token(Some(TOKEN));
+
+        let (response, _) = handler.handle(&request(event(), None));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let (response, _) =
+            handler.handle(&request(event(), Some(&sign("secret_other", &event()))));
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let body = b"not json".to_vec();
+        let (response, _) = handler.handle(&request(body.clone(), Some(&sign(TOKEN, &body))));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = br#"{"type": "page.created"}"#.to_vec();
+        let (response, result) = handler.handle(&request(body.clone(), Some(&sign(TOKEN, &body))));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "could not parse webhook payload"
+        );
+
+        let get = Request::get("/webhooks").body(Vec::new()).unwrap();
+        assert_eq!(
+            handler.handle(&get).0.status(),
+            StatusCode::METHOD_NOT_ALLOWED
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use notion::{
+    cache::CacheConfig,
+    client::Notion,
+    model::{ids::BlockId, objects::block::Block},
+};
+use notion_mock::{MockServer, API_TOKEN};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// A mock server with a page of two paragraphs, and a client with a cache.
+struct Workspace {
+    server: MockServer,
+    notion: Notion,
+    page: BlockId,
+    blocks: Vec<BlockId>,
+    /// Requests made to set up the workspace.
+    setup: usize,
+}
+
+impl Workspace {
+    /// Requests received since the workspace was set up.
+    fn requests(&self) -> usize {
+        self.server.request_count() - self.setup
+    }
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn paragraph(text: &str) -> Block {
+    from_json(json!({
+        "type": "paragraph",
+        "paragraph": {
+            "rich_text": [{ "type": "text", "text": { "content": text } }],
+            "color": "default",
+        },
+    }))
+}
+
+async fn start(config: CacheConfig) -> Workspace {
+    let server = MockServer::start().await.unwrap();
+    let notion = Notion::new(API_TOKEN).unwrap().base_url(&server.url());
+
+    let page = from_json(json!({
+        "parent": { "type": "workspace", "workspace": true },
+        "properties": {
+            "title": { "title": [{ "type": "text", "text": { "content": "Notes" } }] },
+        },
+    }));
+    let page = BlockId::from(
+        *notion
+            .create_page(page)
+            .await
+            .unwrap()
+            .id
+            .unwrap()
+            .into_inner(),
+    );
+
+    let blocks = notion
+        .append_block_children(page, vec![paragraph("hello"), paragraph("world")])
+        .await
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|block| block.id.unwrap())
+        .collect();
+
+    Workspace {
+        setup: server.request_count(),
+        notion: notion.cache(config),
+        server,
+        page,
+        blocks,
+    }
+}
+
+#[tokio::test]
+async fn ttl() {
+    let workspace = start(CacheConfig::new().block_ttl(Some(Duration::from_millis(200)))).await;
+    let (notion, block) = (&workspace.notion, workspace.blocks[0]);
+
+    notion.retrieve_block(block).await.unwrap();
+    notion.retrieve_block(block).await.unwrap();
+    assert_eq!(workspace.requests(), 1);
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    notion.retrieve_block(block).await.unwrap();
+    assert_eq!(workspace.requests(), 2);
+}
+
+#[tokio::test]
+async fn disabled_kind() {
+    let workspace = start(CacheConfig::new().block_ttl(None)).await;
+    let (notion, block) = (&workspace.notion, workspace.blocks[0]);
+
+    notion.retrieve_block(block).await.unwrap();
+    notion.retrieve_block(block).await.unwrap();
+
+    assert_eq!(workspace.requests(), 2);
+}
+
+#[tokio::test]
+async fn coalesces_concurrent_requests() {
+    let workspace = start(CacheConfig::new()).await;
+    let (notion, block) = (&workspace.notion, workspace.blocks[0]);
+
+    // slow enough for concurrent requests to overlap
+    workspace.server.latency(Some(Duration::from_millis(50)));
+
+    let blocks = futures::future::join_all((0..8).map(|_| notion.retrieve_block(block))).await;
+
+    assert!(blocks.iter().all(Result::is_ok));
+    assert_eq!(workspace.requests(), 1);
+}
+
+#[tokio::test]
+async fn max_entries() {
+    let workspace = start(CacheConfig::new().max_entries(1)).await;
+    let notion = &workspace.notion;
+    let (block, other) = (workspace.blocks[0], workspace.blocks[1]);
+
+    notion.retrieve_block(block).await.unwrap();
+    notion.retrieve_block(other).await.unwrap();
+    assert_eq!(workspace.requests(), 2);
+
+    // the first block was evicted to make room for the other one
+    notion.retrieve_block(block).await.unwrap();
+    assert_eq!(workspace.requests(), 3);
+}
+
+#[tokio::test]
+async fn update_invalidates_block_and_parent() {
+    let workspace = start(CacheConfig::new()).await;
+    let (notion, page, block) = (&workspace.notion, workspace.page, workspace.blocks[0]);
+
+    let retrieved = notion.retrieve_block(block).await.unwrap();
+    notion.retrieve_block_children(page, None).await.unwrap();
+
+    notion.update_block(retrieved).await.unwrap();
+    assert_eq!(workspace.requests(), 3);
+
+    notion.retrieve_block(block).await.unwrap();
+    notion.retrieve_block_children(page, None).await.unwrap();
+    assert_eq!(workspace.requests(), 5);
+
+    // clones share the cache
+    notion.clone().retrieve_block(block).await.unwrap();
+    assert_eq!(workspace.requests(), 5);
+
+    notion.clear_cache();
+    notion.retrieve_block(block).await.unwrap();
+    assert_eq!(workspace.requests(), 6);
+}
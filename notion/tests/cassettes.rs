@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use notion::{
+    cassette::{Cassette, Mode},
+    client::Notion,
+    errors::{Error, NotionApiError},
+    model::{
+        ids::{BlockId, PageId},
+        objects::{block::Block, comment::Comment, database::Database, page::Page},
+        query::DatabaseQuery,
+        search::SearchQuery,
+    },
+};
+use notion_mock::{MockServer, API_TOKEN};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// A cassette of every endpoint, recorded against notion-mock. It tests
+/// replaying, but its responses are only as close to the API as the mock is.
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes/workspace.json")
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn rich_text(text: &str) -> Value {
+    json!([{ "type": "text", "text": { "content": text } }])
+}
+
+/// One block of each kind that can be created.
+fn blocks() -> Vec<Block> {
+    let text = rich_text("Lorem ipsum");
+
+    [
+        json!({ "type": "paragraph", "paragraph": { "rich_text": text, "color": "default" } }),
+        json!({ "type": "heading_1", "heading_1": {
+            "rich_text": text, "color": "default", "is_toggleable": false,
+        } }),
+        json!({ "type": "heading_2", "heading_2": {
+            "rich_text": text, "color": "default", "is_toggleable": false,
+        } }),
+        json!({ "type": "heading_3", "heading_3": {
+            "rich_text": text, "color": "default", "is_toggleable": false,
+        } }),
+        json!({ "type": "bulleted_list_item", "bulleted_list_item": {
+            "rich_text": text, "color": "default",
+        } }),
+        json!({ "type": "numbered_list_item", "numbered_list_item": {
+            "rich_text": text, "color": "default",
+        } }),
+        json!({ "type": "to_do", "to_do": {
+            "rich_text": text, "checked": true, "color": "default",
+        } }),
+        json!({ "type": "toggle", "toggle": { "rich_text": text, "color": "default" } }),
+        json!({ "type": "quote", "quote": { "rich_text": text, "color": "default" } }),
+        json!({ "type": "code", "code": {
+            "rich_text": rich_text("fn main() {}"), "caption": [], "language": "rust",
+        } }),
+        json!({ "type": "divider", "divider": {} }),
+        json!({ "type": "bookmark", "bookmark": {
+            "url": "https://developers.notion.com", "caption": [],
+        } }),
+        json!({ "type": "table_of_contents", "table_of_contents": { "color": "default" } }),
+    ]
+    .into_iter()
+    .map(from_json)
+    .collect()
+}
+
+/// Creates and retrieves objects of every kind.
+async fn tour(notion: &Notion) -> Result<()> {
+    let bot = notion.retrieve_bot_user().await?;
+    notion.retrieve_user(bot.id).await?;
+    notion.list_users_all().await?;
+
+    let page = notion
+        .create_page(from_json(json!({
+            "parent": { "type": "workspace", "workspace": true },
+            "properties": { "title": { "title": rich_text("Tour") } },
+        })))
+        .await?;
+    let page_id = page.id.unwrap();
+    let block_id = BlockId::from(*page_id.into_inner());
+
+    notion.retrieve_page(page_id, None).await?;
+    notion.append_block_children(block_id, blocks()).await?;
+
+    for block in notion.retrieve_block_children_all(block_id).await? {
+        notion.retrieve_block(block.id.unwrap()).await?;
+    }
+
+    let database = notion
+        .create_database(from_json::<Database>(json!({
+            "parent": { "type": "page_id", "page_id": page_id },
+            "title": rich_text("Tasks"),
+            "description": [],
+            "properties": {
+                "Name": { "title": {} },
+                "Estimate": { "number": { "format": "number" } },
+                "Done": { "checkbox": {} },
+                "Notes": { "rich_text": {} },
+                "Link": { "url": {} },
+            },
+        })))
+        .await?;
+    let database_id = database.id.unwrap();
+
+    notion
+        .create_page(from_json::<Page>(json!({
+            "parent": { "type": "database_id", "database_id": database_id },
+            "properties": {
+                "Name": { "title": rich_text("Write tests") },
+                "Estimate": { "number": 2 },
+                "Done": { "checkbox": false },
+                "Notes": { "rich_text": rich_text("With cassettes") },
+                "Link": { "url": "https://developers.notion.com" },
+            },
+        })))
+        .await?;
+
+    notion.retrieve_database(database_id).await?;
+    notion
+        .query_database_all(database_id, DatabaseQuery::new())
+        .await?;
+
+    let comment = notion
+        .create_comment(
+            Comment::new()
+                .page(page_id)
+                .rich_text(from_json(rich_text("Looks good"))),
+        )
+        .await?;
+    notion
+        .create_comment(
+            Comment::new()
+                .discussion_id(comment.discussion_id)
+                .rich_text(from_json(rich_text("Thanks"))),
+        )
+        .await?;
+    notion.retrieve_comments_all(block_id).await?;
+
+    notion.search_all(SearchQuery::new()).await?;
+
+    // an error response
+    let missing = "1a2b3c4d-0000-4000-8000-0000000000ff".parse::<PageId>()?;
+    let e = notion.retrieve_page(missing, None).await.unwrap_err();
+    assert!(matches!(
+        e.downcast_ref::<NotionApiError>(),
+        Some(NotionApiError::ObjectNotFound(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replay() {
+    let cassette = Cassette::replay(fixture()).unwrap();
+    let notion = Notion::new("secret_replay").unwrap().cassette(cassette);
+
+    tour(&notion).await.unwrap();
+}
+
+#[tokio::test]
+async fn record_and_replay() {
+    let server = MockServer::start().await.unwrap();
+    let path = std::env::temp_dir().join(format!("notion-cassette-{}.json", std::process::id()));
+
+    let notion = Notion::new(API_TOKEN)
+        .unwrap()
+        .base_url(&server.url())
+        .cassette(Cassette::record(&path));
+    tour(&notion).await.unwrap();
+    let requests = server.request_count();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(!text.contains(API_TOKEN));
+    assert!(text.contains("Bearer [redacted]"));
+
+    // replaying sends nothing
+    let cassette = Cassette::replay(&path).unwrap();
+    assert_eq!(cassette.mode(), Mode::Replay);
+    assert_eq!(cassette.interactions().len(), requests);
+
+    let notion = Notion::new("secret_replay")
+        .unwrap()
+        .base_url(&server.url())
+        .cassette(cassette);
+    tour(&notion).await.unwrap();
+    assert_eq!(server.request_count(), requests);
+
+    // every interaction has been used up
+    let e = notion.retrieve_bot_user().await.unwrap_err();
+    assert_eq!(e.to_string(), Error::UnmatchedRequest.to_string());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+/// Records the fixture again, against notion-mock rather than the API, as
+/// the tour creates pages at the top level of the workspace, which only
+/// public integrations can do.
+#[tokio::test]
+#[ignore]
+async fn record_fixture() {
+    let server = MockServer::start().await.unwrap();
+
+    let notion = Notion::new(API_TOKEN)
+        .unwrap()
+        .base_url(&server.url())
+        .cassette(Cassette::record(fixture()));
+
+    tour(&notion).await.unwrap();
+}
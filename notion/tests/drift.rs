@@ -0,0 +1,98 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use notion::{
+    client::Notion,
+    drift::{Drift, DriftMode},
+    model::{ids::BlockId, objects::block::BlockData},
+    transport::{HeaderMap, HttpRequest, HttpResponse, HttpTransport, StatusCode},
+};
+use notion_mock::{MockServer, API_TOKEN};
+use serde_json::json;
+
+const BLOCK_ID: &str = "1a2b3c4d-0000-4000-8000-000000000040";
+
+/// Answers with a block that has fields the model doesn't know about.
+#[derive(Debug)]
+struct Drifted;
+
+impl HttpTransport for Drifted {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse> {
+        let body = json!({
+            "object": "block",
+            "id": BLOCK_ID,
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": [],
+                "color": "default",
+                "sparkles": true,
+            },
+            "in_trash": false,
+            "request_id": null,
+        });
+
+        Ok(HttpResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.to_string(),
+        })
+    }
+}
+
+fn block_id() -> BlockId {
+    BLOCK_ID.parse().unwrap()
+}
+
+#[tokio::test]
+async fn report() {
+    let reported = Arc::new(Mutex::new(Vec::<Drift>::new()));
+
+    let notion = Notion::with_transport("secret_fake", Drifted)
+        .unwrap()
+        .drift(DriftMode::report({
+            let reported = reported.clone();
+            move |drift| reported.lock().unwrap().push(drift.clone())
+        }));
+
+    let block = notion.retrieve_block(block_id()).await.unwrap();
+    assert!(matches!(block.data, BlockData::Paragraph(_)));
+
+    assert_eq!(
+        *reported.lock().unwrap(),
+        [Drift {
+            endpoint: "retrieve_block",
+            paths: vec!["$.in_trash".to_owned(), "$.paragraph.sparkles".to_owned()],
+        }]
+    );
+}
+
+#[tokio::test]
+async fn deny() {
+    let notion = Notion::with_transport("secret_fake", Drifted)
+        .unwrap()
+        .drift(DriftMode::Deny);
+
+    let e = notion.retrieve_block(block_id()).await.unwrap_err();
+    let drift = e.downcast_ref::<Drift>().unwrap();
+
+    assert_eq!(drift.paths, ["$.in_trash", "$.paragraph.sparkles"]);
+}
+
+#[tokio::test]
+async fn off() {
+    let notion = Notion::with_transport("secret_fake", Drifted).unwrap();
+
+    notion.retrieve_block(block_id()).await.unwrap();
+}
+
+#[tokio::test]
+async fn mock() {
+    let server = MockServer::start().await.unwrap();
+    let notion = Notion::new(API_TOKEN)
+        .unwrap()
+        .base_url(&server.url())
+        .drift(DriftMode::Deny);
+
+    notion.retrieve_bot_user().await.unwrap();
+    notion.list_users_all().await.unwrap();
+}
@@ -0,0 +1,1137 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use notion::{
+    batch::{BatchOperation, BatchOptions, BatchOutput},
+    client::Notion,
+    duplicate::DuplicateOptions,
+    errors::NotionApiError,
+    metrics::MemoryMetrics,
+    model::{
+        ids::{BlockId, DatabaseId, PageId, UserId},
+        objects::{
+            block::{Block, BlockData},
+            comment::Comment,
+            database::Database,
+            file_and_emoji::{Cover, FileOrEmoji},
+            page::Page,
+            parent::ParentData,
+            user::User,
+        },
+        query::{DatabaseQuery, Sort, SortDirection},
+        search::{PageOrDatabase, SearchFilter, SearchObject, SearchQuery},
+    },
+    resolve::AnyObject,
+    watch::{WatchEvent, WatchOptions},
+};
+use notion_mock::{Fault, MockServer, API_TOKEN};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+async fn start() -> (MockServer, Notion) {
+    let server = MockServer::start().await.unwrap();
+    let notion = Notion::new(API_TOKEN).unwrap().base_url(&server.url());
+
+    (server, notion)
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn api_error(e: &anyhow::Error) -> &NotionApiError {
+    e.downcast_ref::<NotionApiError>()
+        .unwrap_or_else(|| panic!("not an API error: {e:?}"))
+}
+
+fn rich_text(text: &str) -> Value {
+    json!([{ "type": "text", "text": { "content": text } }])
+}
+
+fn paragraph(text: &str) -> Block {
+    from_json(json!({
+        "type": "paragraph",
+        "paragraph": { "rich_text": rich_text(text), "color": "default" },
+    }))
+}
+
+fn text_of(block: &Block) -> String {
+    let value = serde_json::to_value(&block.data).unwrap();
+
+    value["paragraph"]["rich_text"][0]["text"]["content"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn title_of(page: &Page) -> String {
+    let value = serde_json::to_value(&page.properties).unwrap();
+
+    value["Name"]["title"][0]["text"]["content"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+async fn create_page(notion: &Notion, title: &str) -> Page {
+    let page = from_json(json!({
+        "parent": { "type": "workspace", "workspace": true },
+        "properties": { "title": { "title": rich_text(title) } },
+    }));
+
+    notion.create_page(page).await.unwrap()
+}
+
+async fn create_database(notion: &Notion, parent: PageId) -> Database {
+    let database = from_json(json!({
+        "parent": { "type": "page_id", "page_id": parent },
+        "title": rich_text("Groceries"),
+        "description": [],
+        "properties": {
+            "Name": { "title": {} },
+            "Price": { "number": { "format": "dollar" } },
+            "Done": { "checkbox": {} },
+        },
+    }));
+
+    notion.create_database(database).await.unwrap()
+}
+
+async fn create_row(notion: &Notion, database: DatabaseId, name: &str, price: f64) -> Page {
+    let page = from_json(json!({
+        "parent": { "type": "database_id", "database_id": database },
+        "properties": {
+            "Name": { "title": rich_text(name) },
+            "Price": { "number": price },
+            "Done": { "checkbox": price > 2.0 },
+        },
+    }));
+
+    notion.create_page(page).await.unwrap()
+}
+
+#[tokio::test]
+async fn pages() {
+    let (_server, notion) = start().await;
+
+    let page = create_page(&notion, "Recipes").await;
+    let page_id = page.id.unwrap();
+    assert_eq!(page.archived, Some(false));
+    assert!(page.url.is_some());
+
+    let retrieved = notion.retrieve_page(page_id, None).await.unwrap();
+    assert_eq!(retrieved.id, Some(page_id));
+    assert_eq!(retrieved.properties, page.properties);
+
+    let filtered = notion
+        .retrieve_page(page_id, Some(vec!["Nothing"]))
+        .await
+        .unwrap();
+    assert!(filtered.properties.is_empty());
+
+    let archived = notion
+        .update_page(Page::new().id(Some(page_id)).archived(Some(true)))
+        .await
+        .unwrap();
+    assert_eq!(archived.archived, Some(true));
+
+    // an archived page can be retrieved, but not edited
+    let retrieved = notion.retrieve_page(page_id, None).await.unwrap();
+    assert_eq!(retrieved.archived, Some(true));
+
+    let e = notion
+        .append_block_children(BlockId::from(*page_id.into_inner()), vec![paragraph("hi")])
+        .await
+        .unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ValidationError(_)));
+
+    let restored = notion
+        .update_page(Page::new().id(Some(page_id)).archived(Some(false)))
+        .await
+        .unwrap();
+    assert_eq!(restored.archived, Some(false));
+}
+
+#[tokio::test]
+async fn databases() {
+    let (_server, notion) = start().await;
+
+    let parent = create_page(&notion, "Home").await.id.unwrap();
+    let database = create_database(&notion, parent).await;
+    let database_id = database.id.unwrap();
+
+    let retrieved = notion.retrieve_database(database_id).await.unwrap();
+    assert_eq!(retrieved.id, Some(database_id));
+    assert_eq!(retrieved.properties.len(), 3);
+
+    for (name, price) in [("Kale", 3.0), ("Bread", 1.5), ("Cheese", 6.0)] {
+        create_row(&notion, database_id, name, price).await;
+    }
+
+    let query = DatabaseQuery::new()
+        .filter(Some(
+            json!({ "property": "Price", "number": { "greater_than": 2 } }),
+        ))
+        .sorts(vec![Sort::Property {
+            property: "Price".to_owned(),
+            direction: SortDirection::Descending,
+        }]);
+    let list = notion.query_database(database_id, query).await.unwrap();
+    let names = list.results.iter().map(title_of).collect::<Vec<_>>();
+    assert_eq!(names, ["Cheese", "Kale"]);
+
+    let query = DatabaseQuery::new().page_size(Some(1));
+    let first = notion
+        .query_database(database_id, query.clone())
+        .await
+        .unwrap();
+    assert_eq!(first.results.len(), 1);
+    assert!(first.has_more);
+
+    let all = notion.query_database_all(database_id, query).await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    // a property that isn't in the schema
+    let page = from_json(json!({
+        "parent": { "type": "database_id", "database_id": database_id },
+        "properties": { "Color": { "rich_text": rich_text("green") } },
+    }));
+    let e = notion.create_page(page).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ValidationError(_)));
+
+    // a filter on the wrong property type
+    let query = DatabaseQuery::new().filter(Some(
+        json!({ "property": "Name", "number": { "equals": 1 } }),
+    ));
+    let e = notion.query_database(database_id, query).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ValidationError(_)));
+}
+
+#[tokio::test]
+async fn icons_and_covers() {
+    let (_server, notion) = start().await;
+
+    let page = from_json::<Page>(json!({
+        "parent": { "type": "workspace", "workspace": true },
+        "properties": { "title": { "title": rich_text("Report") } },
+    }))
+    .icon(Some(FileOrEmoji::emoji("📈")))
+    .cover(Some(Cover::external("https://example.com/cover.png")));
+    let page = notion.create_page(page).await.unwrap();
+    let page_id = page.id.unwrap();
+    assert_eq!(page.icon, Some(FileOrEmoji::emoji("📈")));
+
+    let icon = FileOrEmoji::external("https://example.com/icon.png");
+    let updated = notion
+        .update_page(Page::new().id(Some(page_id)).icon(Some(icon.clone())))
+        .await
+        .unwrap();
+    assert_eq!(updated.icon, Some(icon));
+    assert_eq!(
+        updated.cover,
+        Some(Cover::external("https://example.com/cover.png"))
+    );
+
+    let database_id = create_database(&notion, page_id).await.id.unwrap();
+
+    let cover = Cover::external("https://example.com/banner.png");
+    let updated = notion
+        .update_database(
+            Database::new()
+                .id(Some(database_id))
+                .icon(Some(FileOrEmoji::emoji("🥬")))
+                .cover(Some(cover.clone())),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.icon, Some(FileOrEmoji::emoji("🥬")));
+    assert_eq!(updated.cover, Some(cover));
+    assert_eq!(updated.properties.len(), 3);
+
+    let retrieved = notion.retrieve_database(database_id).await.unwrap();
+    assert_eq!(retrieved.icon, updated.icon);
+}
+
+#[tokio::test]
+async fn blocks() {
+    let (_server, notion) = start().await;
+
+    let page_id = BlockId::from(*create_page(&notion, "Notes").await.id.unwrap().into_inner());
+
+    let mut toggle = from_json::<Block>(json!({
+        "type": "toggle",
+        "toggle": {
+            "rich_text": rich_text("More"),
+            "color": "default",
+            "children": [{
+                "type": "paragraph",
+                "paragraph": { "rich_text": rich_text("Nested"), "color": "default" },
+            }],
+        },
+    }));
+    toggle.id = None;
+
+    let appended = notion
+        .append_block_children(page_id, vec![paragraph("First"), toggle])
+        .await
+        .unwrap();
+    assert_eq!(appended.results.len(), 2);
+
+    let first = &appended.results[0];
+    let first_id = first.id.unwrap();
+    let toggle = &appended.results[1];
+    assert_eq!(toggle.has_children, Some(true));
+
+    let nested = notion
+        .retrieve_block_children_all(toggle.id.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(nested.iter().map(text_of).collect::<Vec<_>>(), ["Nested"]);
+
+    let retrieved = notion.retrieve_block(first_id).await.unwrap();
+    assert_eq!(text_of(&retrieved), "First");
+
+    let mut updated = paragraph("Updated");
+    updated.id = Some(first_id);
+    let updated = notion.update_block(updated).await.unwrap();
+    assert_eq!(text_of(&updated), "Updated");
+
+    // more children than fit on a page of results
+    for batch in [0..60, 60..120] {
+        let children = batch.map(|i| paragraph(&i.to_string())).collect();
+        notion
+            .append_block_children(page_id, children)
+            .await
+            .unwrap();
+    }
+
+    let list = notion.retrieve_block_children(page_id, None).await.unwrap();
+    assert_eq!(list.results.len(), 100);
+    assert!(list.has_more);
+
+    let all = notion.retrieve_block_children_all(page_id).await.unwrap();
+    assert_eq!(all.len(), 122);
+    assert_eq!(text_of(&all[121]), "119");
+
+    let deleted = notion.delete_block(first_id).await.unwrap();
+    assert_eq!(deleted.archived, Some(true));
+
+    // deleted blocks can be retrieved, but are left out of their parent
+    let retrieved = notion.retrieve_block(first_id).await.unwrap();
+    assert_eq!(retrieved.archived, Some(true));
+
+    let all = notion.retrieve_block_children_all(page_id).await.unwrap();
+    assert_eq!(all.len(), 121);
+
+    // pages are blocks too
+    let page_block = notion.retrieve_block(page_id).await.unwrap();
+    assert!(matches!(page_block.data, BlockData::ChildPage(_)));
+}
+
+#[tokio::test]
+async fn sync() {
+    let (_server, notion) = start().await;
+
+    let page_id = BlockId::from(
+        *create_page(&notion, "Status")
+            .await
+            .id
+            .unwrap()
+            .into_inner(),
+    );
+
+    let texts = |blocks: &[Block]| blocks.iter().map(text_of).collect::<Vec<_>>();
+
+    notion
+        .sync_block_children(page_id, vec![paragraph("Up"), paragraph("Footer")])
+        .await
+        .unwrap();
+    let before = notion.retrieve_block_children_all(page_id).await.unwrap();
+    assert_eq!(texts(&before), ["Up", "Footer"]);
+
+    let plan = notion
+        .sync_block_children(
+            page_id,
+            vec![
+                paragraph("Down"),
+                paragraph("Since 10:00"),
+                paragraph("Footer"),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(plan.operations.len(), 2);
+
+    let after = notion.retrieve_block_children_all(page_id).await.unwrap();
+    assert_eq!(texts(&after), ["Down", "Since 10:00", "Footer"]);
+
+    // the updated and unchanged blocks are kept
+    assert_eq!(after[0].id, before[0].id);
+    assert_eq!(after[2].id, before[1].id);
+
+    let plan = notion
+        .sync_block_children(page_id, vec![paragraph("Down"), paragraph("Footer")])
+        .await
+        .unwrap();
+    assert_eq!(plan.operations.len(), 1);
+
+    let after = notion.retrieve_block_children_all(page_id).await.unwrap();
+    assert_eq!(texts(&after), ["Down", "Footer"]);
+
+    assert!(notion
+        .sync_block_children(page_id, vec![paragraph("Down"), paragraph("Footer")])
+        .await
+        .unwrap()
+        .is_empty());
+
+    // children deeper than can be appended at once
+    let toggle = |children: Vec<Block>| {
+        from_json::<Block>(json!({
+            "type": "toggle",
+            "toggle": {
+                "rich_text": rich_text("More"),
+                "color": "default",
+                "children": children,
+            },
+        }))
+    };
+    let deep = toggle(vec![toggle(vec![toggle(vec![paragraph("Deep")])])]);
+
+    notion
+        .sync_block_children(page_id, vec![paragraph("Down"), deep.clone()])
+        .await
+        .unwrap();
+
+    let tree = notion.retrieve_block_tree(page_id).await.unwrap();
+    assert_eq!(tree.len(), 2);
+    assert_eq!(
+        text_of(&tree[1].children[0].children[0].children[0].block),
+        "Deep"
+    );
+
+    assert!(notion
+        .sync_block_children(page_id, vec![paragraph("Down"), deep])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn templates() {
+    let (_server, notion) = start().await;
+
+    let template_page = create_page(&notion, "Incident: {{title}}").await;
+    let template_id = template_page.id.unwrap();
+
+    notion
+        .append_block_children(
+            BlockId::from(*template_id.into_inner()),
+            vec![
+                paragraph("Owner: {{owner}}"),
+                paragraph("{{#each steps}}"),
+                paragraph("{{@index}}: {{this}}"),
+                paragraph("{{/each}}"),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let template = notion.retrieve_template(template_id).await.unwrap();
+    assert_eq!(template.children.len(), 4);
+
+    let page = notion
+        .create_page_from_template(
+            from_json(json!({ "type": "workspace", "workspace": true })),
+            &template,
+            &json!({ "title": "API outage", "owner": "Ada", "steps": ["Page", "Roll back"] }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&page.properties).unwrap()["title"]["title"][0]["text"]["content"],
+        "Incident: API outage"
+    );
+
+    let content = notion
+        .retrieve_block_children_all(BlockId::from(*page.id.unwrap().into_inner()))
+        .await
+        .unwrap();
+    assert_eq!(
+        content.iter().map(text_of).collect::<Vec<_>>(),
+        ["Owner: Ada", "0: Page", "1: Roll back"]
+    );
+
+    let error = notion
+        .create_page_from_template(
+            from_json(json!({ "type": "workspace", "workspace": true })),
+            &template,
+            &json!({ "title": "API outage" }),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "no value for `{{owner}}`");
+}
+
+#[tokio::test]
+async fn watch() {
+    let (_server, notion) = start().await;
+
+    let parent = create_page(&notion, "Home").await.id.unwrap();
+    let database_id = create_database(&notion, parent).await.id.unwrap();
+    let kale = create_row(&notion, database_id, "Kale", 3.0)
+        .await
+        .id
+        .unwrap();
+
+    let checkpoint = std::env::temp_dir().join(format!("notion-watch-{}.json", std::process::id()));
+    let options = WatchOptions::new()
+        .checkpoint(Some(checkpoint.clone()))
+        .full_every(2);
+
+    let events = notion.watch_database(database_id, Duration::from_millis(10), options.clone());
+    futures::pin_mut!(events);
+
+    // the first poll only takes a snapshot of the existing pages
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err()
+    );
+
+    let bread = create_row(&notion, database_id, "Bread", 1.5)
+        .await
+        .id
+        .unwrap();
+    let event = events.next().await.unwrap().unwrap();
+    assert!(matches!(event, WatchEvent::Created(page) if page.id == Some(bread)));
+
+    let update = from_json(json!({ "id": kale, "properties": { "Price": { "number": 4 } } }));
+    notion.update_page(update).await.unwrap();
+    let event = events.next().await.unwrap().unwrap();
+    assert!(matches!(
+        event,
+        WatchEvent::Updated { page, changed_properties }
+            if page.id == Some(kale) && changed_properties == ["Price"]
+    ));
+
+    notion
+        .update_page(Page::new().id(Some(bread)).archived(Some(true)))
+        .await
+        .unwrap();
+    let event = events.next().await.unwrap().unwrap();
+    assert_eq!(event, WatchEvent::Archived(bread));
+
+    // a new watch resumes from the checkpoint, without replaying events
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), events.next())
+            .await
+            .is_err()
+    );
+
+    let events = notion.watch_database(database_id, Duration::from_millis(10), options);
+    futures::pin_mut!(events);
+
+    let update = from_json(json!({ "id": kale, "properties": { "Done": { "checkbox": false } } }));
+    notion.update_page(update).await.unwrap();
+    let event = events.next().await.unwrap().unwrap();
+    assert!(matches!(
+        event,
+        WatchEvent::Updated { changed_properties, .. } if changed_properties == ["Done"]
+    ));
+
+    std::fs::remove_file(checkpoint).unwrap();
+}
+
+#[tokio::test]
+async fn batch() {
+    let (server, notion) = start().await;
+    let notion = notion.rate_limit(Some(50));
+
+    let parent = create_page(&notion, "Home").await.id.unwrap();
+    let database_id = create_database(&notion, parent).await.id.unwrap();
+    let kale = create_row(&notion, database_id, "Kale", 3.0)
+        .await
+        .id
+        .unwrap();
+    let bread = create_row(&notion, database_id, "Bread", 1.5)
+        .await
+        .id
+        .unwrap();
+
+    let row = |name: &str| {
+        BatchOperation::CreatePage(from_json(json!({
+            "parent": { "type": "database_id", "database_id": database_id },
+            "properties": { "Name": { "title": rich_text(name) } },
+        })))
+    };
+    let operations = vec![
+        row("Cheese"),
+        BatchOperation::UpdatePage(from_json(json!({
+            "id": kale,
+            "properties": { "Done": { "checkbox": false } },
+        }))),
+        BatchOperation::AppendBlocks {
+            parent: BlockId::from(*parent.into_inner()),
+            children: (0..150).map(|i| paragraph(&i.to_string())).collect(),
+        },
+        BatchOperation::ArchivePage(bread),
+        // a property that isn't in the schema
+        BatchOperation::UpdatePage(from_json(json!({
+            "id": kale,
+            "properties": { "Color": { "rich_text": rich_text("green") } },
+        }))),
+    ];
+
+    let state = std::env::temp_dir().join(format!("notion-batch-{}.jsonl", std::process::id()));
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let options = BatchOptions::new()
+        .concurrency(2)
+        .state(Some(state.clone()));
+
+    let report = notion
+        .run_batch(
+            operations.clone(),
+            options.clone().on_progress({
+                let progress = progress.clone();
+                move |p| progress.lock().unwrap().push(p.done)
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(report.succeeded.len(), 4);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].index, 4);
+    assert!(matches!(
+        report.failed[0].api_error(),
+        Some(NotionApiError::ValidationError(_))
+    ));
+    assert_eq!(*progress.lock().unwrap(), [1, 2, 3, 4, 5]);
+
+    let appended = report
+        .succeeded
+        .iter()
+        .find(|success| success.index == 2)
+        .unwrap();
+    assert!(matches!(&appended.output, BatchOutput::Blocks(blocks) if blocks.len() == 150));
+
+    let archived = notion.retrieve_page(bread, None).await.unwrap();
+    assert_eq!(archived.archived, Some(true));
+
+    // resuming only runs the operation that failed
+    let requests = server.request_count();
+    let report = notion.run_batch(operations, options.clone()).await.unwrap();
+    assert_eq!(report.skipped, 4);
+    assert!(report.succeeded.is_empty());
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(server.request_count(), requests + 1);
+
+    let rows = notion
+        .query_database_all(database_id, DatabaseQuery::new())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+
+    // appending resumes after the blocks that were appended
+    let log = create_page(&notion, "Log").await.id.unwrap();
+    let log_block = BlockId::from(*log.into_inner());
+
+    let mut children = (0..150)
+        .map(|i| paragraph(&i.to_string()))
+        .collect::<Vec<_>>();
+    // a block that can't be created, in the second request
+    children[120] = from_json(json!({ "type": "transcription", "transcription": {} }));
+    let operations = vec![BatchOperation::AppendBlocks {
+        parent: log_block,
+        children,
+    }];
+
+    // the state of other operations
+    let e = notion
+        .run_batch(operations.clone(), options.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(e.to_string(), "batch state is of other operations");
+    std::fs::remove_file(&state).unwrap();
+
+    for _ in 0..2 {
+        let report = notion
+            .run_batch(operations.clone(), options.clone())
+            .await
+            .unwrap();
+        assert_eq!(report.failed.len(), 1);
+
+        let children = notion.retrieve_block_children_all(log_block).await.unwrap();
+        assert_eq!(children.len(), 100);
+    }
+
+    std::fs::remove_file(state).unwrap();
+}
+
+#[tokio::test]
+async fn duplicate() {
+    let (server, notion) = start().await;
+
+    let root = create_page(&notion, "Project").await.id.unwrap();
+    let root_block = BlockId::from(*root.into_inner());
+
+    notion
+        .append_block_children(root_block, vec![paragraph("Intro")])
+        .await
+        .unwrap();
+
+    let notes = notion
+        .create_page(from_json(json!({
+            "parent": { "type": "page_id", "page_id": root },
+            "properties": { "title": { "title": rich_text("Notes") } },
+        })))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let notes_block = BlockId::from(*notes.into_inner());
+
+    let mention = from_json(json!({
+        "type": "paragraph",
+        "paragraph": { "rich_text": [
+            { "type": "mention", "mention": { "type": "page", "page": { "id": notes } } },
+        ], "color": "default" },
+    }));
+    notion
+        .append_block_children(root_block, vec![mention])
+        .await
+        .unwrap();
+
+    let link = from_json(json!({
+        "type": "paragraph",
+        "paragraph": { "rich_text": [{
+            "type": "text",
+            "text": {
+                "content": "Back",
+                "link": { "url": format!("/{}", root.into_inner().simple()) },
+            },
+        }], "color": "default" },
+    }));
+    notion
+        .append_block_children(notes_block, vec![link])
+        .await
+        .unwrap();
+
+    let database_id = create_database(&notion, root).await.id.unwrap();
+
+    // a status property, which the API can't create, and more properties than
+    // `retrieve_page` returns
+    server.add_database_property(
+        database_id,
+        "Stage",
+        from_json(json!({ "status": { "options": [
+            { "id": "a1", "name": "Doing", "color": "blue" },
+            { "id": "a2", "name": "Done", "color": "green" },
+        ], "groups": [] } })),
+    );
+    for i in 0..30 {
+        server.add_database_property(
+            database_id,
+            &format!("Note {i}"),
+            from_json(json!({ "rich_text": {} })),
+        );
+    }
+
+    create_row(&notion, database_id, "Kale", 3.0).await;
+    let bread = create_row(&notion, database_id, "Bread", 1.5)
+        .await
+        .id
+        .unwrap();
+
+    let mut properties = (0..30)
+        .map(|i| {
+            (
+                format!("Note {i}"),
+                json!({ "rich_text": rich_text(&i.to_string()) }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+    properties.insert("Stage".to_owned(), json!({ "status": { "name": "Doing" } }));
+    notion
+        .update_page(from_json(json!({ "id": bread, "properties": properties })))
+        .await
+        .unwrap();
+
+    let workspace = from_json::<ParentData>(json!({ "type": "workspace", "workspace": true }));
+    let duplicate = notion
+        .duplicate_page(
+            root,
+            workspace,
+            DuplicateOptions::new().child_databases(true),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(duplicate.pages.len(), 4);
+    assert_eq!(duplicate.pages[&root], duplicate.page_id);
+    assert_eq!(duplicate.databases.len(), 1);
+
+    let copy = notion.retrieve_page(duplicate.page_id, None).await.unwrap();
+    assert_eq!(
+        serde_json::to_value(&copy.properties).unwrap()["title"]["title"][0]["text"]["content"],
+        "Project"
+    );
+
+    let content = notion
+        .retrieve_block_children_all(BlockId::from(*duplicate.page_id.into_inner()))
+        .await
+        .unwrap();
+    let notes_copy = duplicate.pages[&notes];
+    let database_copy = duplicate.databases[&database_id];
+
+    assert_eq!(content.len(), 4);
+    assert_eq!(text_of(&content[0]), "Intro");
+    assert_eq!(
+        content[1].id.map(|id| *id.into_inner()),
+        Some(*notes_copy.into_inner())
+    );
+    assert!(matches!(content[1].data, BlockData::ChildPage(_)));
+    assert_eq!(
+        serde_json::to_value(&content[2].data).unwrap()["paragraph"]["rich_text"][0]["mention"]
+            ["page"]["id"],
+        notes_copy.to_string()
+    );
+    assert_eq!(
+        content[3].id.map(|id| *id.into_inner()),
+        Some(*database_copy.into_inner())
+    );
+
+    let notes_content = notion
+        .retrieve_block_children_all(BlockId::from(*notes_copy.into_inner()))
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&notes_content[0].data).unwrap()["paragraph"]["rich_text"][0]["text"]
+            ["link"]["url"],
+        format!("/{}", duplicate.page_id.into_inner().simple())
+    );
+
+    let rows = notion
+        .query_database_all(database_copy, DatabaseQuery::new())
+        .await
+        .unwrap();
+    let mut names = rows.iter().map(title_of).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["Bread", "Kale"]);
+
+    // the status property is copied as a select, with its values
+    let schema = notion.retrieve_database(database_copy).await.unwrap();
+    assert_eq!(
+        serde_json::to_value(&schema.properties["Stage"]).unwrap()["select"]["options"][0]["name"],
+        "Doing"
+    );
+
+    let bread_copy = serde_json::to_value(duplicate.pages[&bread]).unwrap();
+    let bread_copy = rows
+        .iter()
+        .find(|row| serde_json::to_value(row.id).unwrap() == bread_copy)
+        .unwrap();
+    let properties = serde_json::to_value(&bread_copy.properties).unwrap();
+    assert_eq!(properties["Stage"]["select"]["name"], "Doing");
+    for i in 0..30 {
+        assert_eq!(
+            properties[format!("Note {i}")]["rich_text"][0]["text"]["content"],
+            i.to_string()
+        );
+    }
+
+    // child databases are left out by default
+    let duplicate = notion
+        .duplicate_page(root, workspace, DuplicateOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(duplicate.pages.len(), 2);
+    assert!(duplicate.databases.is_empty());
+
+    let content = notion
+        .retrieve_block_children_all(BlockId::from(*duplicate.page_id.into_inner()))
+        .await
+        .unwrap();
+    assert_eq!(content.len(), 3);
+}
+
+#[tokio::test]
+async fn users() {
+    let (server, notion) = start().await;
+
+    let person = from_json::<User>(json!({
+        "object": "user",
+        "id": "1a2b3c4d-0000-4000-8000-000000000030",
+        "name": "Ada",
+        "type": "person",
+        "person": { "email": "ada@example.com" },
+    }));
+    server.add_user(person.clone());
+
+    let bot = notion.retrieve_bot_user().await.unwrap();
+    assert_eq!(bot.id, server.bot_user().id);
+
+    let list = notion.list_users(None).await.unwrap();
+    assert_eq!(list.results.len(), 2);
+
+    let all = notion.list_users_all().await.unwrap();
+    assert!(all.iter().any(|user| user.id == person.id));
+
+    let retrieved = notion.retrieve_user(person.id).await.unwrap();
+    assert_eq!(retrieved.name.as_deref(), Some("Ada"));
+
+    let unknown = "1a2b3c4d-0000-4000-8000-0000000000ff"
+        .parse::<UserId>()
+        .unwrap();
+    let e = notion.retrieve_user(unknown).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ObjectNotFound(_)));
+}
+
+#[tokio::test]
+async fn search() {
+    let (_server, notion) = start().await;
+
+    let parent = create_page(&notion, "Home").await.id.unwrap();
+    create_database(&notion, parent).await;
+    create_page(&notion, "Grocery list").await;
+
+    let all = notion.search_all(SearchQuery::new()).await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    let list = notion
+        .search(SearchQuery::new().query(Some("grocer".to_owned())))
+        .await
+        .unwrap();
+    assert_eq!(list.results.len(), 2);
+
+    let databases = notion
+        .search(SearchQuery::new().filter(Some(SearchFilter::Object {
+            value: SearchObject::Database,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(databases.results.len(), 1);
+    assert!(matches!(databases.results[0], PageOrDatabase::Database(_)));
+
+    let pages = notion
+        .search_all(
+            SearchQuery::new()
+                .query(Some("grocer".to_owned()))
+                .filter(Some(SearchFilter::Object {
+                    value: SearchObject::Page,
+                })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(pages.len(), 1);
+}
+
+#[tokio::test]
+async fn comments() {
+    let (_server, notion) = start().await;
+
+    let page_id = create_page(&notion, "Plans").await.id.unwrap();
+
+    let comment = notion
+        .create_comment(
+            Comment::new()
+                .page(page_id)
+                .rich_text(from_json(rich_text("First"))),
+        )
+        .await
+        .unwrap();
+    let discussion_id = comment.discussion_id;
+    assert!(discussion_id.is_some());
+
+    let reply = notion
+        .create_comment(
+            Comment::new()
+                .discussion_id(discussion_id)
+                .rich_text(from_json(rich_text("Reply"))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(reply.discussion_id, discussion_id);
+
+    let block_id = BlockId::from(*page_id.into_inner());
+
+    let list = notion.retrieve_comments(block_id, None).await.unwrap();
+    assert_eq!(list.results.len(), 2);
+
+    let all = notion.retrieve_comments_all(block_id).await.unwrap();
+    assert_eq!(all[0].id, comment.id);
+    assert_eq!(all[1].id, reply.id);
+}
+
+#[tokio::test]
+async fn errors() {
+    let (server, notion) = start().await;
+
+    let unknown = "1a2b3c4d-0000-4000-8000-0000000000ff"
+        .parse::<PageId>()
+        .unwrap();
+    let e = notion.retrieve_page(unknown, None).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ObjectNotFound(_)));
+
+    let intruder = Notion::new("secret_wrong").unwrap().base_url(&server.url());
+    let e = intruder.retrieve_bot_user().await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::Unauthorized(_)));
+
+    // a database needs a title property
+    let parent = create_page(&notion, "Home").await.id.unwrap();
+    let database = from_json(json!({
+        "parent": { "type": "page_id", "page_id": parent },
+        "title": rich_text("Untitled"),
+        "description": [],
+        "properties": { "Price": { "number": {} } },
+    }));
+    let e = notion.create_database(database).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ValidationError(_)));
+}
+
+#[tokio::test]
+async fn faults() {
+    let (server, notion) = start().await;
+
+    server.inject(Fault::RateLimited, 1);
+    server.inject(Fault::InternalServerError, 1);
+    server.inject(Fault::ServiceUnavailable, 1);
+
+    let e = notion.retrieve_bot_user().await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::RateLimited(_)));
+
+    let e = notion.retrieve_bot_user().await.unwrap_err();
+    assert!(matches!(
+        api_error(&e),
+        NotionApiError::InternalServerError(_)
+    ));
+
+    let e = notion.retrieve_bot_user().await.unwrap_err();
+    assert!(matches!(
+        api_error(&e),
+        NotionApiError::ServiceUnavailable(_)
+    ));
+
+    // the faults are used up
+    notion.retrieve_bot_user().await.unwrap();
+    assert_eq!(server.request_count(), 4);
+
+    server.latency(Some(Duration::from_millis(200)));
+    let start = Instant::now();
+    notion.retrieve_bot_user().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+
+    server.latency(None);
+    let start = Instant::now();
+    notion.retrieve_bot_user().await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn retries() {
+    let (server, notion) = start().await;
+
+    let metrics = Arc::new(MemoryMetrics::new());
+    let notion = notion.retries(2).metrics(metrics.clone());
+
+    server.inject(Fault::InternalServerError, 1);
+    server.inject(Fault::RateLimited, 1);
+
+    // a 500 ms backoff, then the 1 s `Retry-After` of the mock
+    let start = Instant::now();
+    notion.retrieve_bot_user().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(1500));
+    assert_eq!(server.request_count(), 3);
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.requests[&("GET users/me".to_owned(), 200)], 1);
+    assert_eq!(snapshot.retries, 2);
+    assert_eq!(snapshot.rate_limited, 1);
+    assert_eq!(snapshot.latency["GET users/me"].count, 1);
+
+    // the last response is returned once the retries are used up
+    server.inject(Fault::ServiceUnavailable, 3);
+    let e = notion.retrieve_bot_user().await.unwrap_err();
+    assert!(matches!(
+        api_error(&e),
+        NotionApiError::ServiceUnavailable(_)
+    ));
+    assert_eq!(
+        metrics.snapshot().requests[&("GET users/me".to_owned(), 503)],
+        1
+    );
+
+    // other errors aren't retried
+    server.inject(Fault::Conflict, 1);
+    let page_id = PageId::from_str_unchecked("1a2b3c4d-0000-4000-8000-000000000001");
+    notion.retrieve_page(page_id, None).await.unwrap_err();
+    assert_eq!(
+        metrics.snapshot().requests[&("GET pages/{id}".to_owned(), 409)],
+        1
+    );
+
+    // a page may have been created despite a server error, so it isn't
+    // created again
+    server.inject(Fault::InternalServerError, 1);
+    let page = from_json(json!({
+        "parent": { "type": "workspace", "workspace": true },
+        "properties": { "title": { "title": rich_text("Home") } },
+    }));
+    let e = notion.create_page(page).await.unwrap_err();
+    assert!(matches!(
+        api_error(&e),
+        NotionApiError::InternalServerError(_)
+    ));
+    assert_eq!(metrics.snapshot().retries, 4);
+}
+
+#[tokio::test]
+async fn resolve() {
+    let (server, notion) = start().await;
+
+    let page_id = create_page(&notion, "Home").await.id.unwrap();
+    let database_id = create_database(&notion, page_id).await.id.unwrap();
+    let row_id = create_row(&notion, database_id, "Kale", 3.0)
+        .await
+        .id
+        .unwrap();
+    let block_id = notion
+        .append_block_children(BlockId::from(*page_id.into_inner()), vec![paragraph("hi")])
+        .await
+        .unwrap()
+        .results[0]
+        .id
+        .unwrap();
+    let user_id = server.bot_user().id;
+
+    let cases = [
+        (*page_id.into_inner(), "page"),
+        (*row_id.into_inner(), "page"),
+        (*database_id.into_inner(), "database"),
+        (*block_id.into_inner(), "block"),
+        (*user_id.into_inner(), "user"),
+    ];
+
+    for (id, kind) in cases {
+        assert_eq!(notion.resolve(id).await.unwrap().kind(), kind, "{id}");
+    }
+
+    match notion.resolve(*database_id.into_inner()).await.unwrap() {
+        AnyObject::Database(database) => assert_eq!(database.id, Some(database_id)),
+        object => panic!("resolved to a {}", object.kind()),
+    }
+
+    let unknown = "1a2b3c4d-0000-4000-8000-0000000000ff".parse().unwrap();
+    let e = notion.resolve(unknown).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::ObjectNotFound(_)));
+
+    // errors other than misses aren't retried on other endpoints
+    server.inject(Fault::RateLimited, 1);
+    let requests = server.request_count();
+    let e = notion.resolve(unknown).await.unwrap_err();
+    assert!(matches!(api_error(&e), NotionApiError::RateLimited(_)));
+    assert_eq!(server.request_count(), requests + 1);
+}
@@ -0,0 +1,110 @@
+use notion::{
+    authentication::OAuthClient, client::Notion, errors::NotionApiError,
+    model::objects::user::BotOwner,
+};
+use notion_mock::MockServer;
+
+const REDIRECT_URI: &str = "http://localhost:8765/callback";
+
+async fn start() -> (MockServer, OAuthClient) {
+    let server = MockServer::start().await.unwrap();
+    server.add_oauth_client("client", "secret");
+
+    let client = oauth_client(&server, "secret");
+
+    (server, client)
+}
+
+fn oauth_client(server: &MockServer, client_secret: &str) -> OAuthClient {
+    OAuthClient::new("client", client_secret)
+        .unwrap()
+        .redirect_uri(REDIRECT_URI)
+        .base_url(&server.url())
+}
+
+#[tokio::test]
+async fn authorization_url() {
+    let client = OAuthClient::new("client", "secret")
+        .unwrap()
+        .redirect_uri(REDIRECT_URI);
+
+    assert_eq!(
+        client.authorization_url(Some("xyz")).unwrap(),
+        "https://api.notion.com/v1/oauth/authorize?client_id=client&response_type=code&owner=user&redirect_uri=http%3A%2F%2Flocalhost%3A8765%2Fcallback&state=xyz"
+    );
+}
+
+#[tokio::test]
+async fn exchange_and_refresh() {
+    let (server, client) = start().await;
+
+    let code = server.authorize("client", Some(REDIRECT_URI));
+    let token = client.exchange_code(&code).await.unwrap();
+
+    assert_eq!(token.bot_id, server.bot_user().id);
+    assert_eq!(token.workspace_name.as_deref(), Some("Mock Workspace"));
+    assert_eq!(token.owner, BotOwner::Workspace { workspace: true });
+
+    // the access token works with the API
+    let notion = Notion::new(&token.access_token)
+        .unwrap()
+        .base_url(&server.url());
+    notion.retrieve_bot_user().await.unwrap();
+
+    let refreshed = client
+        .refresh_token(token.refresh_token.as_deref().unwrap())
+        .await
+        .unwrap();
+
+    assert_ne!(refreshed.access_token, token.access_token);
+}
+
+#[tokio::test]
+async fn invalid_code() {
+    let (server, client) = start().await;
+
+    let error = client.exchange_code("bad-code").await.unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<NotionApiError>(),
+        Some(NotionApiError::OAuth(message)) if message == "invalid_grant: Invalid code."
+    ));
+
+    // codes can only be exchanged once
+    let code = server.authorize("client", Some(REDIRECT_URI));
+    client.exchange_code(&code).await.unwrap();
+
+    assert!(client.exchange_code(&code).await.is_err());
+}
+
+#[tokio::test]
+async fn introspect_and_revoke() {
+    let (server, client) = start().await;
+
+    let code = server.authorize("client", Some(REDIRECT_URI));
+    let token = client.exchange_code(&code).await.unwrap();
+
+    let info = client.introspect_token(&token.access_token).await.unwrap();
+    assert!(info.active);
+    assert!(info.iat.is_some());
+
+    let error = oauth_client(&server, "wrong")
+        .revoke_token(&token.access_token)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<NotionApiError>(),
+        Some(NotionApiError::Unauthorized(_))
+    ));
+
+    client.revoke_token(&token.access_token).await.unwrap();
+
+    let info = client.introspect_token(&token.access_token).await.unwrap();
+    assert!(!info.active);
+
+    let notion = Notion::new(&token.access_token)
+        .unwrap()
+        .base_url(&server.url());
+    assert!(notion.retrieve_bot_user().await.is_err());
+}
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use notion::{
+    client::Notion,
+    model::{
+        ids::{BlockId, DatabaseId, PageId},
+        objects::{block::Block, database::Database, page::Page, user::User},
+    },
+    sync::{Mirror, SyncOptions, SyncReport},
+};
+use notion_mock::{MockServer, API_TOKEN};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+async fn start() -> (MockServer, Notion) {
+    let server = MockServer::start().await.unwrap();
+    let notion = Notion::new(API_TOKEN).unwrap().base_url(&server.url());
+
+    (server, notion)
+}
+
+fn from_json<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn rich_text(text: &str) -> Value {
+    json!([{ "type": "text", "text": { "content": text } }])
+}
+
+fn paragraph(text: &str) -> Block {
+    from_json(json!({
+        "type": "paragraph",
+        "paragraph": { "rich_text": rich_text(text), "color": "default" },
+    }))
+}
+
+async fn create_page(notion: &Notion, parent: Option<PageId>, title: &str) -> PageId {
+    let parent = match parent {
+        Some(page_id) => json!({ "type": "page_id", "page_id": page_id }),
+        None => json!({ "type": "workspace", "workspace": true }),
+    };
+
+    let page = from_json(json!({
+        "parent": parent,
+        "properties": { "title": { "title": rich_text(title) } },
+    }));
+
+    notion.create_page(page).await.unwrap().id.unwrap()
+}
+
+async fn create_database(notion: &Notion, parent: PageId) -> DatabaseId {
+    let database = from_json::<Database>(json!({
+        "parent": { "type": "page_id", "page_id": parent },
+        "title": rich_text("Tasks"),
+        "description": [],
+        "properties": { "Name": { "title": {} } },
+    }));
+
+    notion.create_database(database).await.unwrap().id.unwrap()
+}
+
+async fn create_row(notion: &Notion, database: DatabaseId, title: &str) -> PageId {
+    let page = from_json(json!({
+        "parent": { "type": "database_id", "database_id": database },
+        "properties": { "Name": { "title": rich_text(title) } },
+    }));
+
+    notion.create_page(page).await.unwrap().id.unwrap()
+}
+
+async fn rename_row(notion: &Notion, page_id: PageId, title: &str) {
+    let page = from_json::<Page>(json!({
+        "properties": { "Name": { "title": rich_text(title) } },
+    }))
+    .id(Some(page_id));
+
+    notion.update_page(page).await.unwrap();
+}
+
+async fn append(notion: &Notion, page_id: PageId, texts: &[&str]) -> Vec<BlockId> {
+    let children = texts.iter().map(|text| paragraph(text)).collect();
+
+    notion
+        .append_block_children(BlockId::from(*page_id.into_inner()), children)
+        .await
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|block| block.id.unwrap())
+        .collect()
+}
+
+/// A database of two rows, the first with a paragraph of content.
+async fn tasks(notion: &Notion) -> (DatabaseId, [PageId; 2]) {
+    let home = create_page(notion, None, "Home").await;
+    let database = create_database(notion, home).await;
+
+    let first = create_row(notion, database, "Write tests").await;
+    let second = create_row(notion, database, "Ship it").await;
+    append(notion, first, &["Unit tests"]).await;
+
+    (database, [first, second])
+}
+
+fn count(mirror: &Mirror, sql: &str) -> i64 {
+    mirror
+        .connection()
+        .query_row(sql, [], |row| row.get(0))
+        .unwrap()
+}
+
+fn title(mirror: &Mirror, id: PageId) -> String {
+    mirror
+        .connection()
+        .query_row(
+            "SELECT title FROM pages WHERE id = ?1",
+            [id.to_string()],
+            |row| row.get(0),
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn database_incremental() {
+    let (server, notion) = start().await;
+    let (database, [_, second]) = tasks(&notion).await;
+
+    server.add_user(from_json::<User>(json!({
+        "object": "user",
+        "id": "1a2b3c4d-0000-4000-8000-000000000030",
+        "name": "Avocado Lovelace",
+        "type": "person",
+        "person": { "email": "avo@example.org" },
+    })));
+
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let options = SyncOptions::new()
+        .databases(vec![database])
+        .content(true)
+        .users(true);
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(
+        report,
+        SyncReport {
+            pages_updated: 2,
+            pages_archived: 0,
+            blocks_updated: 1,
+            users_updated: 2,
+        }
+    );
+    assert_eq!(count(&mirror, "SELECT COUNT(*) FROM databases"), 1);
+    assert_eq!(count(&mirror, "SELECT COUNT(*) FROM pages"), 2);
+    assert_eq!(
+        count(
+            &mirror,
+            "SELECT COUNT(*) FROM properties WHERE value = 'Ship it'"
+        ),
+        1
+    );
+    assert_eq!(
+        count(
+            &mirror,
+            "SELECT COUNT(*) FROM users WHERE email = 'avo@example.org'"
+        ),
+        1
+    );
+
+    // nothing changed, so nothing is written
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 0);
+    assert_eq!(report.blocks_updated, 0);
+
+    rename_row(&notion, second, "Ship it now").await;
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 1);
+    assert_eq!(title(&mirror, second), "Ship it now");
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 0);
+}
+
+#[tokio::test]
+async fn database_full_archives_missing_pages() {
+    let (_server, notion) = start().await;
+    let (database, [first, _]) = tasks(&notion).await;
+
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let options = SyncOptions::new().databases(vec![database]);
+
+    notion.sync(&mut mirror, &options).await.unwrap();
+
+    notion
+        .update_page(Page::new().id(Some(first)).archived(Some(true)))
+        .await
+        .unwrap();
+
+    // an incremental sync can't see archived pages
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+    assert_eq!(report.pages_archived, 0);
+
+    let report = notion
+        .sync(&mut mirror, &options.clone().full(true))
+        .await
+        .unwrap();
+
+    assert_eq!(report.pages_archived, 1);
+    assert_eq!(report.pages_updated, 0);
+    assert_eq!(
+        count(&mirror, "SELECT COUNT(*) FROM pages WHERE archived = 1"),
+        1
+    );
+}
+
+#[tokio::test]
+async fn page_tree() {
+    let (_server, notion) = start().await;
+
+    let root = create_page(&notion, None, "Home").await;
+    let paragraphs = append(&notion, root, &["one", "two"]).await;
+    let child = create_page(&notion, Some(root), "Notes").await;
+    append(&notion, child, &["three"]).await;
+
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let options = SyncOptions::new().pages(vec![root]);
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 2);
+    assert_eq!(report.blocks_updated, 4);
+    assert_eq!(title(&mirror, child), "Notes");
+    assert_eq!(
+        count(
+            &mirror,
+            &format!("SELECT MAX(position) FROM blocks WHERE parent_id = '{root}'")
+        ),
+        2
+    );
+
+    // the root page no longer has its second paragraph
+    notion.delete_block(paragraphs[1]).await.unwrap();
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    // the link to the child page moved up
+    assert_eq!(report.pages_updated, 1);
+    assert_eq!(report.blocks_updated, 1);
+    assert_eq!(report.pages_archived, 0);
+    assert_eq!(
+        count(
+            &mirror,
+            &format!("SELECT COUNT(*) FROM blocks WHERE page_id = '{root}' AND archived = 0")
+        ),
+        2
+    );
+
+    // archived pages are left out of the content of their parent, and are
+    // retrieved once more to notice it
+    notion
+        .update_page(Page::new().id(Some(child)).archived(Some(true)))
+        .await
+        .unwrap();
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_archived, 1);
+    assert_eq!(
+        count(
+            &mirror,
+            &format!("SELECT archived FROM pages WHERE id = '{child}'")
+        ),
+        1
+    );
+}
+
+#[tokio::test]
+async fn same_minute() {
+    let (server, notion) = start().await;
+
+    // every edit gets the same last_edited_time, as edits in the same minute
+    // do in the real API
+    server.set_time(Some(
+        DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    ));
+
+    let (database, [first, second]) = tasks(&notion).await;
+    let root = create_page(&notion, None, "Home").await;
+    let paragraphs = append(&notion, root, &["one"]).await;
+
+    let mut mirror = Mirror::open_in_memory().unwrap();
+    let options = SyncOptions::new()
+        .databases(vec![database])
+        .pages(vec![root])
+        .content(true);
+
+    notion.sync(&mut mirror, &options).await.unwrap();
+
+    rename_row(&notion, second, "Ship it now").await;
+    append(&notion, first, &["Integration tests"]).await;
+
+    let mut updated = paragraph("uno");
+    updated.id = Some(paragraphs[0]);
+    notion.update_block(updated).await.unwrap();
+
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 1);
+    assert_eq!(report.blocks_updated, 2);
+    assert_eq!(title(&mirror, second), "Ship it now");
+    assert_eq!(
+        count(
+            &mirror,
+            &format!("SELECT COUNT(*) FROM blocks WHERE page_id = '{first}' AND archived = 0")
+        ),
+        2
+    );
+    assert_eq!(
+        count(
+            &mirror,
+            "SELECT COUNT(*) FROM blocks WHERE json LIKE '%\"uno\"%'"
+        ),
+        1
+    );
+
+    // and once nothing changed, nothing is written
+    let report = notion.sync(&mut mirror, &options).await.unwrap();
+
+    assert_eq!(report.pages_updated, 0);
+    assert_eq!(report.blocks_updated, 0);
+}
@@ -0,0 +1,149 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use anyhow::Result;
+use notion::{
+    client::Notion,
+    errors::NotionApiError,
+    model::ids::{PageId, UserId},
+    transport::{
+        HeaderMap, HttpRequest, HttpResponse, HttpTransport, Method, ReqwestTransport, StatusCode,
+    },
+};
+use notion_mock::{MockServer, API_TOKEN};
+use serde_json::json;
+
+const USER_ID: &str = "1a2b3c4d-0000-4000-8000-000000000030";
+
+/// Answers every request in-process, keeping the requests it got.
+#[derive(Debug, Default)]
+struct Fake {
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl HttpTransport for Fake {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let (status, body) = match request.url.as_str() {
+            "https://api.notion.com/v1/users/me" => (
+                StatusCode::OK,
+                json!({ "object": "user", "id": USER_ID, "type": "bot", "bot": {} }),
+            ),
+            _ => (
+                StatusCode::NOT_FOUND,
+                json!({
+                    "object": "error",
+                    "status": 404,
+                    "code": "object_not_found",
+                    "message": "Could not find object.",
+                }),
+            ),
+        };
+
+        self.requests.lock().unwrap().push(request);
+
+        Ok(HttpResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.to_string(),
+        })
+    }
+}
+
+/// Counts the requests sent through another transport.
+#[derive(Debug)]
+struct Counted {
+    inner: ReqwestTransport,
+    count: AtomicUsize,
+}
+
+impl HttpTransport for Counted {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        self.inner.send(request).await
+    }
+}
+
+#[tokio::test]
+async fn fake() {
+    let notion = Notion::with_transport("secret_fake", Fake::default()).unwrap();
+
+    let bot = notion.retrieve_bot_user().await.unwrap();
+    assert_eq!(bot.id, USER_ID.parse::<UserId>().unwrap());
+
+    let page_id = "1a2b3c4d-0000-4000-8000-000000000010"
+        .parse::<PageId>()
+        .unwrap();
+    let e = notion
+        .retrieve_page(page_id, Some(vec!["title", "a b"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        e.downcast_ref::<NotionApiError>(),
+        Some(NotionApiError::ObjectNotFound(_))
+    ));
+
+    let e = notion
+        .list_users(Some("next".to_owned()))
+        .await
+        .unwrap_err();
+    assert!(e.downcast_ref::<NotionApiError>().is_some());
+
+    let requests = notion.transport().requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 3);
+
+    let request = &requests[0];
+    assert_eq!(request.method, Method::GET);
+    assert_eq!(request.headers["authorization"], "Bearer secret_fake");
+    assert_eq!(request.headers["notion-version"], "2022-06-28");
+    assert_eq!(request.body, None);
+
+    assert_eq!(
+        requests[1].url,
+        format!(
+            "https://api.notion.com/v1/pages/{page_id}?filter_properties=title&filter_properties=a+b"
+        )
+    );
+    assert_eq!(
+        requests[2].url,
+        "https://api.notion.com/v1/users?start_cursor=next"
+    );
+}
+
+#[tokio::test]
+async fn json_body() {
+    let notion = Notion::with_transport("secret_fake", Fake::default()).unwrap();
+
+    let query = notion::model::search::SearchQuery::new().query(Some("kale".to_owned()));
+    notion.search(query).await.unwrap_err();
+
+    let requests = notion.transport().requests.lock().unwrap().clone();
+    let request = &requests[0];
+
+    assert_eq!(request.method, Method::POST);
+    assert_eq!(request.headers["content-type"], "application/json");
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(request.body.as_deref().unwrap()).unwrap(),
+        json!({ "query": "kale" })
+    );
+}
+
+#[tokio::test]
+async fn middleware() {
+    let server = MockServer::start().await.unwrap();
+
+    let transport = Counted {
+        inner: ReqwestTransport::new().unwrap(),
+        count: AtomicUsize::new(0),
+    };
+    let notion = Notion::with_transport(API_TOKEN, transport)
+        .unwrap()
+        .base_url(&server.url());
+
+    notion.retrieve_bot_user().await.unwrap();
+    notion.list_users_all().await.unwrap();
+
+    assert_eq!(notion.transport().count.load(Ordering::SeqCst), 2);
+    assert_eq!(server.request_count(), 2);
+}
@@ -0,0 +1,166 @@
+use std::{
+    io::{self, Read},
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use notion::{
+    client::Notion,
+    model::objects::file_upload::FileUploadStatus,
+    transport::{HeaderMap, HttpRequest, HttpResponse, HttpTransport, StatusCode},
+    uploads::{UploadSource, PART_SIZE},
+};
+use serde_json::{json, Value};
+
+const UPLOAD_ID: &str = "1a2b3c4d-0000-4000-8000-000000000041";
+
+/// Answers file upload requests, keeping the requests it got.
+#[derive(Debug, Default)]
+struct Fake {
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl HttpTransport for Fake {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let status = if request.url.ends_with("/file_uploads") {
+            "pending"
+        } else {
+            "uploaded"
+        };
+
+        self.requests.lock().unwrap().push(request);
+
+        Ok(HttpResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: json!({
+                "object": "file_upload",
+                "id": UPLOAD_ID,
+                "status": status,
+                "filename": "kale.txt",
+            })
+            .to_string(),
+        })
+    }
+}
+
+impl Fake {
+    /// The paths of the requests, after `/v1/`.
+    fn paths(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.url.replace("https://api.notion.com/v1/", ""))
+            .collect()
+    }
+
+    fn json_body(&self, i: usize) -> Value {
+        let requests = self.requests.lock().unwrap();
+        serde_json::from_slice(requests[i].body.as_ref().unwrap()).unwrap()
+    }
+
+    /// The `multipart/form-data` content type and body of a request.
+    fn form(&self, i: usize) -> (String, Vec<u8>) {
+        let requests = self.requests.lock().unwrap();
+        let request = &requests[i];
+
+        (
+            request.headers["content-type"].to_str().unwrap().to_owned(),
+            request.body.clone().unwrap(),
+        )
+    }
+}
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+#[tokio::test]
+async fn single_part() {
+    let notion = Notion::with_transport("secret_fake", Fake::default()).unwrap();
+
+    let upload = notion
+        .upload_file(
+            UploadSource::reader("kale.txt", 4, &b"kale"[..]),
+            "text/plain",
+        )
+        .await
+        .unwrap();
+    assert_eq!(upload.status, FileUploadStatus::Uploaded);
+
+    let fake = notion.transport();
+    assert_eq!(
+        fake.paths(),
+        ["file_uploads", &format!("file_uploads/{UPLOAD_ID}/send")]
+    );
+    assert_eq!(
+        fake.json_body(0),
+        json!({
+            "mode": "single_part",
+            "filename": "kale.txt",
+            "content_type": "text/plain",
+        })
+    );
+
+    let (content_type, body) = fake.form(1);
+    assert!(content_type.starts_with("multipart/form-data; boundary="));
+    assert!(contains(&body, "filename=\"kale.txt\""));
+    assert!(contains(&body, "\r\n\r\nkale\r\n"));
+    assert!(!contains(&body, "part_number"));
+}
+
+#[tokio::test]
+async fn multi_part() {
+    let notion = Notion::with_transport("secret_fake", Fake::default()).unwrap();
+
+    let len = 2 * PART_SIZE + 5;
+    notion
+        .upload_file(
+            UploadSource::reader("kale.bin", len, io::repeat(0).take(len)),
+            "application/octet-stream",
+        )
+        .await
+        .unwrap();
+
+    let fake = notion.transport();
+    let send = format!("file_uploads/{UPLOAD_ID}/send");
+    assert_eq!(
+        fake.paths(),
+        [
+            "file_uploads",
+            &send,
+            &send,
+            &send,
+            &format!("file_uploads/{UPLOAD_ID}/complete"),
+        ]
+    );
+    assert_eq!(fake.json_body(0)["mode"], "multi_part");
+    assert_eq!(fake.json_body(0)["number_of_parts"], 3);
+
+    for (i, part_number) in [(1, "1"), (2, "2"), (3, "3")] {
+        let (_, body) = fake.form(i);
+        assert!(contains(
+            &body,
+            &format!("name=\"part_number\"\r\n\r\n{part_number}\r\n")
+        ));
+    }
+
+    // The last part has what's left over.
+    assert!(fake.form(3).1.len() < 1024);
+}
+
+#[tokio::test]
+async fn short_reader() {
+    let notion = Notion::with_transport("secret_fake", Fake::default()).unwrap();
+
+    notion
+        .upload_file(
+            UploadSource::reader("kale.txt", 10, &b"kale"[..]),
+            "text/plain",
+        )
+        .await
+        .unwrap_err();
+}